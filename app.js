@@ -1604,6 +1604,10 @@ function simulateHand() {
     }, 50);
 }
 
+// Shoe/count state returned by play_single_game, so consecutive hands keep
+// dealing from the same shoe. Keyed by the settings that shape the shoe.
+let singleGameSession = null;
+
 async function simulateHandWasm() {
     // Clear console for fresh logs
     console.clear();
@@ -1720,8 +1724,7 @@ async function simulateHandWasm() {
                         seed: seed,
                         strategy: strategyPayload,
                         rules: {
-                            dealer_hits_soft_17: dealerSetting === '17',
-                            dealer_stands_on: dealerSetting,
+                            dealer_rule: dealerSetting === '17s' ? 'S17' : 'H17',
                             double_after_split: doubleAfterSplit,
                             allow_resplit: allowResplit,
                             resplit_aces: resplitAces,
@@ -1767,8 +1770,12 @@ async function simulateHandWasm() {
                     hardByCountKeys: Object.keys(strategyPayload.hardByCount || {}).slice(0, 5)
                 }, null, 2));
                 
-                // Call WASM function directly
-                wasmResult = playSingleGame(input);
+                // Call WASM function directly, continuing the previous shoe when the setup is unchanged:
+                // same decks, rules (including penetration) and counting system
+                const sessionKey = JSON.stringify([numDecks, input.rules, countingPayload]);
+                const session = singleGameSession?.key === sessionKey ? singleGameSession.state : undefined;
+                wasmResult = playSingleGame(input, session);
+                singleGameSession = wasmResult?.session ? { key: sessionKey, state: wasmResult.session } : null;
             } else {
                 console.error('play_single_game not found. Available functions:', availableFunctions);
                 console.error('Module contents:', wasmModule);
@@ -1948,8 +1955,7 @@ async function playSingleGameViaWorker() {
             seed: seed,
             strategy: strategyPayload,
             rules: {
-                dealer_hits_soft_17: dealerSetting === '17',
-                dealer_stands_on: dealerSetting,
+                dealer_rule: dealerSetting === '17s' ? 'S17' : 'H17',
                 double_after_split: doubleAfterSplit,
                 allow_resplit: allowResplit,
                 resplit_aces: resplitAces,
@@ -3412,8 +3418,7 @@ async function runWasmSpotCheckForOptimization(playerCards, dealerCard, action,
             seed: generateSeed(),
             strategy: baseStrategy,
             rules: {
-                dealer_hits_soft_17: document.getElementById('dealerStandsOn')?.value === '17',
-                dealer_stands_on: document.getElementById('dealerStandsOn')?.value || '17',
+                dealer_rule: document.getElementById('dealerStandsOn')?.value === '17s' ? 'S17' : 'H17',
                 double_after_split: document.getElementById('doubleAfterSplit')?.checked ?? true,
                 allow_resplit: document.getElementById('allowResplit')?.checked ?? true,
                 resplit_aces: document.getElementById('resplitAces')?.checked ?? false,
@@ -1,10 +1,50 @@
 /* tslint:disable */
 /* eslint-disable */
 export const memory: WebAssembly.Memory;
-export const play_single_game: (a: any) => [number, number, number];
+export const __wbg_jobregistry_free: (a: number, b: number) => void;
+export const __wbg_sessiontracker_free: (a: number, b: number) => void;
+export const __wbg_simulator_free: (a: number, b: number) => void;
+export const calculate_risk_of_ruin: (a: any) => [number, number, number];
+export const check_trainer_action: (a: any) => [number, number, number];
+export const compare_rules: (a: any) => [number, number, number];
+export const export_report: (a: any) => [number, number, number, number];
+export const generate_indices: (a: any) => [number, number, number];
+export const generate_surrender_indices: (a: any) => [number, number, number];
+export const jobregistry_advance: (a: number, b: number) => number;
+export const jobregistry_cancel: (a: number, b: number) => number;
+export const jobregistry_new: () => number;
+export const jobregistry_poll: (a: number, b: number) => [number, number, number];
+export const jobregistry_poll_all: (a: number) => [number, number, number];
+export const jobregistry_result: (a: number, b: number) => [number, number, number];
+export const jobregistry_start: (a: number, b: any) => [number, number, number];
+export const optimize_bet_ramp: (a: any) => [number, number, number];
+export const play_single_game: (a: any, b: any) => [number, number, number];
+export const run_bet_spread_sweep: (a: any) => [number, number, number];
+export const run_count_drill: (a: any) => [number, number, number];
+export const run_counting_sweep: (a: any) => [number, number, number];
+export const run_deck_count_sweep: (a: any) => [number, number, number];
+export const run_penetration_sweep: (a: any) => [number, number, number];
+export const run_rule_sweep: (a: any) => [number, number, number];
+export const run_self_test: (a: any) => [number, number, number];
 export const run_simulation: (a: any) => [number, number, number];
+export const run_simulation_async: (a: any, b: number, c: number) => any;
+export const run_simulation_columnar: (a: any) => [number, number, number];
+export const run_simulation_live: (a: any, b: any, c: number, d: number) => [number, number, number];
 export const run_simulation_with_progress: (a: any, b: any) => [number, number, number];
 export const run_spot_check: (a: any) => [number, number, number];
+export const run_trip: (a: any) => [number, number, number];
+export const sessiontracker_new: (a: any) => [number, number, number];
+export const sessiontracker_record_cards: (a: number, b: any) => [number, number];
+export const sessiontracker_record_hand: (a: number, b: any) => [number, number, number];
+export const sessiontracker_shuffle: (a: number) => void;
+export const sessiontracker_summary: (a: number) => [number, number, number];
+export const simulator_new: (a: any) => [number, number, number];
+export const simulator_run: (a: number, b: any) => [number, number, number];
+export const simulator_spot_check: (a: number, b: any) => [number, number, number];
+export const verify_report: (a: number, b: number) => [number, number, number];
+export const wasm_bindgen__convert__closures_____invoke__h306f4bd0f7c35ced: (a: number, b: number, c: any) => void;
+export const wasm_bindgen__closure__destroy__hb9aa826084de4b97: (a: number, b: number) => void;
+export const wasm_bindgen__convert__closures_____invoke__h16713329aacdf452: (a: number, b: number, c: any, d: any) => void;
 export const __wbindgen_malloc: (a: number, b: number) => number;
 export const __wbindgen_realloc: (a: number, b: number, c: number, d: number) => number;
 export const __wbindgen_exn_store: (a: number) => void;
@@ -90,8 +90,7 @@ function buildSimulationInput() {
         seed: generateSeed(),
         strategy: collectStrategyPayload(),
         rules: {
-            dealer_hits_soft_17: dealerSetting === '17',
-            dealer_stands_on: dealerSetting,
+            dealer_rule: dealerSetting === '17s' ? 'S17' : 'H17',
             double_after_split: doubleAfterSplit,
             allow_resplit: allowResplit,
             resplit_aces: resplitAces,
@@ -455,8 +454,7 @@ function startWasmSpotCheck() {
             seed: generateSeed(),
             strategy: baseStrategy,
             rules: {
-                dealer_hits_soft_17: document.getElementById('dealerStandsOn')?.value === '17',
-                dealer_stands_on: document.getElementById('dealerStandsOn')?.value || '17',
+                dealer_rule: document.getElementById('dealerStandsOn')?.value === '17s' ? 'S17' : 'H17',
                 double_after_split: document.getElementById('doubleAfterSplit')?.checked ?? true,
                 allow_resplit: document.getElementById('allowResplit')?.checked ?? true,
                 resplit_aces: document.getElementById('resplitAces')?.checked ?? false,
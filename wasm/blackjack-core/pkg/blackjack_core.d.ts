@@ -1,18 +1,170 @@
 /* tslint:disable */
 /* eslint-disable */
-export function run_spot_check(params: any): any;
-export function play_single_game(params: any): any;
-export function run_simulation(params: any): any;
+export function run_deck_count_sweep(params: any): any;
+/**
+ * Runs a simulation and returns a report document as JSON text: the input
+ * with defaults filled in, engine version, RNG, seed and full result.
+ */
+export function export_report(params: any): string;
+export function calculate_risk_of_ruin(params: any): any;
+export function generate_surrender_indices(params: any): any;
+/**
+ * Runs a simulation while publishing live counters into `live`, typically a
+ * `Float64Array` over a `SharedArrayBuffer` that the UI polls each frame.
+ * See `live.rs` for the slot layout; the buffer needs at least 6 slots.
+ */
+export function run_simulation_live(params: any, live: Float64Array, starting_bankroll?: number | null): any;
+export function check_trainer_action(params: any): any;
+export function run_rule_sweep(params: any): any;
+/**
+ * Same as `run_simulation_with_progress`, but plays `chunk_size` rounds at a
+ * time and yields to the event loop in between, so it can run on the main
+ * thread without freezing the page.
+ */
+export function run_simulation_async(params: any, progress_callback?: Function | null, chunk_size?: number | null): Promise<any>;
+export function run_self_test(params: any): any;
+/**
+ * Runs a simulation and returns `cellStats` and `countStats` as parallel
+ * typed arrays (`cellColumns`, `countColumns`) instead of nested objects.
+ * String columns are dictionary-encoded as `{ labels, codes }`.
+ */
+export function run_simulation_columnar(params: any): any;
 export function run_simulation_with_progress(params: any, progress_callback: Function): any;
+export function compare_rules(params: any): any;
+/**
+ * Re-runs a report from `export_report` and says whether it still reproduces.
+ */
+export function verify_report(report: string): any;
+export function run_trip(params: any): any;
+export function run_simulation(params: any): any;
+export function run_bet_spread_sweep(params: any): any;
+export function run_counting_sweep(params: any): any;
+/**
+ * Finds count indices for playing deviations under any rules and counting
+ * system, with a count-table block ready to load into the strategy.
+ */
+export function generate_indices(params: any): any;
+export function run_penetration_sweep(params: any): any;
+export function run_count_drill(params: any): any;
+export function optimize_bet_ramp(params: any): any;
+/**
+ * Plays one round. Pass the `session` returned by the previous call to keep
+ * dealing from the same shoe with the same running count; omit it to start
+ * from a freshly shuffled shoe.
+ */
+export function play_single_game(params: any, session: any): any;
+export function run_spot_check(params: any): any;
+/**
+ * Registry of simulations that share one worker. Call `advance` in a loop
+ * (yielding between calls as needed) and collect results by job id.
+ */
+export class JobRegistry {
+  free(): void;
+  [Symbol.dispose](): void;
+  constructor();
+  /**
+   * Status of one job, or `undefined` for an unknown id.
+   */
+  poll(id: number): any;
+  /**
+   * Queues a simulation and returns its job id.
+   */
+  start(params: any): number;
+  cancel(id: number): boolean;
+  /**
+   * Returns a finished job's result and forgets the job.
+   */
+  result(id: number): any;
+  /**
+   * Advances every running job by up to `rounds_per_job` rounds; returns
+   * true while any job is still running.
+   */
+  advance(rounds_per_job: number): boolean;
+  poll_all(): any;
+}
+/**
+ * Logs a real session hand by hand: keeps the count, checks each decision
+ * against the strategy and summarizes results and mistakes.
+ */
+export class SessionTracker {
+  free(): void;
+  [Symbol.dispose](): void;
+  /**
+   * Records a played hand and returns the decision check for it.
+   */
+  record_hand(hand: any): any;
+  /**
+   * Counts cards seen away from the player's hand, given as ranks.
+   */
+  record_cards(ranks: any): void;
+  constructor(params: any);
+  shuffle(): void;
+  summary(): any;
+}
+/**
+ * Holds a parsed strategy and base configuration so the UI can run many
+ * simulations and spot checks without re-sending the strategy JSON.
+ */
+export class Simulator {
+  free(): void;
+  [Symbol.dispose](): void;
+  spot_check(hand: any): any;
+  constructor(params: any);
+  /**
+   * Runs a simulation; `overrides` may change the seed, iterations, bet size,
+   * bet ramp or output detail for this call only.
+   */
+  run(overrides: any): any;
+}
 
 export type InitInput = RequestInfo | URL | Response | BufferSource | WebAssembly.Module;
 
 export interface InitOutput {
   readonly memory: WebAssembly.Memory;
-  readonly play_single_game: (a: any) => [number, number, number];
+  readonly __wbg_jobregistry_free: (a: number, b: number) => void;
+  readonly __wbg_sessiontracker_free: (a: number, b: number) => void;
+  readonly __wbg_simulator_free: (a: number, b: number) => void;
+  readonly calculate_risk_of_ruin: (a: any) => [number, number, number];
+  readonly check_trainer_action: (a: any) => [number, number, number];
+  readonly compare_rules: (a: any) => [number, number, number];
+  readonly export_report: (a: any) => [number, number, number, number];
+  readonly generate_indices: (a: any) => [number, number, number];
+  readonly generate_surrender_indices: (a: any) => [number, number, number];
+  readonly jobregistry_advance: (a: number, b: number) => number;
+  readonly jobregistry_cancel: (a: number, b: number) => number;
+  readonly jobregistry_new: () => number;
+  readonly jobregistry_poll: (a: number, b: number) => [number, number, number];
+  readonly jobregistry_poll_all: (a: number) => [number, number, number];
+  readonly jobregistry_result: (a: number, b: number) => [number, number, number];
+  readonly jobregistry_start: (a: number, b: any) => [number, number, number];
+  readonly optimize_bet_ramp: (a: any) => [number, number, number];
+  readonly play_single_game: (a: any, b: any) => [number, number, number];
+  readonly run_bet_spread_sweep: (a: any) => [number, number, number];
+  readonly run_count_drill: (a: any) => [number, number, number];
+  readonly run_counting_sweep: (a: any) => [number, number, number];
+  readonly run_deck_count_sweep: (a: any) => [number, number, number];
+  readonly run_penetration_sweep: (a: any) => [number, number, number];
+  readonly run_rule_sweep: (a: any) => [number, number, number];
+  readonly run_self_test: (a: any) => [number, number, number];
   readonly run_simulation: (a: any) => [number, number, number];
+  readonly run_simulation_async: (a: any, b: number, c: number) => any;
+  readonly run_simulation_columnar: (a: any) => [number, number, number];
+  readonly run_simulation_live: (a: any, b: any, c: number, d: number) => [number, number, number];
   readonly run_simulation_with_progress: (a: any, b: any) => [number, number, number];
   readonly run_spot_check: (a: any) => [number, number, number];
+  readonly run_trip: (a: any) => [number, number, number];
+  readonly sessiontracker_new: (a: any) => [number, number, number];
+  readonly sessiontracker_record_cards: (a: number, b: any) => [number, number];
+  readonly sessiontracker_record_hand: (a: number, b: any) => [number, number, number];
+  readonly sessiontracker_shuffle: (a: number) => void;
+  readonly sessiontracker_summary: (a: number) => [number, number, number];
+  readonly simulator_new: (a: any) => [number, number, number];
+  readonly simulator_run: (a: number, b: any) => [number, number, number];
+  readonly simulator_spot_check: (a: number, b: any) => [number, number, number];
+  readonly verify_report: (a: number, b: number) => [number, number, number];
+  readonly wasm_bindgen__convert__closures_____invoke__h306f4bd0f7c35ced: (a: number, b: number, c: any) => void;
+  readonly wasm_bindgen__closure__destroy__hb9aa826084de4b97: (a: number, b: number) => void;
+  readonly wasm_bindgen__convert__closures_____invoke__h16713329aacdf452: (a: number, b: number, c: any, d: any) => void;
   readonly __wbindgen_malloc: (a: number, b: number) => number;
   readonly __wbindgen_realloc: (a: number, b: number, c: number, d: number) => number;
   readonly __wbindgen_exn_store: (a: number) => void;
@@ -177,11 +177,99 @@ function handleError(f, args) {
     }
 }
 
+let cachedInt32ArrayMemory0 = null;
+
+function getInt32ArrayMemory0() {
+    if (cachedInt32ArrayMemory0 === null || cachedInt32ArrayMemory0.byteLength === 0) {
+        cachedInt32ArrayMemory0 = new Int32Array(wasm.memory.buffer);
+    }
+    return cachedInt32ArrayMemory0;
+}
+
+function getArrayI32FromWasm0(ptr, len) {
+    ptr = ptr >>> 0;
+    return getInt32ArrayMemory0().subarray(ptr / 4, ptr / 4 + len);
+}
+
+let cachedUint16ArrayMemory0 = null;
+
+function getUint16ArrayMemory0() {
+    if (cachedUint16ArrayMemory0 === null || cachedUint16ArrayMemory0.byteLength === 0) {
+        cachedUint16ArrayMemory0 = new Uint16Array(wasm.memory.buffer);
+    }
+    return cachedUint16ArrayMemory0;
+}
+
+function getArrayU16FromWasm0(ptr, len) {
+    ptr = ptr >>> 0;
+    return getUint16ArrayMemory0().subarray(ptr / 2, ptr / 2 + len);
+}
+
+let cachedUint32ArrayMemory0 = null;
+
+function getUint32ArrayMemory0() {
+    if (cachedUint32ArrayMemory0 === null || cachedUint32ArrayMemory0.byteLength === 0) {
+        cachedUint32ArrayMemory0 = new Uint32Array(wasm.memory.buffer);
+    }
+    return cachedUint32ArrayMemory0;
+}
+
+function getArrayU32FromWasm0(ptr, len) {
+    ptr = ptr >>> 0;
+    return getUint32ArrayMemory0().subarray(ptr / 4, ptr / 4 + len);
+}
+
+let cachedFloat64ArrayMemory0 = null;
+
+function getFloat64ArrayMemory0() {
+    if (cachedFloat64ArrayMemory0 === null || cachedFloat64ArrayMemory0.byteLength === 0) {
+        cachedFloat64ArrayMemory0 = new Float64Array(wasm.memory.buffer);
+    }
+    return cachedFloat64ArrayMemory0;
+}
+
+function getArrayF64FromWasm0(ptr, len) {
+    ptr = ptr >>> 0;
+    return getFloat64ArrayMemory0().subarray(ptr / 8, ptr / 8 + len);
+}
+
 function getArrayU8FromWasm0(ptr, len) {
     ptr = ptr >>> 0;
     return getUint8ArrayMemory0().subarray(ptr / 1, ptr / 1 + len);
 }
 
+const CLOSURE_DTORS = (typeof FinalizationRegistry === 'undefined')
+    ? { register: () => {}, unregister: () => {} }
+    : new FinalizationRegistry(state => state.dtor(state.a, state.b));
+
+function makeMutClosure(arg0, arg1, dtor, f) {
+    const state = { a: arg0, b: arg1, cnt: 1, dtor };
+    const real = (...args) => {
+
+        // First up with a closure we increment the internal reference
+        // count. This ensures that the Rust closure environment won't
+        // be deallocated while we're invoking it.
+        state.cnt++;
+        const a = state.a;
+        state.a = 0;
+        try {
+            return f(a, state.b, ...args);
+        } finally {
+            state.a = a;
+            real._wbg_cb_unref();
+        }
+    };
+    real._wbg_cb_unref = () => {
+        if (--state.cnt === 0) {
+            state.dtor(state.a, state.b);
+            state.a = 0;
+            CLOSURE_DTORS.unregister(state);
+        }
+    };
+    CLOSURE_DTORS.register(real, state, state);
+    return real;
+}
+
 function takeFromExternrefTable0(idx) {
     const value = wasm.__wbindgen_externrefs.get(idx);
     wasm.__externref_table_dealloc(idx);
@@ -191,8 +279,45 @@ function takeFromExternrefTable0(idx) {
  * @param {any} params
  * @returns {any}
  */
-export function run_spot_check(params) {
-    const ret = wasm.run_spot_check(params);
+export function run_deck_count_sweep(params) {
+    const ret = wasm.run_deck_count_sweep(params);
+    if (ret[2]) {
+        throw takeFromExternrefTable0(ret[1]);
+    }
+    return takeFromExternrefTable0(ret[0]);
+}
+
+/**
+ * Runs a simulation and returns a report document as JSON text: the input
+ * with defaults filled in, engine version, RNG, seed and full result.
+ * @param {any} params
+ * @returns {string}
+ */
+export function export_report(params) {
+    let deferred2_0;
+    let deferred2_1;
+    try {
+        const ret = wasm.export_report(params);
+        var ptr1 = ret[0];
+        var len1 = ret[1];
+        if (ret[3]) {
+            ptr1 = 0; len1 = 0;
+            throw takeFromExternrefTable0(ret[2]);
+        }
+        deferred2_0 = ptr1;
+        deferred2_1 = len1;
+        return getStringFromWasm0(ptr1, len1);
+    } finally {
+        wasm.__wbindgen_free(deferred2_0, deferred2_1, 1);
+    }
+}
+
+/**
+ * @param {any} params
+ * @returns {any}
+ */
+export function calculate_risk_of_ruin(params) {
+    const ret = wasm.calculate_risk_of_ruin(params);
     if (ret[2]) {
         throw takeFromExternrefTable0(ret[1]);
     }
@@ -203,8 +328,8 @@ export function run_spot_check(params) {
  * @param {any} params
  * @returns {any}
  */
-export function play_single_game(params) {
-    const ret = wasm.play_single_game(params);
+export function generate_surrender_indices(params) {
+    const ret = wasm.generate_surrender_indices(params);
     if (ret[2]) {
         throw takeFromExternrefTable0(ret[1]);
     }
@@ -212,11 +337,81 @@ export function play_single_game(params) {
 }
 
 /**
+ * Runs a simulation while publishing live counters into `live`, typically a
+ * `Float64Array` over a `SharedArrayBuffer` that the UI polls each frame.
+ * See `live.rs` for the slot layout; the buffer needs at least 6 slots.
  * @param {any} params
+ * @param {Float64Array} live
+ * @param {number | null} [starting_bankroll]
  * @returns {any}
  */
-export function run_simulation(params) {
-    const ret = wasm.run_simulation(params);
+export function run_simulation_live(params, live, starting_bankroll) {
+    const ret = wasm.run_simulation_live(params, live, !isLikeNone(starting_bankroll), isLikeNone(starting_bankroll) ? 0 : starting_bankroll);
+    if (ret[2]) {
+        throw takeFromExternrefTable0(ret[1]);
+    }
+    return takeFromExternrefTable0(ret[0]);
+}
+
+/**
+ * @param {any} params
+ * @returns {any}
+ */
+export function check_trainer_action(params) {
+    const ret = wasm.check_trainer_action(params);
+    if (ret[2]) {
+        throw takeFromExternrefTable0(ret[1]);
+    }
+    return takeFromExternrefTable0(ret[0]);
+}
+
+/**
+ * @param {any} params
+ * @returns {any}
+ */
+export function run_rule_sweep(params) {
+    const ret = wasm.run_rule_sweep(params);
+    if (ret[2]) {
+        throw takeFromExternrefTable0(ret[1]);
+    }
+    return takeFromExternrefTable0(ret[0]);
+}
+
+/**
+ * Same as `run_simulation_with_progress`, but plays `chunk_size` rounds at a
+ * time and yields to the event loop in between, so it can run on the main
+ * thread without freezing the page.
+ * @param {any} params
+ * @param {Function | null} [progress_callback]
+ * @param {number | null} [chunk_size]
+ * @returns {Promise<any>}
+ */
+export function run_simulation_async(params, progress_callback, chunk_size) {
+    const ret = wasm.run_simulation_async(params, isLikeNone(progress_callback) ? 0 : addToExternrefTable0(progress_callback), isLikeNone(chunk_size) ? 0x100000001 : (chunk_size) >>> 0);
+    return ret;
+}
+
+/**
+ * @param {any} params
+ * @returns {any}
+ */
+export function run_self_test(params) {
+    const ret = wasm.run_self_test(params);
+    if (ret[2]) {
+        throw takeFromExternrefTable0(ret[1]);
+    }
+    return takeFromExternrefTable0(ret[0]);
+}
+
+/**
+ * Runs a simulation and returns `cellStats` and `countStats` as parallel
+ * typed arrays (`cellColumns`, `countColumns`) instead of nested objects.
+ * String columns are dictionary-encoded as `{ labels, codes }`.
+ * @param {any} params
+ * @returns {any}
+ */
+export function run_simulation_columnar(params) {
+    const ret = wasm.run_simulation_columnar(params);
     if (ret[2]) {
         throw takeFromExternrefTable0(ret[1]);
     }
@@ -236,6 +431,389 @@ export function run_simulation_with_progress(params, progress_callback) {
     return takeFromExternrefTable0(ret[0]);
 }
 
+/**
+ * @param {any} params
+ * @returns {any}
+ */
+export function compare_rules(params) {
+    const ret = wasm.compare_rules(params);
+    if (ret[2]) {
+        throw takeFromExternrefTable0(ret[1]);
+    }
+    return takeFromExternrefTable0(ret[0]);
+}
+
+/**
+ * Re-runs a report from `export_report` and says whether it still reproduces.
+ * @param {string} report
+ * @returns {any}
+ */
+export function verify_report(report) {
+    const ptr0 = passStringToWasm0(report, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+    const len0 = WASM_VECTOR_LEN;
+    const ret = wasm.verify_report(ptr0, len0);
+    if (ret[2]) {
+        throw takeFromExternrefTable0(ret[1]);
+    }
+    return takeFromExternrefTable0(ret[0]);
+}
+
+/**
+ * @param {any} params
+ * @returns {any}
+ */
+export function run_trip(params) {
+    const ret = wasm.run_trip(params);
+    if (ret[2]) {
+        throw takeFromExternrefTable0(ret[1]);
+    }
+    return takeFromExternrefTable0(ret[0]);
+}
+
+/**
+ * @param {any} params
+ * @returns {any}
+ */
+export function run_simulation(params) {
+    const ret = wasm.run_simulation(params);
+    if (ret[2]) {
+        throw takeFromExternrefTable0(ret[1]);
+    }
+    return takeFromExternrefTable0(ret[0]);
+}
+
+/**
+ * @param {any} params
+ * @returns {any}
+ */
+export function run_bet_spread_sweep(params) {
+    const ret = wasm.run_bet_spread_sweep(params);
+    if (ret[2]) {
+        throw takeFromExternrefTable0(ret[1]);
+    }
+    return takeFromExternrefTable0(ret[0]);
+}
+
+/**
+ * @param {any} params
+ * @returns {any}
+ */
+export function run_counting_sweep(params) {
+    const ret = wasm.run_counting_sweep(params);
+    if (ret[2]) {
+        throw takeFromExternrefTable0(ret[1]);
+    }
+    return takeFromExternrefTable0(ret[0]);
+}
+
+/**
+ * Finds count indices for playing deviations under any rules and counting
+ * system, with a count-table block ready to load into the strategy.
+ * @param {any} params
+ * @returns {any}
+ */
+export function generate_indices(params) {
+    const ret = wasm.generate_indices(params);
+    if (ret[2]) {
+        throw takeFromExternrefTable0(ret[1]);
+    }
+    return takeFromExternrefTable0(ret[0]);
+}
+
+/**
+ * @param {any} params
+ * @returns {any}
+ */
+export function run_penetration_sweep(params) {
+    const ret = wasm.run_penetration_sweep(params);
+    if (ret[2]) {
+        throw takeFromExternrefTable0(ret[1]);
+    }
+    return takeFromExternrefTable0(ret[0]);
+}
+
+/**
+ * @param {any} params
+ * @returns {any}
+ */
+export function run_count_drill(params) {
+    const ret = wasm.run_count_drill(params);
+    if (ret[2]) {
+        throw takeFromExternrefTable0(ret[1]);
+    }
+    return takeFromExternrefTable0(ret[0]);
+}
+
+/**
+ * @param {any} params
+ * @returns {any}
+ */
+export function optimize_bet_ramp(params) {
+    const ret = wasm.optimize_bet_ramp(params);
+    if (ret[2]) {
+        throw takeFromExternrefTable0(ret[1]);
+    }
+    return takeFromExternrefTable0(ret[0]);
+}
+
+/**
+ * Plays one round. Pass the `session` returned by the previous call to keep
+ * dealing from the same shoe with the same running count; omit it to start
+ * from a freshly shuffled shoe.
+ * @param {any} params
+ * @param {any} session
+ * @returns {any}
+ */
+export function play_single_game(params, session) {
+    const ret = wasm.play_single_game(params, session);
+    if (ret[2]) {
+        throw takeFromExternrefTable0(ret[1]);
+    }
+    return takeFromExternrefTable0(ret[0]);
+}
+
+/**
+ * @param {any} params
+ * @returns {any}
+ */
+export function run_spot_check(params) {
+    const ret = wasm.run_spot_check(params);
+    if (ret[2]) {
+        throw takeFromExternrefTable0(ret[1]);
+    }
+    return takeFromExternrefTable0(ret[0]);
+}
+
+function wasm_bindgen__convert__closures_____invoke__h306f4bd0f7c35ced(arg0, arg1, arg2) {
+    wasm.wasm_bindgen__convert__closures_____invoke__h306f4bd0f7c35ced(arg0, arg1, arg2);
+}
+
+function wasm_bindgen__convert__closures_____invoke__h16713329aacdf452(arg0, arg1, arg2, arg3) {
+    wasm.wasm_bindgen__convert__closures_____invoke__h16713329aacdf452(arg0, arg1, arg2, arg3);
+}
+
+const JobRegistryFinalization = (typeof FinalizationRegistry === 'undefined')
+    ? { register: () => {}, unregister: () => {} }
+    : new FinalizationRegistry(ptr => wasm.__wbg_jobregistry_free(ptr >>> 0, 1));
+/**
+ * Registry of simulations that share one worker. Call `advance` in a loop
+ * (yielding between calls as needed) and collect results by job id.
+ */
+export class JobRegistry {
+
+    __destroy_into_raw() {
+        const ptr = this.__wbg_ptr;
+        this.__wbg_ptr = 0;
+        JobRegistryFinalization.unregister(this);
+        return ptr;
+    }
+
+    free() {
+        const ptr = this.__destroy_into_raw();
+        wasm.__wbg_jobregistry_free(ptr, 0);
+    }
+    constructor() {
+        const ret = wasm.jobregistry_new();
+        this.__wbg_ptr = ret >>> 0;
+        JobRegistryFinalization.register(this, this.__wbg_ptr, this);
+        return this;
+    }
+    /**
+     * Status of one job, or `undefined` for an unknown id.
+     * @param {number} id
+     * @returns {any}
+     */
+    poll(id) {
+        const ret = wasm.jobregistry_poll(this.__wbg_ptr, id);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        return takeFromExternrefTable0(ret[0]);
+    }
+    /**
+     * Queues a simulation and returns its job id.
+     * @param {any} params
+     * @returns {number}
+     */
+    start(params) {
+        const ret = wasm.jobregistry_start(this.__wbg_ptr, params);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        return ret[0] >>> 0;
+    }
+    /**
+     * @param {number} id
+     * @returns {boolean}
+     */
+    cancel(id) {
+        const ret = wasm.jobregistry_cancel(this.__wbg_ptr, id);
+        return ret !== 0;
+    }
+    /**
+     * Returns a finished job's result and forgets the job.
+     * @param {number} id
+     * @returns {any}
+     */
+    result(id) {
+        const ret = wasm.jobregistry_result(this.__wbg_ptr, id);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        return takeFromExternrefTable0(ret[0]);
+    }
+    /**
+     * Advances every running job by up to `rounds_per_job` rounds; returns
+     * true while any job is still running.
+     * @param {number} rounds_per_job
+     * @returns {boolean}
+     */
+    advance(rounds_per_job) {
+        const ret = wasm.jobregistry_advance(this.__wbg_ptr, rounds_per_job);
+        return ret !== 0;
+    }
+    /**
+     * @returns {any}
+     */
+    poll_all() {
+        const ret = wasm.jobregistry_poll_all(this.__wbg_ptr);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        return takeFromExternrefTable0(ret[0]);
+    }
+}
+if (Symbol.dispose) JobRegistry.prototype[Symbol.dispose] = JobRegistry.prototype.free;
+
+const SessionTrackerFinalization = (typeof FinalizationRegistry === 'undefined')
+    ? { register: () => {}, unregister: () => {} }
+    : new FinalizationRegistry(ptr => wasm.__wbg_sessiontracker_free(ptr >>> 0, 1));
+/**
+ * Logs a real session hand by hand: keeps the count, checks each decision
+ * against the strategy and summarizes results and mistakes.
+ */
+export class SessionTracker {
+
+    __destroy_into_raw() {
+        const ptr = this.__wbg_ptr;
+        this.__wbg_ptr = 0;
+        SessionTrackerFinalization.unregister(this);
+        return ptr;
+    }
+
+    free() {
+        const ptr = this.__destroy_into_raw();
+        wasm.__wbg_sessiontracker_free(ptr, 0);
+    }
+    /**
+     * Records a played hand and returns the decision check for it.
+     * @param {any} hand
+     * @returns {any}
+     */
+    record_hand(hand) {
+        const ret = wasm.sessiontracker_record_hand(this.__wbg_ptr, hand);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        return takeFromExternrefTable0(ret[0]);
+    }
+    /**
+     * Counts cards seen away from the player's hand, given as ranks.
+     * @param {any} ranks
+     */
+    record_cards(ranks) {
+        const ret = wasm.sessiontracker_record_cards(this.__wbg_ptr, ranks);
+        if (ret[1]) {
+            throw takeFromExternrefTable0(ret[0]);
+        }
+    }
+    /**
+     * @param {any} params
+     */
+    constructor(params) {
+        const ret = wasm.sessiontracker_new(params);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        this.__wbg_ptr = ret[0] >>> 0;
+        SessionTrackerFinalization.register(this, this.__wbg_ptr, this);
+        return this;
+    }
+    shuffle() {
+        wasm.sessiontracker_shuffle(this.__wbg_ptr);
+    }
+    /**
+     * @returns {any}
+     */
+    summary() {
+        const ret = wasm.sessiontracker_summary(this.__wbg_ptr);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        return takeFromExternrefTable0(ret[0]);
+    }
+}
+if (Symbol.dispose) SessionTracker.prototype[Symbol.dispose] = SessionTracker.prototype.free;
+
+const SimulatorFinalization = (typeof FinalizationRegistry === 'undefined')
+    ? { register: () => {}, unregister: () => {} }
+    : new FinalizationRegistry(ptr => wasm.__wbg_simulator_free(ptr >>> 0, 1));
+/**
+ * Holds a parsed strategy and base configuration so the UI can run many
+ * simulations and spot checks without re-sending the strategy JSON.
+ */
+export class Simulator {
+
+    __destroy_into_raw() {
+        const ptr = this.__wbg_ptr;
+        this.__wbg_ptr = 0;
+        SimulatorFinalization.unregister(this);
+        return ptr;
+    }
+
+    free() {
+        const ptr = this.__destroy_into_raw();
+        wasm.__wbg_simulator_free(ptr, 0);
+    }
+    /**
+     * @param {any} hand
+     * @returns {any}
+     */
+    spot_check(hand) {
+        const ret = wasm.simulator_spot_check(this.__wbg_ptr, hand);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        return takeFromExternrefTable0(ret[0]);
+    }
+    /**
+     * @param {any} params
+     */
+    constructor(params) {
+        const ret = wasm.simulator_new(params);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        this.__wbg_ptr = ret[0] >>> 0;
+        SimulatorFinalization.register(this, this.__wbg_ptr, this);
+        return this;
+    }
+    /**
+     * Runs a simulation; `overrides` may change the seed, iterations, bet size,
+     * bet ramp or output detail for this call only.
+     * @param {any} overrides
+     * @returns {any}
+     */
+    run(overrides) {
+        const ret = wasm.simulator_run(this.__wbg_ptr, overrides);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        return takeFromExternrefTable0(ret[0]);
+    }
+}
+if (Symbol.dispose) Simulator.prototype[Symbol.dispose] = Simulator.prototype.free;
+
 const EXPECTED_RESPONSE_TYPES = new Set(['basic', 'cors', 'default']);
 
 async function __wbg_load(module, imports) {
@@ -357,6 +935,13 @@ function __wbg_get_imports() {
     imports.wbg.__wbg___wbindgen_throw_b855445ff6a94295 = function(arg0, arg1) {
         throw new Error(getStringFromWasm0(arg0, arg1));
     };
+    imports.wbg.__wbg__wbg_cb_unref_2454a539ea5790d9 = function(arg0) {
+        arg0._wbg_cb_unref();
+    };
+    imports.wbg.__wbg_call_525440f72fbfc0ea = function() { return handleError(function (arg0, arg1, arg2) {
+        const ret = arg0.call(arg1, arg2);
+        return ret;
+    }, arguments) };
     imports.wbg.__wbg_call_e45d2cf9fc925fcf = function() { return handleError(function (arg0, arg1, arg2, arg3) {
         const ret = arg0.call(arg1, arg2, arg3);
         return ret;
@@ -365,6 +950,10 @@ function __wbg_get_imports() {
         const ret = arg0.call(arg1);
         return ret;
     }, arguments) };
+    imports.wbg.__wbg_crypto_574e78ad8b13b65f = function(arg0) {
+        const ret = arg0.crypto;
+        return ret;
+    };
     imports.wbg.__wbg_done_2042aa2670fb1db1 = function(arg0) {
         const ret = arg0.done;
         return ret;
@@ -384,6 +973,9 @@ function __wbg_get_imports() {
             wasm.__wbindgen_free(deferred0_0, deferred0_1, 1);
         }
     };
+    imports.wbg.__wbg_getRandomValues_b8f5dbd5f3995a9e = function() { return handleError(function (arg0, arg1) {
+        arg0.getRandomValues(arg1);
+    }, arguments) };
     imports.wbg.__wbg_get_7bed016f185add81 = function(arg0, arg1) {
         const ret = arg0[arg1 >>> 0];
         return ret;
@@ -446,10 +1038,36 @@ function __wbg_get_imports() {
         const ret = arg0.length;
         return ret;
     };
+    imports.wbg.__wbg_length_e70e9e6484b0952f = function(arg0) {
+        const ret = arg0.length;
+        return ret;
+    };
+    imports.wbg.__wbg_msCrypto_a61aeb35a24c1329 = function(arg0) {
+        const ret = arg0.msCrypto;
+        return ret;
+    };
     imports.wbg.__wbg_new_1acc0b6eea89d040 = function() {
         const ret = new Object();
         return ret;
     };
+    imports.wbg.__wbg_new_3c3d849046688a66 = function(arg0, arg1) {
+        try {
+            var state0 = {a: arg0, b: arg1};
+            var cb0 = (arg0, arg1) => {
+                const a = state0.a;
+                state0.a = 0;
+                try {
+                    return wasm_bindgen__convert__closures_____invoke__h16713329aacdf452(a, state0.b, arg0, arg1);
+                } finally {
+                    state0.a = a;
+                }
+            };
+            const ret = new Promise(cb0);
+            return ret;
+        } finally {
+            state0.a = state0.b = 0;
+        }
+    };
     imports.wbg.__wbg_new_5a79be3ab53b8aa5 = function(arg0) {
         const ret = new Uint8Array(arg0);
         return ret;
@@ -466,6 +1084,30 @@ function __wbg_get_imports() {
         const ret = new Array();
         return ret;
     };
+    imports.wbg.__wbg_new_from_slice_28b392e73daecb5c = function(arg0, arg1) {
+        const ret = new Int32Array(getArrayI32FromWasm0(arg0, arg1));
+        return ret;
+    };
+    imports.wbg.__wbg_new_from_slice_4ebacc19af8a8125 = function(arg0, arg1) {
+        const ret = new Uint16Array(getArrayU16FromWasm0(arg0, arg1));
+        return ret;
+    };
+    imports.wbg.__wbg_new_from_slice_7943307099c96d15 = function(arg0, arg1) {
+        const ret = new Uint32Array(getArrayU32FromWasm0(arg0, arg1));
+        return ret;
+    };
+    imports.wbg.__wbg_new_from_slice_fde3e31e670b38a6 = function(arg0, arg1) {
+        const ret = new Float64Array(getArrayF64FromWasm0(arg0, arg1));
+        return ret;
+    };
+    imports.wbg.__wbg_new_no_args_ee98eee5275000a4 = function(arg0, arg1) {
+        const ret = new Function(getStringFromWasm0(arg0, arg1));
+        return ret;
+    };
+    imports.wbg.__wbg_new_with_length_01aa0dc35aa13543 = function(arg0) {
+        const ret = new Uint8Array(arg0 >>> 0);
+        return ret;
+    };
     imports.wbg.__wbg_next_020810e0ae8ebcb0 = function() { return handleError(function (arg0) {
         const ret = arg0.next();
         return ret;
@@ -474,9 +1116,43 @@ function __wbg_get_imports() {
         const ret = arg0.next;
         return ret;
     };
+    imports.wbg.__wbg_node_905d3e251edff8a2 = function(arg0) {
+        const ret = arg0.node;
+        return ret;
+    };
+    imports.wbg.__wbg_now_793306c526e2e3b6 = function() {
+        const ret = Date.now();
+        return ret;
+    };
+    imports.wbg.__wbg_process_dc0fbacc7c1c06f7 = function(arg0) {
+        const ret = arg0.process;
+        return ret;
+    };
     imports.wbg.__wbg_prototypesetcall_2a6620b6922694b2 = function(arg0, arg1, arg2) {
         Uint8Array.prototype.set.call(getArrayU8FromWasm0(arg0, arg1), arg2);
     };
+    imports.wbg.__wbg_push_df81a39d04db858c = function(arg0, arg1) {
+        const ret = arg0.push(arg1);
+        return ret;
+    };
+    imports.wbg.__wbg_queueMicrotask_34d692c25c47d05b = function(arg0) {
+        const ret = arg0.queueMicrotask;
+        return ret;
+    };
+    imports.wbg.__wbg_queueMicrotask_9d76cacb20c84d58 = function(arg0) {
+        queueMicrotask(arg0);
+    };
+    imports.wbg.__wbg_randomFillSync_ac0988aba3254290 = function() { return handleError(function (arg0, arg1) {
+        arg0.randomFillSync(arg1);
+    }, arguments) };
+    imports.wbg.__wbg_require_60cc747a6bc5215a = function() { return handleError(function () {
+        const ret = module.require;
+        return ret;
+    }, arguments) };
+    imports.wbg.__wbg_resolve_caf97c30b83f7053 = function(arg0) {
+        const ret = Promise.resolve(arg0);
+        return ret;
+    };
     imports.wbg.__wbg_set_3f1d0b984ed272ed = function(arg0, arg1, arg2) {
         arg0[arg1] = arg2;
     };
@@ -487,6 +1163,13 @@ function __wbg_get_imports() {
     imports.wbg.__wbg_set_c213c871859d6500 = function(arg0, arg1, arg2) {
         arg0[arg1 >>> 0] = arg2;
     };
+    imports.wbg.__wbg_set_c2abbebe8b9ebee1 = function() { return handleError(function (arg0, arg1, arg2) {
+        const ret = Reflect.set(arg0, arg1, arg2);
+        return ret;
+    }, arguments) };
+    imports.wbg.__wbg_set_index_a0c01b257dd824f8 = function(arg0, arg1, arg2) {
+        arg0[arg1 >>> 0] = arg2;
+    };
     imports.wbg.__wbg_stack_0ed75d68575b0f3c = function(arg0, arg1) {
         const ret = arg1.stack;
         const ptr1 = passStringToWasm0(ret, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
@@ -494,10 +1177,42 @@ function __wbg_get_imports() {
         getDataViewMemory0().setInt32(arg0 + 4 * 1, len1, true);
         getDataViewMemory0().setInt32(arg0 + 4 * 0, ptr1, true);
     };
+    imports.wbg.__wbg_static_accessor_GLOBAL_89e1d9ac6a1b250e = function() {
+        const ret = typeof global === 'undefined' ? null : global;
+        return isLikeNone(ret) ? 0 : addToExternrefTable0(ret);
+    };
+    imports.wbg.__wbg_static_accessor_GLOBAL_THIS_8b530f326a9e48ac = function() {
+        const ret = typeof globalThis === 'undefined' ? null : globalThis;
+        return isLikeNone(ret) ? 0 : addToExternrefTable0(ret);
+    };
+    imports.wbg.__wbg_static_accessor_SELF_6fdf4b64710cc91b = function() {
+        const ret = typeof self === 'undefined' ? null : self;
+        return isLikeNone(ret) ? 0 : addToExternrefTable0(ret);
+    };
+    imports.wbg.__wbg_static_accessor_WINDOW_b45bfc5a37f6cfa2 = function() {
+        const ret = typeof window === 'undefined' ? null : window;
+        return isLikeNone(ret) ? 0 : addToExternrefTable0(ret);
+    };
+    imports.wbg.__wbg_subarray_480600f3d6a9f26c = function(arg0, arg1, arg2) {
+        const ret = arg0.subarray(arg1 >>> 0, arg2 >>> 0);
+        return ret;
+    };
+    imports.wbg.__wbg_then_4f46f6544e6b4a28 = function(arg0, arg1) {
+        const ret = arg0.then(arg1);
+        return ret;
+    };
+    imports.wbg.__wbg_then_70d05cf780a18d77 = function(arg0, arg1, arg2) {
+        const ret = arg0.then(arg1, arg2);
+        return ret;
+    };
     imports.wbg.__wbg_value_692627309814bb8c = function(arg0) {
         const ret = arg0.value;
         return ret;
     };
+    imports.wbg.__wbg_versions_c01dfd4722a88165 = function(arg0) {
+        const ret = arg0.versions;
+        return ret;
+    };
     imports.wbg.__wbindgen_cast_2241b6af4c4b2941 = function(arg0, arg1) {
         // Cast intrinsic for `Ref(String) -> Externref`.
         const ret = getStringFromWasm0(arg0, arg1);
@@ -508,11 +1223,21 @@ function __wbg_get_imports() {
         const ret = BigInt.asUintN(64, arg0);
         return ret;
     };
+    imports.wbg.__wbindgen_cast_72f2309ca88b7133 = function(arg0, arg1) {
+        // Cast intrinsic for `Closure(Closure { dtor_idx: 161, function: Function { arguments: [Externref], shim_idx: 162, ret: Unit, inner_ret: Some(Unit) }, mutable: true }) -> Externref`.
+        const ret = makeMutClosure(arg0, arg1, wasm.wasm_bindgen__closure__destroy__hb9aa826084de4b97, wasm_bindgen__convert__closures_____invoke__h306f4bd0f7c35ced);
+        return ret;
+    };
     imports.wbg.__wbindgen_cast_9ae0607507abb057 = function(arg0) {
         // Cast intrinsic for `I64 -> Externref`.
         const ret = arg0;
         return ret;
     };
+    imports.wbg.__wbindgen_cast_cb9088102bce6b30 = function(arg0, arg1) {
+        // Cast intrinsic for `Ref(Slice(U8)) -> NamedExternref("Uint8Array")`.
+        const ret = getArrayU8FromWasm0(arg0, arg1);
+        return ret;
+    };
     imports.wbg.__wbindgen_cast_d6cd19b81560fd6e = function(arg0) {
         // Cast intrinsic for `F64 -> Externref`.
         const ret = arg0;
@@ -536,6 +1261,10 @@ function __wbg_finalize_init(instance, module) {
     wasm = instance.exports;
     __wbg_init.__wbindgen_wasm_module = module;
     cachedDataViewMemory0 = null;
+    cachedFloat64ArrayMemory0 = null;
+    cachedInt32ArrayMemory0 = null;
+    cachedUint16ArrayMemory0 = null;
+    cachedUint32ArrayMemory0 = null;
     cachedUint8ArrayMemory0 = null;
 
 
@@ -0,0 +1,328 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{betting, stats::quantile};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BankrollEvaluationInput {
+    /// Candidate starting bankrolls, all evaluated against the same outcome stream.
+    pub bankrolls: Vec<f64>,
+    /// Rounds per trip; the stream is cut into consecutive trips of this length.
+    /// When omitted the whole run is a single trip.
+    #[serde(default)]
+    pub trip_rounds: Option<u32>,
+    /// Profit at which a trip stops as a success.
+    #[serde(default)]
+    pub win_goal: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BankrollOutcome {
+    pub bankroll: f64,
+    pub trips: u32,
+    pub ruined_trips: u32,
+    pub risk_of_ruin: f64,
+    pub goal_trips: u32,
+    pub goal_probability: f64,
+    pub mean_final_bankroll: f64,
+    /// Average rounds played before ruin, over ruined trips.
+    pub mean_rounds_to_ruin: f64,
+}
+
+struct TripState {
+    start: f64,
+    current: f64,
+    ruined_at: Option<u32>,
+    reached_goal: bool,
+    trips: u32,
+    ruined_trips: u32,
+    goal_trips: u32,
+    final_sum: f64,
+    ruin_rounds_sum: u64,
+}
+
+/// Replays a per-round result stream against several bankrolls at once.
+pub struct BankrollEvaluator {
+    trip_rounds: Option<u32>,
+    win_goal: Option<f64>,
+    round_in_trip: u32,
+    states: Vec<TripState>,
+}
+
+impl BankrollEvaluator {
+    pub fn new(input: &BankrollEvaluationInput) -> Self {
+        let states = input
+            .bankrolls
+            .iter()
+            .map(|&start| TripState {
+                start,
+                current: start,
+                ruined_at: None,
+                reached_goal: false,
+                trips: 0,
+                ruined_trips: 0,
+                goal_trips: 0,
+                final_sum: 0.0,
+                ruin_rounds_sum: 0,
+            })
+            .collect();
+        BankrollEvaluator {
+            trip_rounds: input.trip_rounds.filter(|&r| r > 0),
+            win_goal: input.win_goal.filter(|&g| g > 0.0),
+            round_in_trip: 0,
+            states,
+        }
+    }
+
+    /// Applies one round. `stake` is the bet the round required up front,
+    /// over every spot. Doubles and splits can lose more than that, so a
+    /// bankroll may end the round below zero; it is then ruined at that round
+    /// and counts as zero in `mean_final_bankroll`.
+    pub fn record(&mut self, stake: f64, winnings: f64) {
+        self.round_in_trip += 1;
+        for state in &mut self.states {
+            if state.ruined_at.is_some() || state.reached_goal {
+                continue;
+            }
+            if state.current < stake || state.current <= 0.0 {
+                state.ruined_at = Some(self.round_in_trip - 1);
+                continue;
+            }
+            state.current += winnings;
+            if state.current < stake || state.current <= 0.0 {
+                state.ruined_at = Some(self.round_in_trip);
+            } else if let Some(goal) = self.win_goal {
+                state.reached_goal = state.current - state.start >= goal;
+            }
+        }
+        if self.trip_rounds == Some(self.round_in_trip) {
+            self.close_trip();
+        }
+    }
+
+    fn close_trip(&mut self) {
+        for state in &mut self.states {
+            state.trips += 1;
+            state.final_sum += state.current.max(0.0);
+            if let Some(rounds) = state.ruined_at {
+                state.ruined_trips += 1;
+                state.ruin_rounds_sum += rounds as u64;
+            }
+            if state.reached_goal {
+                state.goal_trips += 1;
+            }
+            state.current = state.start;
+            state.ruined_at = None;
+            state.reached_goal = false;
+        }
+        self.round_in_trip = 0;
+    }
+
+    pub fn finish(mut self) -> Vec<BankrollOutcome> {
+        // A trailing partial trip only counts when it is a single whole-run trip.
+        if self.trip_rounds.is_none() && self.round_in_trip > 0 {
+            self.close_trip();
+        }
+        self.states
+            .into_iter()
+            .map(|state| {
+                let trips = state.trips.max(1) as f64;
+                BankrollOutcome {
+                    bankroll: state.start,
+                    trips: state.trips,
+                    ruined_trips: state.ruined_trips,
+                    risk_of_ruin: state.ruined_trips as f64 / trips,
+                    goal_trips: state.goal_trips,
+                    goal_probability: state.goal_trips as f64 / trips,
+                    mean_final_bankroll: state.final_sum / trips,
+                    mean_rounds_to_ruin: if state.ruined_trips > 0 {
+                        state.ruin_rounds_sum as f64 / state.ruined_trips as f64
+                    } else {
+                        0.0
+                    },
+                }
+            })
+            .collect()
+    }
+}
+
+/// Spread of the rounds it took to go broke, over every bust in a run.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuinTimes {
+    pub mean: f64,
+    pub shortest: u32,
+    pub p10: u32,
+    pub median: u32,
+    pub p90: u32,
+    pub longest: u32,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BankrollTracking {
+    pub starting_bankroll: f64,
+    pub final_bankroll: f64,
+    /// Dips below zero when doubles or splits lose more than the bet the
+    /// round was checked against.
+    pub lowest_bankroll: f64,
+    /// Times the bankroll could not cover the next bet.
+    pub busts: u32,
+    /// Round the run stopped at, when it stops on ruin.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stopped_at_round: Option<u32>,
+    /// Rounds played from the start, or the last rebuy, to each bust.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rounds_to_ruin: Option<RuinTimes>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_bankroll: Option<f64>,
+    /// Times the bankroll reached the target, each time starting over.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub targets_reached: Option<u32>,
+    /// Share of finished attempts that reached the target before going broke,
+    /// and the same from the run's win rate and spread per round.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_probability: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub analytic_target_probability: Option<f64>,
+}
+
+/// One bankroll played through a run. A bust either stops the run or rebuys
+/// the starting bankroll and carries on; reaching the target starts over too.
+pub struct BankrollTracker {
+    start: f64,
+    target: Option<f64>,
+    targets_reached: u32,
+    current: f64,
+    lowest: f64,
+    stop_on_ruin: bool,
+    rounds_since_rebuy: u32,
+    ruin_rounds: Vec<u32>,
+    stopped_at: Option<u32>,
+}
+
+impl BankrollTracker {
+    pub fn new(start: f64, target: Option<f64>, stop_on_ruin: bool) -> Self {
+        BankrollTracker {
+            start,
+            target,
+            targets_reached: 0,
+            current: start,
+            lowest: start,
+            stop_on_ruin,
+            rounds_since_rebuy: 0,
+            ruin_rounds: Vec::new(),
+            stopped_at: None,
+        }
+    }
+
+    pub fn stopped(&self) -> bool {
+        self.stopped_at.is_some()
+    }
+
+    /// Checks the bankroll covers `stake` before round `round`. Returns false
+    /// when it does not and the run stops here.
+    pub fn cover(&mut self, stake: f64, round: u32) -> bool {
+        if self.current >= stake && self.current > 0.0 {
+            return true;
+        }
+        self.ruin_rounds.push(self.rounds_since_rebuy);
+        if self.stop_on_ruin {
+            self.stopped_at = Some(round);
+            return false;
+        }
+        self.current = self.start;
+        self.rounds_since_rebuy = 0;
+        true
+    }
+
+    pub fn record(&mut self, winnings: f64) {
+        self.rounds_since_rebuy += 1;
+        self.current += winnings;
+        self.lowest = self.lowest.min(self.current);
+        if self.target.is_some_and(|target| self.current >= target) {
+            self.targets_reached += 1;
+            self.current = self.start;
+            self.rounds_since_rebuy = 0;
+        }
+    }
+
+    /// `ev` and `variance` are the run's per-round figures, for the analytic
+    /// target probability.
+    pub fn finish(mut self, ev: f64, variance: f64) -> BankrollTracking {
+        self.ruin_rounds.sort_unstable();
+        let rounds = &self.ruin_rounds;
+        let rounds_to_ruin = (!rounds.is_empty()).then(|| RuinTimes {
+            mean: rounds.iter().map(|&r| r as f64).sum::<f64>() / rounds.len() as f64,
+            shortest: rounds[0],
+            p10: quantile(rounds, 0.1),
+            median: quantile(rounds, 0.5),
+            p90: quantile(rounds, 0.9),
+            longest: rounds[rounds.len() - 1],
+        });
+        BankrollTracking {
+            starting_bankroll: self.start,
+            final_bankroll: self.current,
+            lowest_bankroll: self.lowest,
+            busts: self.ruin_rounds.len() as u32,
+            stopped_at_round: self.stopped_at,
+            rounds_to_ruin,
+            target_bankroll: self.target,
+            targets_reached: self.target.map(|_| self.targets_reached),
+            target_probability: self.target.and_then(|_| {
+                let attempts = self.targets_reached + self.ruin_rounds.len() as u32;
+                (attempts > 0).then(|| self.targets_reached as f64 / attempts as f64)
+            }),
+            analytic_target_probability: self
+                .target
+                .map(|target| betting::target_probability(self.start, target, ev, variance)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn target_and_bust_each_end_an_attempt() {
+        let mut tracker = BankrollTracker::new(100.0, Some(150.0), false);
+        assert!(tracker.cover(10.0, 1));
+        tracker.record(60.0);
+        assert!(tracker.cover(10.0, 2));
+        tracker.record(-100.0);
+        assert!(tracker.cover(10.0, 3));
+        tracker.record(5.0);
+        let tracking = tracker.finish(0.0, 1.0);
+        assert_eq!(tracking.targets_reached, Some(1));
+        assert_eq!(tracking.busts, 1);
+        assert_eq!(tracking.target_probability, Some(0.5));
+        assert_eq!(tracking.final_bankroll, 105.0);
+        assert_eq!(tracking.lowest_bankroll, 0.0);
+        assert_eq!(tracking.rounds_to_ruin.map(|times| times.longest), Some(1));
+    }
+
+    #[test]
+    fn open_attempt_is_not_counted() {
+        let mut tracker = BankrollTracker::new(100.0, Some(200.0), false);
+        assert!(tracker.cover(10.0, 1));
+        tracker.record(50.0);
+        let tracking = tracker.finish(0.0, 1.0);
+        assert_eq!(tracking.targets_reached, Some(0));
+        assert_eq!(tracking.target_probability, None);
+    }
+
+    #[test]
+    fn stop_on_ruin_stops_at_the_uncovered_round() {
+        let mut tracker = BankrollTracker::new(20.0, None, true);
+        assert!(tracker.cover(10.0, 1));
+        tracker.record(-15.0);
+        assert!(!tracker.cover(10.0, 2));
+        assert!(tracker.stopped());
+        let tracking = tracker.finish(0.0, 1.0);
+        assert_eq!(tracking.stopped_at_round, Some(2));
+        assert_eq!(tracking.busts, 1);
+        assert_eq!(tracking.targets_reached, None);
+        assert_eq!(tracking.analytic_target_probability, None);
+    }
+}
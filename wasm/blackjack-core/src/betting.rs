@@ -0,0 +1,792 @@
+use serde::{Deserialize, Serialize};
+
+use crate::sim::CountStats;
+
+/// One rung of a bet ramp: bet `units` base bets whenever the rounded true
+/// count is at least `true_count` (and below the next rung).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RampStep {
+    pub true_count: i32,
+    pub units: f64,
+}
+
+/// Back-counting: watch the shoe from behind the table, join once the true
+/// count reaches `entry_true_count` and leave when it drops below
+/// `exit_true_count` or the shoe is shuffled.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Wonging {
+    pub entry_true_count: f64,
+    pub exit_true_count: f64,
+}
+
+impl Wonging {
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.entry_true_count.is_finite() || !self.exit_true_count.is_finite() {
+            return Err("wonging thresholds must be finite".to_string());
+        }
+        if self.exit_true_count > self.entry_true_count {
+            return Err(format!(
+                "wonging exit_true_count {} is above entry_true_count {}",
+                self.exit_true_count, self.entry_true_count
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Bet per round: a flat amount, or a schedule of amounts played round by
+/// round (`[10, 20, 40]` or `{"bets": [10, 20, 40], "after_end": "hold_last"}`).
+/// A scheduled zero sits the round out. With a bet ramp, the ramp's units
+/// multiply the scheduled bet.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum BetSize {
+    Flat(f64),
+    /// A bare list of bets, repeated from the start once it runs out.
+    Cycle(Vec<f64>),
+    Schedule {
+        bets: Vec<f64>,
+        #[serde(default)]
+        after_end: ScheduleEnd,
+    },
+}
+
+/// What a bet schedule does past its last entry.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleEnd {
+    /// Start over from the first bet.
+    #[default]
+    Repeat,
+    /// Keep betting the last entry, so bets are indexed by round number.
+    HoldLast,
+}
+
+impl BetSize {
+    fn schedule(&self) -> Option<(&[f64], ScheduleEnd)> {
+        match self {
+            BetSize::Flat(_) => None,
+            BetSize::Cycle(bets) => Some((bets, ScheduleEnd::Repeat)),
+            BetSize::Schedule { bets, after_end } => Some((bets, *after_end)),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let Some((bets, _)) = self.schedule() else {
+            return Ok(());
+        };
+        if bets.iter().any(|bet| !bet.is_finite() || *bet < 0.0) {
+            return Err("scheduled bets must be zero or positive".to_string());
+        }
+        if !bets.iter().any(|bet| *bet > 0.0) {
+            return Err("a bet schedule needs at least one positive bet".to_string());
+        }
+        Ok(())
+    }
+
+    /// The bet base-bet units are measured in: the flat bet, or the smallest
+    /// positive bet of a schedule.
+    pub fn unit(&self) -> f64 {
+        match self {
+            BetSize::Flat(bet) => *bet,
+            BetSize::Cycle(bets) | BetSize::Schedule { bets, .. } => {
+                bets.iter().copied().filter(|bet| *bet > 0.0).fold(f64::INFINITY, f64::min)
+            }
+        }
+    }
+
+    /// Scheduled bet for round `round` (counted from zero), or `None` when betting flat.
+    pub fn scheduled(&self, round: u32) -> Option<f64> {
+        let (bets, after_end) = self.schedule()?;
+        let round = round as usize;
+        let index = match after_end {
+            ScheduleEnd::Repeat => round % bets.len(),
+            ScheduleEnd::HoldLast => round.min(bets.len() - 1),
+        };
+        Some(bets[index])
+    }
+}
+
+/// Checks a bet ramp: each rung bets a finite, non-negative number of units
+/// at its own true count. Zero units sits the round out.
+pub fn validate_ramp(ramp: &[RampStep]) -> Result<(), String> {
+    for (i, step) in ramp.iter().enumerate() {
+        if !step.units.is_finite() || step.units < 0.0 {
+            return Err(format!(
+                "bet_ramp bets {} units at true count {}, expected zero or more",
+                step.units, step.true_count
+            ));
+        }
+        if ramp[..i].iter().any(|earlier| earlier.true_count == step.true_count) {
+            return Err(format!("bet_ramp lists true count {} more than once", step.true_count));
+        }
+    }
+    Ok(())
+}
+
+/// Units to bet at `true_count`; counts below the first rung use the first rung.
+pub fn ramp_units(ramp: &[RampStep], true_count: i32) -> f64 {
+    let mut units = match ramp.iter().min_by_key(|s| s.true_count) {
+        Some(lowest) => lowest.units,
+        None => return 1.0,
+    };
+    let mut best = i32::MIN;
+    for step in ramp {
+        if step.true_count <= true_count && step.true_count >= best {
+            best = step.true_count;
+            units = step.units;
+        }
+    }
+    units.max(0.0)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BetRecommendationInput {
+    pub bankroll: f64,
+    /// Fraction of full Kelly to bet; derived from `risk_of_ruin` when omitted.
+    #[serde(default)]
+    pub kelly_fraction: Option<f64>,
+    /// Target risk of ruin (e.g. 0.05), used when `kelly_fraction` is omitted.
+    #[serde(default)]
+    pub risk_of_ruin: Option<f64>,
+    /// Bet placed at counts without an advantage; defaults to the simulation bet size.
+    #[serde(default)]
+    pub min_bet: Option<f64>,
+    #[serde(default)]
+    pub max_bet: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BetRecommendation {
+    pub bankroll: f64,
+    pub kelly_fraction: f64,
+    pub ramp: Vec<RecommendedStep>,
+    /// Expected win per round with the recommended ramp, from the measured count frequencies.
+    pub expected_win_per_round: f64,
+    pub std_dev_per_round: f64,
+    /// Approximate risk of ruin of the recommended ramp with a fixed bankroll.
+    pub risk_of_ruin: f64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecommendedStep {
+    pub true_count: i32,
+    pub bet: f64,
+    pub advantage: f64,
+    pub variance: f64,
+    pub frequency: f64,
+}
+
+/// Risk of ruin for a fixed bankroll given per-round win rate and variance.
+pub fn risk_of_ruin(bankroll: f64, ev: f64, variance: f64) -> f64 {
+    if ev <= 0.0 || variance <= 0.0 {
+        return 1.0;
+    }
+    (-2.0 * ev * bankroll / variance).exp().min(1.0)
+}
+
+/// Chance of reaching `target` before losing all of `bankroll`, given
+/// per-round win rate and variance.
+pub fn target_probability(bankroll: f64, target: f64, ev: f64, variance: f64) -> f64 {
+    if bankroll >= target {
+        return 1.0;
+    }
+    if variance <= 0.0 {
+        return if ev > 0.0 { 1.0 } else { 0.0 };
+    }
+    let drift = -2.0 * ev / variance;
+    if drift.abs() * target < 1e-12 {
+        return bankroll / target;
+    }
+    let probability = (drift * bankroll).exp_m1() / (drift * target).exp_m1();
+    if probability.is_finite() {
+        probability.clamp(0.0, 1.0)
+    } else {
+        // Both terms overflow when losing steadily; their ratio is what is left.
+        (drift * (bankroll - target)).exp().min(1.0)
+    }
+}
+
+/// Kelly fraction whose fixed-bankroll ruin probability is roughly `ror`.
+fn kelly_fraction_for_ror(ror: f64) -> f64 {
+    let ror = ror.clamp(1e-9, 0.999_999);
+    -2.0 / ror.ln()
+}
+
+pub fn recommend_ramp(
+    input: &BetRecommendationInput,
+    count_stats: &CountStats,
+    default_min_bet: f64,
+) -> BetRecommendation {
+    let kelly_fraction = input
+        .kelly_fraction
+        .or_else(|| input.risk_of_ruin.map(kelly_fraction_for_ror))
+        .unwrap_or(0.5)
+        .max(0.0);
+    let min_bet = input.min_bet.unwrap_or(default_min_bet).max(0.0);
+    let max_bet = input.max_bet.unwrap_or(f64::INFINITY).max(min_bet);
+
+    let mut counts: Vec<i32> = count_stats
+        .hands_by_count
+        .keys()
+        .filter_map(|k| k.parse().ok())
+        .collect();
+    counts.sort_unstable();
+
+    let total_hands = count_stats.total_hands.max(1) as f64;
+    let mut ramp = Vec::new();
+    let mut ev = 0.0;
+    let mut second_moment = 0.0;
+    for count in counts {
+        let key = count.to_string();
+        let hands = count_stats.hands_by_count.get(&key).copied().unwrap_or(0);
+        let advantage = count_stats.advantage_by_count.get(&key).copied().unwrap_or(0.0);
+        let variance = count_stats.variance_by_count.get(&key).copied().unwrap_or(0.0);
+        let bet = if advantage > 0.0 && variance > 0.0 {
+            (kelly_fraction * input.bankroll * advantage / variance).clamp(min_bet, max_bet)
+        } else {
+            min_bet
+        };
+        let frequency = hands as f64 / total_hands;
+        ev += frequency * bet * advantage;
+        second_moment += frequency * bet * bet * (variance + advantage * advantage);
+        ramp.push(RecommendedStep {
+            true_count: count,
+            bet,
+            advantage,
+            variance,
+            frequency,
+        });
+    }
+    let variance = (second_moment - ev * ev).max(0.0);
+
+    BetRecommendation {
+        bankroll: input.bankroll,
+        kelly_fraction,
+        ramp,
+        expected_win_per_round: ev,
+        std_dev_per_round: variance.sqrt(),
+        risk_of_ruin: risk_of_ruin(input.bankroll, ev, variance),
+    }
+}
+
+/// Bets sized from the current bankroll by the Kelly criterion, using an
+/// advantage that grows linearly with the rounded true count.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KellyBettingInput {
+    pub bankroll: f64,
+    /// Fraction of full Kelly to bet, e.g. 0.5 for half Kelly.
+    #[serde(default = "default_kelly_fraction")]
+    pub kelly_fraction: f64,
+    /// Player advantage at a true count of zero.
+    #[serde(default = "default_base_advantage")]
+    pub base_advantage: f64,
+    /// Advantage gained per point of true count.
+    #[serde(default = "default_advantage_per_true_count")]
+    pub advantage_per_true_count: f64,
+    /// Variance of one hand in squared bets.
+    #[serde(default = "default_hand_variance")]
+    pub variance: f64,
+    /// Bet placed at counts without an advantage; defaults to the simulation bet size.
+    #[serde(default)]
+    pub min_bet: Option<f64>,
+    #[serde(default)]
+    pub max_bet: Option<f64>,
+}
+
+fn default_kelly_fraction() -> f64 {
+    1.0
+}
+
+fn default_base_advantage() -> f64 {
+    -0.005
+}
+
+fn default_advantage_per_true_count() -> f64 {
+    0.005
+}
+
+fn default_hand_variance() -> f64 {
+    1.3
+}
+
+impl KellyBettingInput {
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.bankroll.is_finite() || self.bankroll <= 0.0 {
+            return Err("kelly betting needs a positive bankroll".to_string());
+        }
+        if !self.kelly_fraction.is_finite() || self.kelly_fraction <= 0.0 {
+            return Err("kelly_fraction must be positive".to_string());
+        }
+        if !self.base_advantage.is_finite() || !self.advantage_per_true_count.is_finite() {
+            return Err("kelly advantage estimates must be finite".to_string());
+        }
+        if !self.variance.is_finite() || self.variance <= 0.0 {
+            return Err("kelly variance must be positive".to_string());
+        }
+        let min_bet = self.min_bet.unwrap_or(0.0);
+        if !min_bet.is_finite() || min_bet < 0.0 {
+            return Err("kelly min_bet must be zero or positive".to_string());
+        }
+        if let Some(max_bet) = self.max_bet.filter(|max_bet| !max_bet.is_finite() || *max_bet < min_bet) {
+            return Err(format!("kelly max_bet {max_bet} is below min_bet {min_bet}"));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KellyOutcome {
+    pub starting_bankroll: f64,
+    pub kelly_fraction: f64,
+    pub final_bankroll: f64,
+    pub peak_bankroll: f64,
+    /// Largest fall from a previous peak.
+    pub max_drawdown: f64,
+    /// Mean log growth of the bankroll per round; omitted once ruined.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub growth_rate_per_round: Option<f64>,
+    pub ruined: bool,
+    /// Round the bankroll fell below the minimum bet.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ruined_at_round: Option<u32>,
+    pub mean_bet: f64,
+}
+
+/// Bankroll carried through a run for Kelly bet sizing. Once ruined the
+/// bankroll is frozen and the rest of the run is played at the minimum bet.
+pub struct KellyBettor {
+    input: KellyBettingInput,
+    min_bet: f64,
+    max_bet: f64,
+    bankroll: f64,
+    peak: f64,
+    max_drawdown: f64,
+    rounds: u32,
+    total_bet: f64,
+    ruined_at: Option<u32>,
+}
+
+impl KellyBettor {
+    pub fn new(input: KellyBettingInput, default_min_bet: f64) -> Self {
+        let min_bet = input.min_bet.unwrap_or(default_min_bet);
+        let max_bet = input.max_bet.unwrap_or(f64::INFINITY).max(min_bet);
+        KellyBettor {
+            bankroll: input.bankroll,
+            peak: input.bankroll,
+            input,
+            min_bet,
+            max_bet,
+            max_drawdown: 0.0,
+            rounds: 0,
+            total_bet: 0.0,
+            ruined_at: None,
+        }
+    }
+
+    /// Bet per spot at `true_count`, with the round's stake over `spots` kept
+    /// within the bankroll.
+    pub fn bet(&self, true_count: i32, spots: usize) -> f64 {
+        if self.ruined_at.is_some() {
+            return self.min_bet;
+        }
+        let advantage = self.input.base_advantage + self.input.advantage_per_true_count * true_count as f64;
+        let kelly = self.input.kelly_fraction * self.bankroll * advantage / self.input.variance;
+        let bet = kelly.clamp(self.min_bet, self.max_bet);
+        bet.min(self.bankroll / spots as f64).max(self.min_bet)
+    }
+
+    pub fn settle(&mut self, stake: f64, winnings: f64) {
+        self.rounds += 1;
+        self.total_bet += stake;
+        if self.ruined_at.is_some() {
+            return;
+        }
+        self.bankroll += winnings;
+        self.peak = self.peak.max(self.bankroll);
+        self.max_drawdown = self.max_drawdown.max(self.peak - self.bankroll);
+        if self.bankroll < self.min_bet || self.bankroll <= 0.0 {
+            self.ruined_at = Some(self.rounds);
+        }
+    }
+
+    pub fn finish(self) -> KellyOutcome {
+        let growth_rate_per_round = (self.ruined_at.is_none() && self.rounds > 0)
+            .then(|| (self.bankroll / self.input.bankroll).ln() / self.rounds as f64);
+        KellyOutcome {
+            starting_bankroll: self.input.bankroll,
+            kelly_fraction: self.input.kelly_fraction,
+            final_bankroll: self.bankroll,
+            peak_bankroll: self.peak,
+            max_drawdown: self.max_drawdown,
+            growth_rate_per_round,
+            ruined: self.ruined_at.is_some(),
+            ruined_at_round: self.ruined_at,
+            mean_bet: if self.rounds > 0 { self.total_bet / self.rounds as f64 } else { 0.0 },
+        }
+    }
+}
+
+/// Negative and positive progressions that size the next bet from the
+/// results of the previous ones.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProgressionSystem {
+    /// Double after every loss, back to one unit after a win.
+    Martingale,
+    /// Double after every win for up to three wins, back to one unit after a loss.
+    Paroli,
+    /// Bet 1, 3, 2 and 6 units on consecutive wins, starting over after a loss.
+    #[serde(rename = "1-3-2-6", alias = "one_three_two_six")]
+    OneThreeTwoSix,
+    /// Raise one unit after a win, never betting more than would finish the
+    /// cycle one unit up.
+    OscarsGrind,
+    /// One unit more after a loss, one less after a win.
+    #[serde(alias = "dalembert")]
+    DAlembert,
+}
+
+const ONE_THREE_TWO_SIX: [f64; 4] = [1.0, 3.0, 2.0, 6.0];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProgressionInput {
+    pub system: ProgressionSystem,
+    /// Table limit in base-bet units; a bet past it starts the progression over.
+    #[serde(default)]
+    pub max_units: Option<f64>,
+}
+
+impl ProgressionInput {
+    pub fn validate(&self) -> Result<(), String> {
+        match self.max_units {
+            Some(max_units) if !max_units.is_finite() || max_units < 1.0 => {
+                Err(format!("progression max_units {max_units} must be at least 1"))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgressionStats {
+    pub system: ProgressionSystem,
+    pub rounds: u32,
+    /// Mean and largest bet per spot.
+    pub mean_bet: f64,
+    pub largest_bet: f64,
+    /// Times the next bet would have passed `max_units` and the progression started over.
+    pub limit_resets: u32,
+    pub longest_losing_streak: u32,
+}
+
+/// Progression state through a run. Pushes leave the next bet unchanged.
+pub struct Progression {
+    input: ProgressionInput,
+    units: f64,
+    step: usize,
+    cycle_units: f64,
+    rounds: u32,
+    total_bet: f64,
+    largest_bet: f64,
+    limit_resets: u32,
+    losing_streak: u32,
+    longest_losing_streak: u32,
+}
+
+impl Progression {
+    pub fn new(input: ProgressionInput) -> Self {
+        Progression {
+            input,
+            units: 1.0,
+            step: 0,
+            cycle_units: 0.0,
+            rounds: 0,
+            total_bet: 0.0,
+            largest_bet: 0.0,
+            limit_resets: 0,
+            losing_streak: 0,
+            longest_losing_streak: 0,
+        }
+    }
+
+    /// Units of the base bet to stake next round.
+    pub fn units(&self) -> f64 {
+        self.units
+    }
+
+    fn restart(&mut self) {
+        self.units = 1.0;
+        self.step = 0;
+        self.cycle_units = 0.0;
+    }
+
+    /// Applies one played round: `unit` is the base bet, `bet` what was staked
+    /// per spot after table limits and `winnings` the net result per spot.
+    pub fn record(&mut self, unit: f64, bet: f64, winnings: f64) {
+        self.rounds += 1;
+        self.total_bet += bet;
+        self.largest_bet = self.largest_bet.max(bet);
+        let net_units = if unit > 0.0 { winnings / unit } else { 0.0 };
+        let won = net_units > 0.0;
+        let lost = net_units < 0.0;
+        if lost {
+            self.losing_streak += 1;
+            self.longest_losing_streak = self.longest_losing_streak.max(self.losing_streak);
+        } else if won {
+            self.losing_streak = 0;
+        }
+
+        match self.input.system {
+            ProgressionSystem::Martingale if lost => self.units *= 2.0,
+            ProgressionSystem::Martingale if won => self.restart(),
+            ProgressionSystem::Paroli if won && self.step < 2 => {
+                self.step += 1;
+                self.units *= 2.0;
+            }
+            ProgressionSystem::Paroli if won || lost => self.restart(),
+            ProgressionSystem::OneThreeTwoSix if won && self.step + 1 < ONE_THREE_TWO_SIX.len() => {
+                self.step += 1;
+                self.units = ONE_THREE_TWO_SIX[self.step];
+            }
+            ProgressionSystem::OneThreeTwoSix if won || lost => self.restart(),
+            ProgressionSystem::OscarsGrind => {
+                self.cycle_units += net_units;
+                if self.cycle_units >= 1.0 {
+                    self.restart();
+                } else if won {
+                    self.units = (self.units + 1.0).min(1.0 - self.cycle_units).max(1.0);
+                }
+            }
+            ProgressionSystem::DAlembert if lost => self.units += 1.0,
+            ProgressionSystem::DAlembert if won => self.units = (self.units - 1.0).max(1.0),
+            _ => {}
+        }
+        if self.input.max_units.is_some_and(|max_units| self.units > max_units) {
+            self.limit_resets += 1;
+            self.restart();
+        }
+    }
+
+    pub fn finish(self) -> ProgressionStats {
+        ProgressionStats {
+            system: self.input.system,
+            rounds: self.rounds,
+            mean_bet: if self.rounds > 0 { self.total_bet / self.rounds as f64 } else { 0.0 },
+            largest_bet: self.largest_bet,
+            limit_resets: self.limit_resets,
+            longest_losing_streak: self.longest_losing_streak,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableLimitStats {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub table_min: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub table_max: Option<f64>,
+    /// Played rounds whose wanted bet was below the minimum or above the maximum.
+    pub raised_to_min: u32,
+    pub capped_at_max: u32,
+    pub capped_frequency: f64,
+}
+
+/// Table minimum and maximum every bet is held to, whatever sized it.
+pub struct TableLimits {
+    min: Option<f64>,
+    max: Option<f64>,
+    rounds: u32,
+    raised_to_min: u32,
+    capped_at_max: u32,
+}
+
+impl TableLimits {
+    pub fn new(min: Option<f64>, max: Option<f64>) -> Result<Self, String> {
+        if min.is_some_and(|min| !min.is_finite() || min < 0.0) {
+            return Err("table_min must be zero or positive".to_string());
+        }
+        if let Some(max) = max.filter(|max| !max.is_finite() || *max <= 0.0 || *max < min.unwrap_or(0.0)) {
+            return Err(format!("table_max {max} must be positive and at least table_min"));
+        }
+        Ok(TableLimits {
+            min,
+            max,
+            rounds: 0,
+            raised_to_min: 0,
+            capped_at_max: 0,
+        })
+    }
+
+    /// Bet the table takes for a wanted `bet`; a zero bet still sits the round out.
+    pub fn clamp(&self, bet: f64) -> f64 {
+        if bet <= 0.0 {
+            return bet;
+        }
+        let bet = self.min.map_or(bet, |min| bet.max(min));
+        self.max.map_or(bet, |max| bet.min(max))
+    }
+
+    /// Counts a played round that wanted to bet `bet`.
+    pub fn record(&mut self, bet: f64) {
+        self.rounds += 1;
+        if self.min.is_some_and(|min| bet < min) {
+            self.raised_to_min += 1;
+        }
+        if self.max.is_some_and(|max| bet > max) {
+            self.capped_at_max += 1;
+        }
+    }
+
+    pub fn finish(self) -> TableLimitStats {
+        TableLimitStats {
+            table_min: self.min,
+            table_max: self.max,
+            raised_to_min: self.raised_to_min,
+            capped_at_max: self.capped_at_max,
+            capped_frequency: if self.rounds > 0 { self.capped_at_max as f64 / self.rounds as f64 } else { 0.0 },
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChipRoundingStats {
+    pub chip_size: f64,
+    /// Played rounds whose bet changed when rounded to chips.
+    pub rounds_rounded: u32,
+    /// Mean bet per spot as placed and as sized before rounding.
+    pub mean_bet: f64,
+    pub mean_ideal_bet: f64,
+    /// Win per played round at the placed bets, and estimated at the unrounded
+    /// bets by scaling each round's result to its ideal bet.
+    pub ev_per_round: f64,
+    pub ideal_ev_per_round: f64,
+    /// What rounding costs per played round; negative when it happened to help.
+    pub ev_cost_per_round: f64,
+}
+
+/// Rounds every sized bet to whole chips, never below one chip.
+pub struct ChipRounding {
+    chip_size: f64,
+    rounds: u32,
+    rounds_rounded: u32,
+    total_bet: f64,
+    total_ideal_bet: f64,
+    winnings: f64,
+    ideal_winnings: f64,
+}
+
+impl ChipRounding {
+    pub fn new(chip_size: f64) -> Result<Self, String> {
+        if !chip_size.is_finite() || chip_size <= 0.0 {
+            return Err("chip_size must be positive".to_string());
+        }
+        Ok(ChipRounding {
+            chip_size,
+            rounds: 0,
+            rounds_rounded: 0,
+            total_bet: 0.0,
+            total_ideal_bet: 0.0,
+            winnings: 0.0,
+            ideal_winnings: 0.0,
+        })
+    }
+
+    /// Nearest whole number of chips; a zero bet still sits the round out.
+    pub fn round(&self, bet: f64) -> f64 {
+        if bet <= 0.0 {
+            return bet;
+        }
+        (bet / self.chip_size).round().max(1.0) * self.chip_size
+    }
+
+    /// Applies a played round bet at `bet` per spot that would have been
+    /// `ideal` without rounding.
+    pub fn record(&mut self, ideal: f64, bet: f64, winnings: f64) {
+        self.rounds += 1;
+        if (bet - ideal).abs() > f64::EPSILON * ideal.max(1.0) {
+            self.rounds_rounded += 1;
+        }
+        self.total_bet += bet;
+        self.total_ideal_bet += ideal;
+        self.winnings += winnings;
+        self.ideal_winnings += if bet > 0.0 { winnings * ideal / bet } else { 0.0 };
+    }
+
+    pub fn finish(self) -> ChipRoundingStats {
+        let rounds = self.rounds.max(1) as f64;
+        ChipRoundingStats {
+            chip_size: self.chip_size,
+            rounds_rounded: self.rounds_rounded,
+            mean_bet: self.total_bet / rounds,
+            mean_ideal_bet: self.total_ideal_bet / rounds,
+            ev_per_round: self.winnings / rounds,
+            ideal_ev_per_round: self.ideal_winnings / rounds,
+            ev_cost_per_round: (self.ideal_winnings - self.winnings) / rounds,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-12, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn risk_of_ruin_matches_closed_form() {
+        close(risk_of_ruin(100.0, 0.01, 1.3), (-2.0 * 0.01 * 100.0 / 1.3_f64).exp());
+        close(risk_of_ruin(100.0, 0.0, 1.3), 1.0);
+        close(risk_of_ruin(100.0, -0.01, 1.3), 1.0);
+    }
+
+    #[test]
+    fn target_probability_matches_closed_form() {
+        let (bankroll, target, variance): (f64, f64, f64) = (100.0, 200.0, 1.3);
+        for ev in [0.01, -0.005] {
+            let drift = 2.0 * ev / variance;
+            let expected = (1.0 - (-drift * bankroll).exp()) / (1.0 - (-drift * target).exp());
+            close(target_probability(bankroll, target, ev, variance), expected);
+        }
+    }
+
+    #[test]
+    fn target_probability_edge_cases() {
+        close(target_probability(100.0, 400.0, 0.0, 1.3), 0.25);
+        close(target_probability(200.0, 200.0, -0.01, 1.3), 1.0);
+        close(target_probability(100.0, 200.0, 0.01, 0.0), 1.0);
+        close(target_probability(100.0, 200.0, -0.01, 0.0), 0.0);
+        let steep = target_probability(1e6, 2e6, -1.0, 1.0);
+        assert!((0.0..=1.0).contains(&steep), "{steep}");
+    }
+
+    #[test]
+    fn unreachable_target_is_one_minus_risk_of_ruin() {
+        let (ev, variance) = (0.02, 1.3);
+        let far = target_probability(100.0, 1e5, ev, variance);
+        close(far, 1.0 - risk_of_ruin(100.0, ev, variance));
+    }
+
+    #[test]
+    fn ramp_units_uses_highest_rung_reached() {
+        let ramp = [
+            RampStep { true_count: 1, units: 2.0 },
+            RampStep { true_count: 3, units: 8.0 },
+            RampStep { true_count: 2, units: 4.0 },
+        ];
+        assert_eq!(ramp_units(&ramp, -2), 2.0);
+        assert_eq!(ramp_units(&ramp, 2), 4.0);
+        assert_eq!(ramp_units(&ramp, 7), 8.0);
+        assert_eq!(ramp_units(&[], 7), 1.0);
+        assert!(validate_ramp(&ramp).is_ok());
+        assert!(validate_ramp(&[ramp[0].clone(), ramp[0].clone()]).is_err());
+    }
+}
@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::sim::CellStats;
+
+const DEALER_COLUMNS: [&str; 10] = ["2", "3", "4", "5", "6", "7", "8", "9", "10", "A"];
+const PAIR_ROWS: [&str; 10] = ["2", "3", "4", "5", "6", "7", "8", "9", "10", "A"];
+
+/// Simulation results laid out on the same grid as the UI strategy chart.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChartOverlay {
+    pub hard: Vec<ChartRow>,
+    pub soft: Vec<ChartRow>,
+    pub pairs: Vec<ChartRow>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChartRow {
+    pub player: String,
+    pub cells: Vec<ChartCell>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChartCell {
+    pub dealer: String,
+    pub hands: u32,
+    pub ev: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub best_action: Option<String>,
+    pub best_action_ev: f64,
+}
+
+#[derive(Default)]
+struct ActionTotals {
+    hands: u32,
+    winnings: f64,
+}
+
+/// Folds cell_stats (keyed by total, upcard, action and count) into chart cells,
+/// summing across counts and picking the action with the highest EV per hand.
+pub fn build_overlay(cell_stats: &HashMap<String, CellStats>) -> ChartOverlay {
+    let mut grid: HashMap<(String, String), HashMap<String, ActionTotals>> = HashMap::new();
+    for cell in cell_stats.values() {
+        let row = chart_row_key(&cell.player_total);
+        let totals = grid
+            .entry((row, cell.dealer_card.clone()))
+            .or_default()
+            .entry(cell.action.clone())
+            .or_default();
+        totals.hands += cell.hands;
+        totals.winnings += cell.total_winnings;
+    }
+
+    let hard = (5..=21).map(|t| build_row(&grid, t.to_string())).collect();
+    let soft = (13..=21).map(|t| build_row(&grid, format!("S{t}"))).collect();
+    let pairs = PAIR_ROWS.iter().map(|p| build_row(&grid, format!("{p},{p}"))).collect();
+
+    ChartOverlay { hard, soft, pairs }
+}
+
+fn build_row(
+    grid: &HashMap<(String, String), HashMap<String, ActionTotals>>,
+    key: String,
+) -> ChartRow {
+    let cells = DEALER_COLUMNS
+        .iter()
+        .map(|dealer| {
+            let mut cell = ChartCell {
+                dealer: dealer.to_string(),
+                hands: 0,
+                ev: 0.0,
+                best_action: None,
+                best_action_ev: 0.0,
+            };
+            let Some(actions) = grid.get(&(key.clone(), dealer.to_string())) else {
+                return cell;
+            };
+            let mut winnings = 0.0;
+            for (action, totals) in actions {
+                if totals.hands == 0 {
+                    continue;
+                }
+                cell.hands += totals.hands;
+                winnings += totals.winnings;
+                let action_ev = totals.winnings / totals.hands as f64;
+                if cell.best_action.is_none() || action_ev > cell.best_action_ev {
+                    cell.best_action = Some(action.clone());
+                    cell.best_action_ev = action_ev;
+                }
+            }
+            if cell.hands > 0 {
+                cell.ev = winnings / cell.hands as f64;
+            }
+            cell
+        })
+        .collect();
+    ChartRow { player: key, cells }
+}
+
+/// Maps a cell_stats player label onto a chart row, folding face-card pairs into 10,10.
+fn chart_row_key(player_total: &str) -> String {
+    if let Some((first, _)) = player_total.split_once(',') {
+        let rank = match first {
+            "J" | "Q" | "K" => "10",
+            other => other,
+        };
+        return format!("{rank},{rank}");
+    }
+    player_total.to_string()
+}
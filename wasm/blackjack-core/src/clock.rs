@@ -0,0 +1,81 @@
+/// Milliseconds since the Unix epoch, from `Date.now()` in the browser.
+#[cfg(target_arch = "wasm32")]
+pub fn now_ms() -> f64 {
+    js_sys::Date::now()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn now_ms() -> f64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0.0, |elapsed| elapsed.as_secs_f64() * 1000.0)
+}
+
+/// Rounds between clock reads when throttling by time.
+const CLOCK_CHECK_ROUNDS: u32 = 64;
+
+/// Decides when a progress callback is due, either every N rounds or at most
+/// once per wall-clock interval. The final round is always reported.
+/// Progress may advance by more than one at a time, e.g. when counted in
+/// hands, so intervals are measured from the last report.
+pub struct ProgressThrottle {
+    every_rounds: u32,
+    every_ms: Option<f64>,
+    last_ms: f64,
+    last_checked: u32,
+    last_reported: u32,
+}
+
+impl ProgressThrottle {
+    pub fn new(every_rounds: u32, every_ms: Option<f64>) -> Self {
+        let every_ms = every_ms.filter(|ms| *ms > 0.0);
+        ProgressThrottle {
+            every_rounds: every_rounds.max(1),
+            every_ms,
+            last_ms: if every_ms.is_some() { now_ms() } else { 0.0 },
+            last_checked: 0,
+            last_reported: 0,
+        }
+    }
+
+    pub fn due(&mut self, completed: u32, total: u32) -> bool {
+        if completed >= total {
+            return true;
+        }
+        match self.every_ms {
+            Some(interval) => {
+                if completed - self.last_checked < CLOCK_CHECK_ROUNDS {
+                    return false;
+                }
+                self.last_checked = completed;
+                let now = now_ms();
+                if now - self.last_ms >= interval {
+                    self.last_ms = now;
+                    true
+                } else {
+                    false
+                }
+            }
+            None => {
+                if completed - self.last_reported < self.every_rounds {
+                    return false;
+                }
+                self.last_reported = completed;
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uneven_steps_still_report_every_interval() {
+        let mut throttle = ProgressThrottle::new(5, None);
+        let reported: Vec<u32> = (1..=12).map(|round| round * 2).filter(|&done| throttle.due(done, 100)).collect();
+        assert_eq!(reported, [6, 12, 18, 24]);
+        assert!(throttle.due(100, 100));
+    }
+}
@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+
+use crate::sim::{CellStats, CountStats};
+
+/// A string column stored as distinct labels plus one code per row.
+pub struct Dictionary {
+    pub labels: Vec<String>,
+    pub codes: Vec<u16>,
+}
+
+impl Dictionary {
+    fn encode<'a>(values: impl Iterator<Item = &'a str>) -> Self {
+        let mut labels: Vec<String> = Vec::new();
+        let mut index: HashMap<&str, u16> = HashMap::new();
+        let mut codes = Vec::new();
+        for value in values {
+            let code = *index.entry(value).or_insert_with(|| {
+                labels.push(value.to_string());
+                (labels.len() - 1) as u16
+            });
+            codes.push(code);
+        }
+        Dictionary { labels, codes }
+    }
+}
+
+/// `cell_stats` as parallel columns, one row per cell, ordered by key.
+pub struct CellColumns {
+    pub player_total: Dictionary,
+    pub dealer_card: Dictionary,
+    pub action: Dictionary,
+    pub count: Vec<i32>,
+    pub hands: Vec<u32>,
+    pub wins: Vec<u32>,
+    pub losses: Vec<u32>,
+    pub pushes: Vec<u32>,
+    pub total_winnings: Vec<f64>,
+    pub total_bet: Vec<f64>,
+    /// NaN where a cell has too few hands for an estimate.
+    pub standard_error: Vec<f64>,
+}
+
+pub fn cell_columns(cell_stats: &HashMap<String, CellStats>) -> CellColumns {
+    let mut keys: Vec<&String> = cell_stats.keys().collect();
+    keys.sort();
+    let cells: Vec<&CellStats> = keys.iter().map(|key| &cell_stats[*key]).collect();
+    CellColumns {
+        player_total: Dictionary::encode(cells.iter().map(|c| c.player_total.as_str())),
+        dealer_card: Dictionary::encode(cells.iter().map(|c| c.dealer_card.as_str())),
+        action: Dictionary::encode(cells.iter().map(|c| c.action.as_str())),
+        count: cells.iter().map(|c| c.count).collect(),
+        hands: cells.iter().map(|c| c.hands).collect(),
+        wins: cells.iter().map(|c| c.wins).collect(),
+        losses: cells.iter().map(|c| c.losses).collect(),
+        pushes: cells.iter().map(|c| c.pushes).collect(),
+        total_winnings: cells.iter().map(|c| c.total_winnings).collect(),
+        total_bet: cells.iter().map(|c| c.total_bet).collect(),
+        standard_error: cells.iter().map(|c| c.standard_error.unwrap_or(f64::NAN)).collect(),
+    }
+}
+
+/// Per-true-count statistics as parallel columns, ordered by true count.
+pub struct CountColumns {
+    pub true_count: Vec<i32>,
+    pub hands: Vec<u32>,
+    pub ev: Vec<f64>,
+    pub advantage: Vec<f64>,
+    pub variance: Vec<f64>,
+}
+
+pub fn count_columns(stats: &CountStats) -> CountColumns {
+    let mut counts: Vec<i32> = stats.hands_by_count.keys().filter_map(|k| k.parse().ok()).collect();
+    counts.sort_unstable();
+    let value = |map: &HashMap<String, f64>, count: i32| map.get(&count.to_string()).copied().unwrap_or(0.0);
+    CountColumns {
+        hands: counts
+            .iter()
+            .map(|c| stats.hands_by_count.get(&c.to_string()).copied().unwrap_or(0))
+            .collect(),
+        ev: counts.iter().map(|&c| value(&stats.ev_by_count, c)).collect(),
+        advantage: counts.iter().map(|&c| value(&stats.advantage_by_count, c)).collect(),
+        variance: counts.iter().map(|&c| value(&stats.variance_by_count, c)).collect(),
+        true_count: counts,
+    }
+}
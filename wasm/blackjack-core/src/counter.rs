@@ -2,6 +2,7 @@ use std::collections::HashMap;
 
 use crate::deck::Card;
 
+#[derive(Clone)]
 pub struct CardCounter {
     running_count: f64,
     values: HashMap<String, i32>,
@@ -22,17 +23,28 @@ impl CardCounter {
     }
 
     pub fn update(&mut self, card: &Card) {
-        let value = self.values.get(&card.rank).copied().unwrap_or(0);
-        self.running_count += value as f64;
+        self.running_count += self.tag(card) as f64;
+    }
+
+    /// Count value the system assigns to `card`.
+    pub fn tag(&self, card: &Card) -> i32 {
+        self.values.get(&card.rank).copied().unwrap_or(0)
     }
 
     pub fn reset(&mut self) {
         self.running_count = 0.0;
     }
 
-    pub fn true_count(&self, remaining_cards: usize, num_decks: u8) -> f64 {
-        let remaining_decks = remaining_cards as f64 / 52.0;
-        let decks = remaining_decks.max(0.5).min(num_decks as f64);
+    pub fn running_count(&self) -> f64 {
+        self.running_count
+    }
+
+    pub fn set_running_count(&mut self, running_count: f64) {
+        self.running_count = running_count;
+    }
+
+    pub fn true_count(&self, decks_remaining: f64, num_decks: u8) -> f64 {
+        let decks = decks_remaining.max(0.5).min(num_decks as f64);
         if decks <= 0.0 {
             0.0
         } else {
@@ -40,8 +52,8 @@ impl CardCounter {
         }
     }
 
-    pub fn count_range(&self, remaining_cards: usize, num_decks: u8) -> i32 {
-        self.true_count(remaining_cards, num_decks).round() as i32
+    pub fn count_range(&self, decks_remaining: f64, num_decks: u8) -> i32 {
+        self.true_count(decks_remaining, num_decks).round() as i32
     }
 }
 
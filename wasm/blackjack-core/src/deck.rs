@@ -1,14 +1,25 @@
-use rand::{rngs::SmallRng, seq::SliceRandom, SeedableRng};
-use serde::Serialize;
+use std::collections::HashMap;
+
+use rand::{rngs::SmallRng, seq::SliceRandom, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+pub const RANKS: [&str; 13] = ["A", "2", "3", "4", "5", "6", "7", "8", "9", "10", "J", "Q", "K"];
 
 #[derive(Clone, Debug, Serialize)]
 pub struct Card {
     pub rank: String,
     pub value: u8,
+    /// Suit index 0-3; only suited bonus payouts and side bets look at it.
+    #[serde(skip)]
+    pub suit: u8,
 }
 
 impl Card {
     pub fn new(rank: &str) -> Self {
+        Card::with_suit(rank, 0)
+    }
+
+    pub fn with_suit(rank: &str, suit: u8) -> Self {
         let value = match rank {
             "A" => 11,
             "J" | "Q" | "K" | "10" => 10,
@@ -17,48 +28,191 @@ impl Card {
         Card {
             rank: rank.to_string(),
             value,
+            suit,
         }
     }
 }
 
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RankCount {
+    pub rank: String,
+    pub count: u32,
+}
+
+/// Snapshot of the undealt portion of the shoe.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShoeComposition {
+    pub total_cards: u32,
+    pub decks_remaining: f64,
+    pub penetration: f64,
+    /// Remaining cards per rank, in `RANKS` order.
+    pub ranks: Vec<RankCount>,
+    /// Remaining ten-value cards (10, J, Q, K) combined.
+    pub ten_value_cards: u32,
+}
+
+/// When the shoe is reshuffled relative to the cut card.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReshufflePolicy {
+    /// Original behavior: shuffle before a round once penetration is reached and
+    /// fewer than one deck remains.
+    #[default]
+    Legacy,
+    /// Shuffle before the next round once the cut card has come out.
+    CutCardEndOfRound,
+    /// Shuffle as soon as the cut card comes out, even in the middle of a round.
+    CutCardImmediate,
+    /// Shuffle after every N rounds regardless of penetration.
+    EveryNRounds(u32),
+}
+
+/// Deliberately skewed shoe for stress-testing strategies and counters. It is
+/// echoed back in the simulation result so the run can be reproduced.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ShoeBias {
+    /// Cards added (positive) or removed (negative) per rank across the whole
+    /// shoe, e.g. `{"A": -8}` for an ace-poor six-deck shoe.
+    #[serde(default)]
+    pub rank_adjustments: HashMap<String, i32>,
+    /// Ten-value cards added (or removed when negative), spread over 10, J, Q and K.
+    #[serde(default)]
+    pub extra_tens: i32,
+    /// Chance (0-1) that each segment is left sorted by value after the
+    /// shuffle, so high and low cards come out in runs.
+    #[serde(default)]
+    pub clumping: f64,
+    /// Cards per clumping segment; one deck when omitted.
+    #[serde(default)]
+    pub segment_size: Option<u32>,
+}
+
+impl ShoeBias {
+    /// Cards of each rank, in `RANKS` order, for a shoe of `num_decks`.
+    fn rank_counts(&self, num_decks: u8) -> Result<[u32; 13], String> {
+        if let Some(rank) = self.rank_adjustments.keys().find(|rank| !RANKS.contains(&rank.as_str())) {
+            return Err(format!("unknown card rank '{rank}' in shoe bias"));
+        }
+        if !(0.0..=1.0).contains(&self.clumping) {
+            return Err(format!("clumping must be between 0 and 1, got {}", self.clumping));
+        }
+        let mut counts = [0u32; 13];
+        for (index, rank) in RANKS.iter().enumerate() {
+            let mut count = num_decks as i32 * 4 + self.rank_adjustments.get(*rank).copied().unwrap_or(0);
+            if index >= 9 {
+                // Ranks 10, J, Q, K share the extra tens; the first ones take any remainder.
+                let slot = (index - 9) as i32;
+                count += self.extra_tens.div_euclid(4) + i32::from(slot < self.extra_tens.rem_euclid(4));
+            }
+            counts[index] = count.max(0) as u32;
+        }
+        let total: u32 = counts.iter().sum();
+        if total < 52 {
+            return Err(format!("a biased shoe needs at least 52 cards, this one has {total}"));
+        }
+        Ok(counts)
+    }
+}
+
+/// Serializable snapshot of a shoe so a later call can keep dealing from it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShoeState {
+    /// Undealt ranks; the next card dealt is the last one.
+    pub cards: Vec<String>,
+    /// Ranks dealt since the last shuffle, in order.
+    pub used_cards: Vec<String>,
+    pub rounds_since_shuffle: u32,
+    /// Seeds the shuffles after the state is restored.
+    pub seed: u32,
+}
+
+#[derive(Clone)]
 pub struct Deck {
     pub num_decks: u8,
     cards: Vec<Card>,
     used_cards: Vec<Card>,
-    penetration_threshold: u8,
-    penetration: f64,
+    penetration_threshold: f64,
+    reshuffle_policy: ReshufflePolicy,
+    rounds_since_shuffle: u32,
     rng: SmallRng,
+    /// Cards in a freshly shuffled shoe per rank, in `RANKS` order.
+    rank_counts: [u32; 13],
+    clumping: f64,
+    segment_size: usize,
 }
 
 impl Deck {
-    pub fn new(num_decks: u8, penetration_threshold: u8, seed: u64) -> Self {
+    pub fn new(num_decks: u8, penetration_threshold: f64, seed: u64) -> Self {
         let mut deck = Deck {
             num_decks,
             cards: Vec::new(),
             used_cards: Vec::new(),
             penetration_threshold,
-            penetration: 0.0,
+            reshuffle_policy: ReshufflePolicy::Legacy,
+            rounds_since_shuffle: 0,
             rng: SmallRng::seed_from_u64(seed),
+            rank_counts: [num_decks as u32 * 4; 13],
+            clumping: 0.0,
+            segment_size: 52,
         };
         deck.shuffle();
         deck
     }
 
     pub fn shuffle(&mut self) {
-        let ranks = ["A", "2", "3", "4", "5", "6", "7", "8", "9", "10", "J", "Q", "K"];
         self.cards.clear();
         self.used_cards.clear();
 
-        for _ in 0..self.num_decks {
-            for rank in &ranks {
-                for _ in 0..4 {
-                    self.cards.push(Card::new(rank));
+        if self.rank_counts == [self.num_decks as u32 * 4; 13] {
+            for _ in 0..self.num_decks {
+                for rank in &RANKS {
+                    for suit in 0..4 {
+                        self.cards.push(Card::with_suit(rank, suit));
+                    }
+                }
+            }
+        } else {
+            for (rank, count) in RANKS.iter().zip(self.rank_counts) {
+                for index in 0..count {
+                    self.cards.push(Card::with_suit(rank, (index % 4) as u8));
                 }
             }
         }
 
         self.cards.shuffle(&mut self.rng);
-        self.penetration = 0.0;
+        if self.clumping > 0.0 {
+            for segment in self.cards.chunks_mut(self.segment_size) {
+                if self.rng.gen::<f64>() < self.clumping {
+                    segment.sort_by_key(|card| card.value);
+                }
+            }
+        }
+        self.rounds_since_shuffle = 0;
+    }
+
+    /// Replaces the shoe composition and clumping, then reshuffles.
+    pub fn set_bias(&mut self, bias: &ShoeBias) -> Result<(), String> {
+        self.rank_counts = bias.rank_counts(self.num_decks)?;
+        self.clumping = bias.clumping;
+        self.segment_size = bias.segment_size.unwrap_or(52).max(2) as usize;
+        self.shuffle();
+        Ok(())
+    }
+
+    pub fn set_reshuffle_policy(&mut self, policy: ReshufflePolicy) {
+        self.reshuffle_policy = policy;
+    }
+
+    pub fn rounds_since_shuffle(&self) -> u32 {
+        self.rounds_since_shuffle
+    }
+
+    /// Marks the start of a new round for round-based reshuffle policies.
+    pub fn start_round(&mut self) {
+        self.rounds_since_shuffle += 1;
     }
 
     pub fn deal_card(&mut self) -> Card {
@@ -67,18 +221,136 @@ impl Deck {
         }
         let card = self.cards.pop().expect("deck should not be empty");
         self.used_cards.push(card.clone());
-        let total_cards = (self.num_decks as usize) * 52;
-        let used = self.used_cards.len();
-        self.penetration = (used as f64 / total_cards as f64) * 100.0;
         card
     }
 
+    pub fn total_cards(&self) -> usize {
+        self.rank_counts.iter().sum::<u32>() as usize
+    }
+
     pub fn remaining_cards(&self) -> usize {
         self.cards.len()
     }
 
+    /// Fraction of the shoe still undealt, in decks (e.g. 2.5 = two and a half decks).
+    pub fn decks_remaining(&self) -> f64 {
+        self.remaining_cards() as f64 / 52.0
+    }
+
+    /// Percentage of the shoe dealt since the last shuffle (0.0 - 100.0).
+    pub fn penetration(&self) -> f64 {
+        let total = self.total_cards();
+        if total == 0 {
+            return 0.0;
+        }
+        (self.used_cards.len() as f64 / total as f64) * 100.0
+    }
+
+    pub fn composition(&self) -> ShoeComposition {
+        let mut counts = [0u32; 13];
+        for card in &self.cards {
+            if let Some(idx) = RANKS.iter().position(|r| *r == card.rank) {
+                counts[idx] += 1;
+            }
+        }
+        let ranks = RANKS
+            .iter()
+            .zip(counts.iter())
+            .map(|(rank, count)| RankCount {
+                rank: rank.to_string(),
+                count: *count,
+            })
+            .collect();
+        ShoeComposition {
+            total_cards: self.cards.len() as u32,
+            decks_remaining: self.decks_remaining(),
+            penetration: self.penetration(),
+            ranks,
+            ten_value_cards: counts[9..].iter().sum(),
+        }
+    }
+
+    fn cut_card_reached(&self) -> bool {
+        self.penetration() >= self.penetration_threshold
+    }
+
+    /// Checked between rounds.
     pub fn should_reshuffle(&self) -> bool {
-        self.penetration >= self.penetration_threshold as f64 && self.cards.len() < 52
+        match self.reshuffle_policy {
+            ReshufflePolicy::Legacy => self.cut_card_reached() && self.cards.len() < 52,
+            ReshufflePolicy::CutCardEndOfRound => self.cut_card_reached(),
+            ReshufflePolicy::CutCardImmediate => self.cut_card_reached(),
+            ReshufflePolicy::EveryNRounds(rounds) => {
+                self.rounds_since_shuffle >= rounds.max(1) || self.cards.len() < 52
+            }
+        }
+    }
+
+    /// Checked before every card, for policies that shuffle mid-round.
+    pub fn should_reshuffle_now(&self) -> bool {
+        self.cards.is_empty()
+            || (self.reshuffle_policy == ReshufflePolicy::CutCardImmediate && self.cut_card_reached())
+    }
+
+    /// Captures the shoe. The RNG cannot be serialized, so a fresh seed is drawn
+    /// from it for whoever restores the state.
+    pub fn save_state(&mut self) -> ShoeState {
+        ShoeState {
+            cards: self.cards.iter().map(|c| c.rank.clone()).collect(),
+            used_cards: self.used_cards.iter().map(|c| c.rank.clone()).collect(),
+            rounds_since_shuffle: self.rounds_since_shuffle,
+            seed: self.rng.gen(),
+        }
+    }
+
+    pub fn restore_state(&mut self, state: &ShoeState) -> Result<(), String> {
+        if let Some(rank) = state
+            .cards
+            .iter()
+            .chain(&state.used_cards)
+            .find(|rank| !RANKS.contains(&rank.as_str()))
+        {
+            return Err(format!("unknown card rank '{rank}' in shoe state"));
+        }
+        if state.cards.len() + state.used_cards.len() != self.total_cards() {
+            return Err(format!(
+                "shoe state holds {} cards but a {}-deck shoe has {}",
+                state.cards.len() + state.used_cards.len(),
+                self.num_decks,
+                self.total_cards()
+            ));
+        }
+        let mut held = [0u32; 13];
+        for rank in state.cards.iter().chain(&state.used_cards) {
+            held[RANKS.iter().position(|r| r == rank).unwrap_or(0)] += 1;
+        }
+        if let Some(index) = (0..RANKS.len()).find(|&index| held[index] != self.rank_counts[index]) {
+            return Err(format!(
+                "shoe state holds {} of rank '{}' but this shoe has {}",
+                held[index], RANKS[index], self.rank_counts[index]
+            ));
+        }
+        // Suits are not saved; deal them out per rank as a fresh shoe would.
+        let mut dealt = [0usize; 13];
+        let mut card = |rank: &String| {
+            let index = RANKS.iter().position(|r| r == rank).unwrap_or(0);
+            dealt[index] += 1;
+            Card::with_suit(rank, ((dealt[index] - 1) % 4) as u8)
+        };
+        self.cards = state.cards.iter().map(&mut card).collect();
+        self.used_cards = state.used_cards.iter().map(&mut card).collect();
+        self.rounds_since_shuffle = state.rounds_since_shuffle;
+        self.rng = SmallRng::seed_from_u64(state.seed as u64);
+        Ok(())
+    }
+
+    /// Reshuffles the undealt cards, e.g. after removing some of them by rank.
+    pub fn shuffle_remaining(&mut self) {
+        self.cards.shuffle(&mut self.rng);
+    }
+
+    pub fn has_rank(&self, rank: &str) -> bool {
+        self.cards.iter().any(|c| c.rank == rank)
     }
 
     pub fn remove_card_by_rank(&mut self, rank: &str) -> bool {
@@ -89,4 +361,13 @@ impl Deck {
             false
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+impl Deck {
+    /// A six-deck shoe that deals `ranks` first, in order.
+    pub(crate) fn stacked(ranks: &[&str]) -> Deck {
+        let mut deck = Deck::new(6, 75.0, 0);
+        deck.cards.extend(ranks.iter().rev().map(|rank| Card::new(rank)));
+        deck
+    }
+}
@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{counter::CardCounter, deck::Deck};
+
+fn default_drill_decks() -> u8 {
+    1
+}
+
+fn default_drill_cards() -> u32 {
+    20
+}
+
+/// Most candidate mistakes reported for one drill.
+const MAX_LIKELY_MISTAKES: usize = 5;
+
+#[derive(Debug, Deserialize)]
+pub struct CountDrillInput {
+    #[serde(default = "default_drill_decks")]
+    pub num_decks: u8,
+    /// Cards dealt in the burst, capped at the shoe size.
+    #[serde(default = "default_drill_cards")]
+    pub cards: u32,
+    pub seed: u64,
+    #[serde(default)]
+    pub system: Option<String>,
+    #[serde(default)]
+    pub custom_values: Option<HashMap<String, i32>>,
+    /// The user's running count after the burst; omit to just deal the cards.
+    #[serde(default)]
+    pub user_running_count: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DrillCard {
+    pub rank: String,
+    pub tag: i32,
+    /// Running count after this card.
+    pub running_count: i32,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DrillMistake {
+    pub card_index: usize,
+    pub rank: String,
+    /// "missed", "double_counted" or "wrong_sign".
+    pub kind: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CountDrillResult {
+    pub cards: Vec<DrillCard>,
+    pub running_count: i32,
+    pub true_count: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_running_count: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correct: Option<bool>,
+    /// User count minus the correct count.
+    pub difference: i32,
+    /// Single-card slips that would explain the difference, earliest first.
+    pub likely_mistakes: Vec<DrillMistake>,
+}
+
+pub fn run_count_drill(input: CountDrillInput) -> Result<CountDrillResult, String> {
+    if input.num_decks == 0 {
+        return Err("drill needs at least one deck".to_string());
+    }
+    let mut deck = Deck::new(input.num_decks, 100.0, input.seed);
+    let mut counter = CardCounter::new(input.system.clone(), input.custom_values.clone());
+    let burst = (input.cards as usize).min(deck.total_cards());
+
+    let mut cards = Vec::with_capacity(burst);
+    for _ in 0..burst {
+        let card = deck.deal_card();
+        let tag = counter.tag(&card);
+        counter.update(&card);
+        cards.push(DrillCard {
+            rank: card.rank,
+            tag,
+            running_count: counter.running_count() as i32,
+        });
+    }
+
+    let running_count = counter.running_count() as i32;
+    let difference = input.user_running_count.map_or(0, |user| user - running_count);
+    let likely_mistakes = if difference == 0 {
+        Vec::new()
+    } else {
+        cards
+            .iter()
+            .enumerate()
+            .filter(|(_, card)| card.tag != 0)
+            .filter_map(|(index, card)| {
+                let kind = if difference == -card.tag {
+                    "missed"
+                } else if difference == card.tag {
+                    "double_counted"
+                } else if difference == -2 * card.tag {
+                    "wrong_sign"
+                } else {
+                    return None;
+                };
+                Some(DrillMistake {
+                    card_index: index,
+                    rank: card.rank.clone(),
+                    kind,
+                })
+            })
+            .take(MAX_LIKELY_MISTAKES)
+            .collect()
+    };
+
+    Ok(CountDrillResult {
+        cards,
+        running_count,
+        true_count: counter.true_count(deck.decks_remaining(), deck.num_decks),
+        user_running_count: input.user_running_count,
+        correct: input.user_running_count.map(|user| user == running_count),
+        difference,
+        likely_mistakes,
+    })
+}
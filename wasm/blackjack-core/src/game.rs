@@ -1,55 +1,568 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     counter::CardCounter,
-    deck::{Card, Deck},
-    strategy::{Action, Strategy},
+    deck::{Card, Deck, ShoeComposition, ShoeState},
+    strategy::{Action, ChartCell, ChartSource, Strategy},
 };
 
-#[derive(Clone)]
+/// Strategy column Pontoon decisions are read from, as the dealer shows no
+/// card.
+pub const PONTOON_DEALER_COLUMN: &str = "hidden";
+
+#[derive(Clone, Serialize)]
 pub struct GameRules {
-    pub dealer_hits_soft_17: bool,
-    pub dealer_stands_on: String,
+    pub variant: Variant,
+    pub dealer_rule: DealerRule,
     pub double_after_split: bool,
+    /// Doubling on hands formed by splitting aces, separate from `double_after_split`.
+    pub double_split_aces: bool,
+    /// Hands formed by splitting aces play on like any other hand; otherwise
+    /// each takes one card and stands, unless it can be resplit.
+    pub hit_split_aces: bool,
+    /// Only cards of the same rank form a pair, so K-10 is a hard 20.
+    pub split_identical_ranks: bool,
+    /// Two-card totals a hand may double on; a disallowed double is hit.
+    pub double_on: DoubleOn,
+    /// Doubling stays open after hitting, on any number of cards, as in
+    /// Spanish 21.
+    pub double_after_hit: bool,
+    /// Largest multiple of the bet a two-card double may put in play: 2, or
+    /// 3 and 4 for Power Blackjack's triple and quadruple downs.
+    pub max_double_multiplier: u8,
+    /// Spanish 21's double down rescue: a doubled hand may be given up, the
+    /// double handed back and only the bet before it lost.
+    pub double_rescue: bool,
+    /// A hand reaching this many cards without busting wins at once, unless
+    /// the dealer has blackjack.
+    pub charlie: Option<u8>,
+    /// A dealer 22 pushes every hand still standing instead of busting, as
+    /// in Blackjack Switch and Free Bet.
+    pub push_22: bool,
+    /// Double Exposure: both dealer cards are dealt face up, so a dealer
+    /// blackjack is known at once and nothing is insured. The dealer wins
+    /// ties, except that a player blackjack always wins.
+    pub double_exposure: bool,
+    /// Ties the dealer takes outright, as in carnival and charity games.
+    pub dealer_wins_ties: DealerWinsTies,
     pub allow_resplit: bool,
+    #[serde(rename = "resplit_aces")]
     pub _resplit_aces: bool,
-    pub blackjack_pays: String,
+    /// Most hands a player may split into; unlimited when `None`.
+    pub max_split_hands: Option<u8>,
+    /// Same for splitting aces, e.g. 2 to split aces only once.
+    pub max_split_ace_hands: Option<u8>,
+    pub blackjack_pays: BlackjackPayout,
+    /// Surrender half the bet on the first two cards once the dealer has
+    /// checked for blackjack.
+    pub late_surrender: bool,
+    /// Surrender half the bet before the dealer checks for blackjack, so it
+    /// also saves half against a dealer natural, aces included.
+    pub early_surrender: bool,
+    /// A dealer blackjack only takes the original bet; money added by doubles
+    /// and splits is returned.
+    pub original_bets_only: bool,
+    /// The dealer checks for blackjack under an ace or a ten before the
+    /// players act; without a peek, doubles and splits are at risk.
+    pub peek_aces: bool,
+    pub peek_tens: bool,
+    /// An ace and a ten on a hand formed by a split pays as a natural rather
+    /// than counting as an ordinary 21.
+    pub split_blackjack_pays: bool,
+    pub hand_bonuses: HandBonuses,
+    /// Insurance is offered on hands other than a blackjack when the dealer
+    /// shows an ace: half the bet, paying 2:1 if the dealer has blackjack.
+    pub insurance: bool,
 }
 
-#[derive(Clone, Debug, Serialize)]
+/// How the dealer draws. Accepts "S17"/"17s", "H17"/"17h" or a total
+/// (number or string) at which the dealer stands on hard and soft hands,
+/// e.g. 18 to hit to 18.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "DealerRuleSetting", into = "DealerRuleSetting")]
+pub enum DealerRule {
+    S17,
+    H17,
+    Total(u8),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum DealerRuleSetting {
+    Total(u8),
+    Name(String),
+}
+
+impl DealerRule {
+    fn total(total: u8) -> Result<Self, String> {
+        if (12..=21).contains(&total) {
+            Ok(DealerRule::Total(total))
+        } else {
+            Err(format!("dealer_rule total must be between 12 and 21, got {total}"))
+        }
+    }
+
+    /// Whether the dealer draws to a soft 17.
+    pub fn hits_soft_17(self) -> bool {
+        match self {
+            DealerRule::S17 => false,
+            DealerRule::H17 => true,
+            DealerRule::Total(total) => total > 17,
+        }
+    }
+
+    /// Total the dealer stands on for a hand at `value`.
+    fn stand_value(self, value: u8, is_soft: bool) -> u8 {
+        match self {
+            DealerRule::H17 if is_soft && value == 17 => 18,
+            DealerRule::S17 | DealerRule::H17 => 17,
+            DealerRule::Total(total) => total,
+        }
+    }
+}
+
+impl From<DealerRule> for DealerRuleSetting {
+    fn from(rule: DealerRule) -> Self {
+        match rule {
+            DealerRule::S17 => DealerRuleSetting::Name("S17".to_string()),
+            DealerRule::H17 => DealerRuleSetting::Name("H17".to_string()),
+            DealerRule::Total(total) => DealerRuleSetting::Total(total),
+        }
+    }
+}
+
+impl TryFrom<DealerRuleSetting> for DealerRule {
+    type Error = String;
+
+    fn try_from(setting: DealerRuleSetting) -> Result<Self, String> {
+        let name = match setting {
+            DealerRuleSetting::Total(total) => return DealerRule::total(total),
+            DealerRuleSetting::Name(name) => name,
+        };
+        match name.trim().to_ascii_lowercase().as_str() {
+            "s17" | "17s" => Ok(DealerRule::S17),
+            "h17" | "17h" => Ok(DealerRule::H17),
+            other => other
+                .parse::<u8>()
+                .map_err(|_| format!("unknown dealer_rule '{name}', expected S17, H17 or a total"))
+                .and_then(DealerRule::total),
+        }
+    }
+}
+
+/// What a natural pays per unit bet. Accepts a ratio string ("3:2", "7/5"),
+/// a number (1.5) or `{"numerator": 3, "denominator": 2}`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "PayoutSetting")]
+pub struct BlackjackPayout(pub f64);
+
+impl Default for BlackjackPayout {
+    fn default() -> Self {
+        BlackjackPayout(1.5)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PayoutSetting {
+    Ratio(f64),
+    Fraction { numerator: f64, denominator: f64 },
+    Text(String),
+}
+
+impl TryFrom<PayoutSetting> for BlackjackPayout {
+    type Error = String;
+
+    fn try_from(setting: PayoutSetting) -> Result<Self, String> {
+        let (numerator, denominator) = match setting {
+            PayoutSetting::Ratio(ratio) => (ratio, 1.0),
+            PayoutSetting::Fraction { numerator, denominator } => (numerator, denominator),
+            PayoutSetting::Text(text) => {
+                let parse = |part: &str| part.trim().parse::<f64>().ok();
+                text.split_once([':', '/'])
+                    .and_then(|(numerator, denominator)| Some((parse(numerator)?, parse(denominator)?)))
+                    .or_else(|| Some((parse(&text)?, 1.0)))
+                    .ok_or_else(|| format!("unknown blackjack_pays '{text}', expected a ratio like 3:2"))?
+            }
+        };
+        let ratio = numerator / denominator;
+        // Anything past 10:1 is far more likely a typo than a real promotion.
+        if !ratio.is_finite() || ratio <= 0.0 || ratio > 10.0 {
+            return Err(format!(
+                "blackjack_pays must be a positive ratio no larger than 10:1, got {numerator}:{denominator}"
+            ));
+        }
+        Ok(BlackjackPayout(ratio))
+    }
+}
+
+/// Game the rules describe. Pontoon is a profile of its own: both dealer
+/// cards stay hidden though the dealer still checks for pontoon, players must
+/// draw to 15, a five-card trick pays 2:1 and the dealer wins every tie,
+/// pontoon against pontoon included. Zappit lets a hard 15-17 on the first
+/// two cards be zapped for two new cards.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Variant {
+    #[default]
+    Blackjack,
+    Pontoon,
+    Zappit,
+}
+
+/// Which hands may double: "any", "9-11" or "10-11". The restricted forms
+/// are hard totals only.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum DoubleOn {
+    #[default]
+    #[serde(rename = "any")]
+    Any,
+    #[serde(rename = "9-11")]
+    NineToEleven,
+    #[serde(rename = "10-11")]
+    TenToEleven,
+}
+
+impl DoubleOn {
+    pub fn allows(self, cards: &[Card]) -> bool {
+        let lowest = match self {
+            DoubleOn::Any => return true,
+            DoubleOn::NineToEleven => 9,
+            DoubleOn::TenToEleven => 10,
+        };
+        let hard: u8 = cards.iter().map(|card| if card.rank == "A" { 1 } else { card.value }).sum();
+        let soft = cards.iter().any(|card| card.rank == "A") && hard + 10 <= 21;
+        !soft && (lowest..=11).contains(&hard)
+    }
+}
+
+/// Ties the dealer wins: `true` for every tie, or a list of totals such as
+/// `[17, 18, 19]`. A player blackjack is settled as usual.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DealerWinsTies {
+    All(bool),
+    Totals(Vec<u8>),
+}
+
+impl Default for DealerWinsTies {
+    fn default() -> Self {
+        DealerWinsTies::All(false)
+    }
+}
+
+impl DealerWinsTies {
+    pub fn covers(&self, total: u8) -> bool {
+        match self {
+            DealerWinsTies::All(all) => *all,
+            DealerWinsTies::Totals(totals) => totals.contains(&total),
+        }
+    }
+}
+
+/// Extra payouts for three-card 21s of certain compositions, as in Spanish 21
+/// and promotional games. A bonus is paid per unit bet in place of even money,
+/// whatever the dealer ends with; doubled hands are paid as ordinary wins.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct HandBonuses {
+    #[serde(default)]
+    pub six_seven_eight: Option<f64>,
+    /// All one suit; falls back to `six_seven_eight` when omitted.
+    #[serde(default)]
+    pub six_seven_eight_suited: Option<f64>,
+    #[serde(default)]
+    pub seven_seven_seven: Option<f64>,
+    #[serde(default)]
+    pub seven_seven_seven_suited: Option<f64>,
+    /// Further bonus hands, checked in order after the ones above.
+    #[serde(default)]
+    pub hands: Vec<BonusHand>,
+}
+
+/// A bonus hand of your own, e.g. `{"total": 21, "min_cards": 5, "pays": 1.5}`
+/// or `{"ranks": ["6", "7", "8"], "suited": true, "pays": 2}`. Every condition
+/// given must hold of the final hand.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BonusHand {
+    /// The hand's exact ranks, in any order.
+    #[serde(default)]
+    pub ranks: Option<Vec<String>>,
+    #[serde(default)]
+    pub suited: bool,
+    #[serde(default)]
+    pub total: Option<u8>,
+    #[serde(default)]
+    pub min_cards: Option<u8>,
+    pub pays: f64,
+}
+
+impl BonusHand {
+    pub fn has_condition(&self) -> bool {
+        self.ranks.is_some() || self.suited || self.total.is_some() || self.min_cards.is_some()
+    }
+
+    fn matches(&self, cards: &[Card], total: u8) -> bool {
+        let ranks_match = self.ranks.as_ref().is_none_or(|ranks| {
+            let mut wanted: Vec<&str> = ranks.iter().map(String::as_str).collect();
+            let mut held: Vec<&str> = cards.iter().map(|card| card.rank.as_str()).collect();
+            wanted.sort_unstable();
+            held.sort_unstable();
+            wanted == held
+        });
+        ranks_match
+            && (!self.suited || cards.iter().all(|card| card.suit == cards[0].suit))
+            && self.total.is_none_or(|wanted| total == wanted)
+            && self.min_cards.is_none_or(|min| cards.len() >= min as usize)
+    }
+}
+
+impl GameRules {
+    pub fn pontoon(&self) -> bool {
+        self.variant == Variant::Pontoon
+    }
+
+    /// Whether a tie on `total` goes to the dealer.
+    pub fn dealer_wins_tie(&self, total: u8) -> bool {
+        self.double_exposure || self.pontoon() || self.dealer_wins_ties.covers(total)
+    }
+
+    /// Won per unit bet by an N-card Charlie; Pontoon's five-card trick pays 2:1.
+    pub fn charlie_payout(&self) -> f64 {
+        if self.pontoon() {
+            2.0
+        } else {
+            1.0
+        }
+    }
+
+    /// Whether `cards`, a spot's opening hand, may be zapped. With only two
+    /// cards, any ace makes the hand soft.
+    pub fn zap_allowed(&self, cards: &[Card]) -> bool {
+        let total: u8 = cards.iter().map(|card| card.value).sum();
+        self.variant == Variant::Zappit
+            && cards.len() == 2
+            && cards.iter().all(|card| card.rank != "A")
+            && (15..=17).contains(&total)
+    }
+
+    /// `action` with a power double cut to what the rules allow on a hand of
+    /// `cards` cards; past the first two only a plain double is possible.
+    pub fn limit_double(&self, action: Action, cards: usize) -> Action {
+        let Some(multiplier) = action.double_multiplier() else {
+            return action;
+        };
+        let limit = if cards == 2 { self.max_double_multiplier } else { 2 };
+        Action::double_of(multiplier.min(limit))
+    }
+
+    /// Whether a hand split from `split_card` may double. Split aces also
+    /// need to be played on, since a one-card ace never gets the chance.
+    pub fn doubles_after_split(&self, split_card: &Card) -> bool {
+        if split_card.rank == "A" {
+            self.double_split_aces && self.hit_split_aces
+        } else {
+            self.double_after_split
+        }
+    }
+
+    pub fn surrender_allowed(&self) -> bool {
+        self.late_surrender || self.early_surrender
+    }
+
+    /// Two cards that may be split: equal in value, and in rank too under
+    /// `split_identical_ranks`.
+    pub fn is_pair(&self, cards: &[Card]) -> bool {
+        cards.len() == 2
+            && cards[0].value == cards[1].value
+            && (!self.split_identical_ranks || cards[0].rank == cards[1].rank)
+    }
+
+    /// Whether a spot already holding `hands` hands may split (or resplit) again.
+    pub fn split_allowed(&self, hands: usize, aces: bool) -> bool {
+        let limit = if aces {
+            self.max_split_ace_hands.or(self.max_split_hands)
+        } else {
+            self.max_split_hands
+        };
+        limit.is_none_or(|limit| hands < limit as usize)
+    }
+
+    /// Amount a natural wins per unit bet.
+    pub fn blackjack_payout(&self) -> f64 {
+        self.blackjack_pays.0
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
 pub struct HandRecord {
     pub cards: Vec<Card>,
     pub bet: f64,
+    /// Part of `bet` that is the round's original wager: 1 on the first hand,
+    /// 0 on hands created by splitting. Doubles only add to `bet`.
+    pub original_bet: f64,
     pub result: Option<String>,
+    /// Net result of this hand in currency, filled in at settlement.
+    pub winnings: f64,
+    pub doubled: bool,
+    /// Strategy wanted to double but the rules did not allow it.
+    pub double_blocked: bool,
+    /// Strategy label of the hand when it doubled (or was blocked from doubling).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub double_label: Option<String>,
+    /// Paid a hand bonus instead of its ordinary result.
+    pub bonus: bool,
+    /// Given up after doubling under double down rescue.
+    pub rescued: bool,
+}
+
+/// A decision where the count tables overrode the basic chart.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexPlay {
+    pub player: String,
+    pub dealer: String,
+    pub true_count: i32,
+    pub action: Action,
+    pub basic_action: Action,
 }
 
 #[derive(Debug, Serialize)]
 pub struct GameResult {
+    /// "win", "lose" or "push"; "blackjack" for a dealt natural and
+    /// "charlie" for a win with an N-card Charlie.
     pub outcome: String,
     pub winnings: f64,
     pub bet: f64,
+    /// Bet size for one hand of the round, before doubles and splits.
+    pub base_bet: f64,
     pub player_cards: Vec<Card>,
+    /// First card the player drew after the deal, to whichever hand.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub third_card: Option<Card>,
     pub dealer_cards: Vec<Card>,
     pub dealer_up_card: Card,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub initial_action: Option<Action>,
     pub hands: Vec<HandRecord>,
+    /// Decisions where doubling was permitted.
+    pub doubles_offered: u32,
+    /// Split actions taken this round, including resplits.
+    pub splits: u32,
+    /// Pairs the chart wanted to resplit but the rules did not allow.
+    pub resplits_blocked: u32,
+    /// Running count before the first card of the round was dealt.
+    pub running_count: f64,
+    /// True count before the first card of the round was dealt.
+    pub true_count: f64,
+    /// Decks left in the shoe before the first card of the round was dealt.
+    pub decks_remaining: f64,
+    /// Undealt shoe after the round; only populated for interactive single-game calls.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shoe_composition: Option<ShoeComposition>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub index_plays: Vec<IndexPlay>,
+    /// Strategy cells the decisions were read from: every decision when chart
+    /// tracing is on, otherwise only those that fell back to `default_action`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub chart_cells: Vec<ChartCell>,
+    /// Whether even money was taken, when a blackjack faced a dealer ace.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub even_money: Option<bool>,
+    /// Whether insurance was taken, when it was offered. Its stake and result
+    /// are part of `bet` and `winnings`, but not of `outcome`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub insurance: Option<bool>,
+}
+
+/// Emitted every time the shoe is reshuffled, just before the counter is reset.
+#[derive(Clone, Debug)]
+pub struct ShoeBoundary {
+    pub shoe_index: u32,
+    pub rounds_played: u32,
+    pub cards_dealt: u32,
+    pub final_running_count: f64,
+}
+
+pub type ShoeHook = Box<dyn FnMut(&ShoeBoundary)>;
+
+/// Shoe and count carried between `play_single_game` calls.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionState {
+    pub shoe: ShoeState,
+    pub running_count: f64,
+    pub shoe_index: u32,
 }
 
 pub struct BlackjackGame {
     pub deck: Deck,
     pub rules: GameRules,
     pub counter: Option<CardCounter>,
+    shoe_index: u32,
+    shoe_hook: Option<ShoeHook>,
+    trace_chart: bool,
 }
 
 impl BlackjackGame {
     pub fn new(deck: Deck, rules: GameRules, counter: Option<CardCounter>) -> Self {
-        BlackjackGame { deck, rules, counter }
+        BlackjackGame {
+            deck,
+            rules,
+            counter,
+            shoe_index: 0,
+            shoe_hook: None,
+            trace_chart: false,
+        }
+    }
+
+    /// Copy of the table state for replaying a round; the shoe hook is not carried over.
+    pub fn snapshot(&self) -> BlackjackGame {
+        BlackjackGame {
+            deck: self.deck.clone(),
+            rules: self.rules.clone(),
+            counter: self.counter.clone(),
+            shoe_index: self.shoe_index,
+            shoe_hook: None,
+            trace_chart: false,
+        }
+    }
+
+    pub fn save_session(&mut self) -> SessionState {
+        SessionState {
+            shoe: self.deck.save_state(),
+            running_count: self.counter.as_ref().map_or(0.0, |c| c.running_count()),
+            shoe_index: self.shoe_index,
+        }
+    }
+
+    pub fn restore_session(&mut self, session: &SessionState) -> Result<(), String> {
+        self.deck.restore_state(&session.shoe)?;
+        if let Some(counter) = &mut self.counter {
+            counter.set_running_count(session.running_count);
+        }
+        self.shoe_index = session.shoe_index;
+        Ok(())
+    }
+
+    /// Records the strategy cell behind every decision in `GameResult::chart_cells`.
+    pub fn set_chart_tracing(&mut self, enabled: bool) {
+        self.trace_chart = enabled;
+    }
+
+    /// Registers a callback fired at every shoe boundary so external
+    /// accumulators can stay in sync with the counter reset.
+    pub fn set_shoe_hook(&mut self, hook: ShoeHook) {
+        self.shoe_hook = Some(hook);
+    }
+
+    /// Shoes reshuffled so far; changes whenever a new shoe is started.
+    pub fn shoe_index(&self) -> u32 {
+        self.shoe_index
     }
 
     pub fn get_true_count(&self) -> f64 {
         if let Some(counter) = &self.counter {
-            counter.true_count(self.deck.remaining_cards(), self.deck.num_decks)
+            counter.true_count(self.deck.decks_remaining(), self.deck.num_decks)
         } else {
             0.0
         }
@@ -57,13 +570,46 @@ impl BlackjackGame {
 
     pub fn count_range(&self) -> i32 {
         if let Some(counter) = &self.counter {
-            counter.count_range(self.deck.remaining_cards(), self.deck.num_decks)
+            counter.count_range(self.deck.decks_remaining(), self.deck.num_decks)
         } else {
             0
         }
     }
 
+    pub fn shoe_composition(&self) -> ShoeComposition {
+        self.deck.composition()
+    }
+
+    /// Shuffles the shoe and resets the running count.
+    pub fn reshuffle(&mut self) {
+        if let Some(hook) = &mut self.shoe_hook {
+            let boundary = ShoeBoundary {
+                shoe_index: self.shoe_index,
+                rounds_played: self.deck.rounds_since_shuffle(),
+                cards_dealt: (self.deck.total_cards() - self.deck.remaining_cards()) as u32,
+                final_running_count: self.counter.as_ref().map_or(0.0, |c| c.running_count()),
+            };
+            hook(&boundary);
+        }
+        self.shoe_index += 1;
+        self.deck.shuffle();
+        if let Some(counter) = &mut self.counter {
+            counter.reset();
+        }
+    }
+
+    /// Reshuffles between rounds if the policy calls for it. Call before reading
+    /// the pre-deal count so it reflects the shoe the round is dealt from.
+    pub fn prepare_round(&mut self) {
+        if self.deck.should_reshuffle() {
+            self.reshuffle();
+        }
+    }
+
     pub fn deal_card(&mut self) -> Card {
+        if self.deck.should_reshuffle_now() {
+            self.reshuffle();
+        }
         let card = self.deck.deal_card();
         if let Some(counter) = &mut self.counter {
             counter.update(&card);
@@ -71,6 +617,19 @@ impl BlackjackGame {
         card
     }
 
+    /// True count from the cards the player has seen, i.e. without the
+    /// dealer's hole card.
+    fn visible_count(&self, hole_card: &Card) -> i32 {
+        match &self.counter {
+            Some(counter) => {
+                let mut seen = counter.clone();
+                seen.set_running_count(counter.running_count() - counter.tag(hole_card) as f64);
+                seen.count_range(self.deck.decks_remaining(), self.deck.num_decks)
+            }
+            None => 0,
+        }
+    }
+
     pub fn calculate_hand_value(&self, cards: &[Card]) -> (u8, bool) {
         let mut value = 0;
         let mut aces = 0;
@@ -93,8 +652,14 @@ impl BlackjackGame {
         cards.len() == 2 && self.calculate_hand_value(cards).0 == 21
     }
 
+    /// Whether `cards` make an N-card Charlie under the rules.
+    pub fn is_charlie(&self, cards: &[Card]) -> bool {
+        self.rules.charlie.is_some_and(|charlie| cards.len() >= charlie as usize)
+            && self.calculate_hand_value(cards).0 <= 21
+    }
+
     pub fn can_split(&self, cards: &[Card]) -> bool {
-        cards.len() == 2 && cards[0].value == cards[1].value
+        self.rules.is_pair(cards)
     }
 
     pub fn play_dealer(&mut self, dealer_cards: &[Card]) -> Vec<Card> {
@@ -104,17 +669,7 @@ impl BlackjackGame {
             if value > 21 {
                 break;
             }
-            let stand_value = match self.rules.dealer_stands_on.as_str() {
-                "17s" => 17,
-                _ => {
-                    if self.rules.dealer_hits_soft_17 && is_soft && value == 17 {
-                        18
-                    } else {
-                        17
-                    }
-                }
-            };
-            if value >= stand_value {
+            if value >= self.rules.dealer_rule.stand_value(value, is_soft) {
                 break;
             }
             hand.push(self.deal_card());
@@ -130,22 +685,24 @@ impl BlackjackGame {
         }
     }
 
-    fn get_initial_action(&self, initial_cards: &[Card], hands: &[HandRecord]) -> Action {
-        if hands.len() > 1 {
-            return Action::Split;
+    /// Dealer column decisions are read from: the up card, or under Double
+    /// Exposure the dealer's two-card total ("H12", "S17") when the strategy
+    /// has a column for it.
+    fn dealer_label(&self, strategy: &Strategy, dealer_cards: &[Card]) -> String {
+        if self.rules.pontoon() {
+            return PONTOON_DEALER_COLUMN.to_string();
         }
-        if let Some(first_hand) = hands.first() {
-            if first_hand.cards.len() == 3 && initial_cards.len() == 2 {
-                return Action::Double;
-            }
-            if first_hand.cards.len() > initial_cards.len() {
-                return Action::Hit;
+        if self.rules.double_exposure {
+            let (value, soft) = self.calculate_hand_value(dealer_cards);
+            let label = format!("{}{value}", if soft { 'S' } else { 'H' });
+            if strategy.has_dealer_column(&label) {
+                return label;
             }
         }
-        Action::Stand
+        Self::dealer_card_value(&dealer_cards[0])
     }
 
-    fn strategy_pair_label(cards: &[Card]) -> Option<String> {
+    pub fn strategy_pair_label(cards: &[Card]) -> Option<String> {
         if cards.len() != 2 {
             return None;
         }
@@ -163,77 +720,353 @@ impl BlackjackGame {
     }
 
     pub fn play_game(&mut self, strategy: &Strategy, bet_size: f64) -> GameResult {
-        if self.deck.should_reshuffle() {
-            self.deck.shuffle();
-            if let Some(counter) = &mut self.counter {
-                counter.reset();
-            }
-        }
+        self.play_round(strategy, &[bet_size])
+            .pop()
+            .expect("a single-spot round yields one result")
+    }
+
+    /// Plays one round with a spot per entry in `bets`. Each spot is dealt two
+    /// cards in seat order before the dealer, spots play out in seat order, and
+    /// the dealer plays once for the whole table. A blackjack found by the
+    /// dealer's peek ends the round before anyone acts.
+    pub fn play_round(&mut self, strategy: &Strategy, bets: &[f64]) -> Vec<GameResult> {
+        self.prepare_round();
+        self.deck.start_round();
+        let running_count = self.counter.as_ref().map_or(0.0, |c| c.running_count());
+        let true_count = self.get_true_count();
+        let decks_remaining = self.deck.decks_remaining();
 
-        let player_cards = vec![self.deal_card(), self.deal_card()];
+        let spot_cards: Vec<Vec<Card>> = bets
+            .iter()
+            .map(|_| vec![self.deal_card(), self.deal_card()])
+            .collect();
         let dealer_cards = vec![self.deal_card(), self.deal_card()];
         let dealer_up = dealer_cards[0].clone();
 
-        // Check for player blackjack immediately (known after dealing)
-        // If player has blackjack, treat it as Stand (no decision category needed)
-        if self.is_blackjack(&player_cards) {
-            // Check if dealer also has blackjack
-            if self.is_blackjack(&dealer_cards) {
-                return GameResult {
-                    outcome: "push".to_string(),
-                    winnings: 0.0,
-                    bet: bet_size,
-                    player_cards: player_cards.clone(),
-                    dealer_cards: dealer_cards.clone(),
-                    dealer_up_card: dealer_up,
-                    initial_action: Some(Action::Stand), // Count as Stand
-                    hands: vec![HandRecord { cards: player_cards, bet: 1.0, result: None }],
-                };
+        let dealer_has_blackjack = self.is_blackjack(&dealer_cards);
+        let dealer_peeks = self.rules.double_exposure
+            || self.rules.pontoon()
+            || match dealer_up.value {
+                11 => self.rules.peek_aces,
+                10 => self.rules.peek_tens,
+                _ => false,
+            };
+
+        // Player blackjacks are known after dealing and only face the even-money
+        // offer; everyone else may insure, then plays unless the peek already
+        // ended the round.
+        let mut plays = Vec::with_capacity(bets.len());
+        let mut even_money = Vec::with_capacity(bets.len());
+        let mut insurance = Vec::with_capacity(bets.len());
+        // Both offers need an ace showing and a hole card still unknown.
+        let ace_offer = dealer_up.rank == "A" && !self.rules.double_exposure && !self.rules.pontoon();
+        for player_cards in &spot_cards {
+            let blackjack = self.is_blackjack(player_cards);
+            even_money.push(
+                (blackjack && ace_offer)
+                    .then(|| strategy.takes_even_money(self.visible_count(&dealer_cards[1]))),
+            );
+            insurance.push(
+                (!blackjack && ace_offer && self.rules.insurance)
+                    .then(|| strategy.takes_insurance(self.visible_count(&dealer_cards[1]))),
+            );
+            if blackjack {
+                plays.push(None);
+            } else if let Some(play) = dealer_has_blackjack
+                .then(|| self.surrender_early(strategy, player_cards, &dealer_up))
+                .flatten()
+            {
+                plays.push(Some(play));
+            } else if dealer_peeks && dealer_has_blackjack {
+                plays.push(Some(SpotPlay::unplayed(player_cards)));
             } else {
-                // Player has blackjack, dealer doesn't - automatic win
-                let payout = match self.rules.blackjack_pays.as_str() {
-                    "6:5" => 1.2,
-                    "1:1" => 1.0,
-                    _ => 1.5,
+                let dealer_label = self.dealer_label(strategy, &dealer_cards);
+                plays.push(Some(self.play_spot(strategy, player_cards, &dealer_label)));
+            }
+        }
+
+        let dealer_final = if !dealer_has_blackjack && plays.iter().any(Option::is_some) {
+            self.play_dealer(&dealer_cards)
+        } else {
+            dealer_cards.clone()
+        };
+        let dealer_value = self.calculate_hand_value(&dealer_final).0;
+        let dealer_bust = dealer_value > 21;
+
+        let mut results = Vec::with_capacity(bets.len());
+        for ((((player_cards, play), &bet_size), even_money), insurance) in
+            spot_cards.into_iter().zip(plays).zip(bets).zip(even_money).zip(insurance)
+        {
+            let Some(mut play) = play else {
+                // Player blackjack: even money pays 1:1 whatever the hole card;
+                // otherwise push against a dealer blackjack or get paid at once.
+                let (outcome, winnings) = if even_money == Some(true) {
+                    ("blackjack", bet_size)
+                } else if dealer_has_blackjack && self.rules.pontoon() {
+                    ("lose", -bet_size)
+                } else if dealer_has_blackjack && !self.rules.double_exposure {
+                    ("push", 0.0)
+                } else {
+                    ("blackjack", bet_size * self.rules.blackjack_payout())
                 };
-                return GameResult {
-                    outcome: "blackjack".to_string(),
-                    winnings: bet_size * payout,
+                results.push(GameResult {
+                    outcome: outcome.to_string(),
+                    winnings,
                     bet: bet_size,
+                    base_bet: bet_size,
                     player_cards: player_cards.clone(),
+                    third_card: None,
                     dealer_cards: dealer_cards.clone(),
-                    dealer_up_card: dealer_up,
+                    dealer_up_card: dealer_up.clone(),
                     initial_action: Some(Action::Stand), // Count as Stand
-                    hands: vec![HandRecord { cards: player_cards, bet: 1.0, result: None }],
-                };
+                    hands: vec![HandRecord {
+                        cards: player_cards,
+                        bet: 1.0,
+                        original_bet: 1.0,
+                        ..Default::default()
+                    }],
+                    doubles_offered: 0,
+                    splits: 0,
+                    resplits_blocked: 0,
+                    running_count,
+                    true_count,
+                    decks_remaining,
+                    shoe_composition: None,
+                    index_plays: Vec::new(),
+                    chart_cells: Vec::new(),
+                    even_money,
+                    insurance,
+                });
+                continue;
+            };
+
+            let mut total_winnings = 0.0;
+            if dealer_has_blackjack {
+                // Dealer has blackjack, player doesn't - player loses all hands,
+                // or only the original bet under OBO
+                let split = play.hands.len() > 1;
+                for hand in &mut play.hands {
+                    let surrendered = hand.result.as_deref() == Some("surrender");
+                    let lost = if split && self.pays_split_natural(&hand.cards) {
+                        // Natural against natural
+                        0.0
+                    } else if surrendered && self.rules.early_surrender {
+                        hand.bet / 2.0
+                    } else if self.rules.original_bets_only {
+                        hand.original_bet
+                    } else {
+                        hand.bet
+                    };
+                    hand.winnings = -bet_size * lost;
+                    let result = if surrendered && self.rules.early_surrender {
+                        "surrender"
+                    } else if lost > 0.0 {
+                        "lose"
+                    } else {
+                        "push"
+                    };
+                    hand.result = Some(result.to_string());
+                    total_winnings += hand.winnings;
+                }
+            } else {
+                let split = play.hands.len() > 1;
+                for hand in &mut play.hands {
+                    let bet = bet_size * hand.bet;
+                    let player_value = self.calculate_hand_value(&hand.cards).0;
+                    let busted = hand.result.as_deref() == Some("lose") || player_value > 21;
+                    let (result, winnings) = if hand.result.as_deref() == Some("surrender") {
+                        ("surrender", -bet / 2.0)
+                    } else if hand.rescued {
+                        ("rescue", -bet)
+                    } else if busted {
+                        ("lose", -bet)
+                    } else if self.is_charlie(&hand.cards) {
+                        ("charlie", bet * self.rules.charlie_payout())
+                    } else if split && self.pays_split_natural(&hand.cards) {
+                        ("blackjack", bet * self.rules.blackjack_payout())
+                    } else if let Some(bonus) = self.hand_bonus(hand) {
+                        hand.bonus = true;
+                        ("win", bet * bonus)
+                    } else if self.rules.push_22 && dealer_value == 22 {
+                        ("push", 0.0)
+                    } else if dealer_bust || player_value > dealer_value {
+                        ("win", bet)
+                    } else if player_value < dealer_value || self.rules.dealer_wins_tie(player_value) {
+                        ("lose", -bet)
+                    } else {
+                        ("push", 0.0)
+                    };
+                    hand.result = Some(result.to_string());
+                    hand.winnings = winnings;
+                    total_winnings += winnings;
+                }
             }
+
+            // Split naturals are labeled on their hand only; the spot's outcome
+            // stays win/lose/push so "blackjack" still means a dealt natural.
+            // A winning spot with a Charlie among its hands is a "charlie".
+            let charlie = play.hands.iter().any(|hand| hand.result.as_deref() == Some("charlie"));
+            let outcome = if total_winnings > 0.0 && charlie {
+                "charlie"
+            } else if total_winnings > 0.0 {
+                "win"
+            } else if total_winnings < 0.0 {
+                "lose"
+            } else {
+                "push"
+            }
+            .to_string();
+
+            // Insurance is settled on its own, whatever became of the hand.
+            let (insurance_bet, insurance_winnings) = match insurance {
+                Some(true) if dealer_has_blackjack => (bet_size / 2.0, bet_size),
+                Some(true) => (bet_size / 2.0, -bet_size / 2.0),
+                _ => (0.0, 0.0),
+            };
+
+            results.push(GameResult {
+                outcome,
+                winnings: total_winnings + insurance_winnings,
+                bet: bet_size * play.total_bet_units + insurance_bet,
+                base_bet: bet_size,
+                player_cards,
+                third_card: play.first_draw,
+                dealer_cards: dealer_final.clone(),
+                dealer_up_card: dealer_up.clone(),
+                initial_action: play.initial_action,
+                hands: play.hands,
+                doubles_offered: play.doubles_offered,
+                splits: play.splits,
+                resplits_blocked: play.resplits_blocked,
+                running_count,
+                true_count,
+                decks_remaining,
+                shoe_composition: None,
+                index_plays: play.index_plays,
+                chart_cells: play.chart_cells,
+                even_money: None,
+                insurance,
+            });
+        }
+        results
+    }
+
+    /// Double down rescue: gives up a doubled hand the strategy's rescue chart
+    /// marks, handing back the double. Returns the bet units handed back.
+    pub fn rescue_double(
+        &self,
+        strategy: &Strategy,
+        hand: &mut HandRecord,
+        multiplier: f64,
+        dealer_label: &str,
+    ) -> f64 {
+        if !self.rules.double_rescue {
+            return 0.0;
+        }
+        let (value, is_soft) = self.calculate_hand_value(&hand.cards);
+        let label = if is_soft { format!("S{value}") } else { value.to_string() };
+        if value > 21 || !strategy.rescues(&label, dealer_label) {
+            return 0.0;
+        }
+        let handed_back = hand.bet - hand.bet / multiplier;
+        hand.bet -= handed_back;
+        hand.rescued = true;
+        handed_back
+    }
+
+    /// Whether a hand formed by a split pays as a natural: an ace and a ten
+    /// under `split_blackjack_pays`.
+    pub fn pays_split_natural(&self, cards: &[Card]) -> bool {
+        self.rules.split_blackjack_pays && cards.len() == 2 && self.calculate_hand_value(cards).0 == 21
+    }
+
+    /// Bonus paid per unit bet when `hand` is a rewarded three-card 21 or
+    /// one of the custom bonus hands.
+    pub fn hand_bonus(&self, hand: &HandRecord) -> Option<f64> {
+        let bonuses = &self.rules.hand_bonuses;
+        if hand.doubled || (hand.cards.len() != 3 && bonuses.hands.is_empty()) {
+            return None;
+        }
+        let mut values: Vec<u8> = hand.cards.iter().map(|card| card.value).collect();
+        values.sort_unstable();
+        let (mixed, suited) = match values[..] {
+            [6, 7, 8] => (bonuses.six_seven_eight, bonuses.six_seven_eight_suited),
+            [7, 7, 7] => (bonuses.seven_seven_seven, bonuses.seven_seven_seven_suited),
+            _ => (None, None),
+        };
+        let three_card = if hand.cards.iter().all(|card| card.suit == hand.cards[0].suit) {
+            suited.or(mixed)
+        } else {
+            mixed
+        };
+        let total = self.calculate_hand_value(&hand.cards).0;
+        let custom = || bonuses.hands.iter().find(|bonus| bonus.matches(&hand.cards, total));
+        three_card.or_else(|| custom().map(|bonus| bonus.pays))
+    }
+
+    /// The spot's play when it gives up under early surrender, before the
+    /// dealer's blackjack is known. Without a dealer blackjack the same
+    /// decision is simply the first one `play_spot` makes.
+    fn surrender_early(&self, strategy: &Strategy, player_cards: &[Card], dealer_up: &Card) -> Option<SpotPlay> {
+        if !self.rules.early_surrender {
+            return None;
+        }
+        let pair_label = Self::strategy_pair_label(player_cards).filter(|_| self.can_split(player_cards));
+        let can_split = pair_label.is_some();
+        let player_label = pair_label.unwrap_or_else(|| {
+            let (value, is_soft) = self.calculate_hand_value(player_cards);
+            if is_soft {
+                format!("S{value}")
+            } else {
+                value.to_string()
+            }
+        });
+        let dealer_label = Self::dealer_card_value(dealer_up);
+        let count = self.count_range();
+        let decision = strategy.decide_action_traced(&player_label, &dealer_label, true, can_split, true, count);
+        let source = decision.source;
+        if decision.action != Action::Surrender {
+            return None;
         }
+        let mut play = SpotPlay::unplayed(player_cards);
+        play.hands[0].result = Some("surrender".to_string());
+        play.initial_action = Some(Action::Surrender);
+        if self.trace_chart || source == ChartSource::Default {
+            play.chart_cells
+                .push(Strategy::chart_cell(source, &player_label, &dealer_label, count));
+        }
+        Some(play)
+    }
 
-        let mut hands = vec![HandRecord { cards: player_cards.clone(), bet: 1.0, result: None }];
+    /// Whether the strategy would split `cards` again if the rules let it.
+    fn resplit_wanted(&self, strategy: &Strategy, cards: &[Card], dealer_label: &str, can_double: bool) -> bool {
+        Self::strategy_pair_label(cards).filter(|_| self.can_split(cards)).is_some_and(|pair_label| {
+            strategy.decide_action(&pair_label, dealer_label, can_double, true, false, self.count_range())
+                == Action::Split
+        })
+    }
+
+    /// Plays out one spot's decisions, including any splits.
+    fn play_spot(&mut self, strategy: &Strategy, player_cards: &[Card], dealer_label: &str) -> SpotPlay {
+        let mut hands = vec![HandRecord {
+            cards: player_cards.to_vec(),
+            bet: 1.0,
+            original_bet: 1.0,
+            ..Default::default()
+        }];
+        let mut doubles_offered = 0;
+        let mut splits = 0;
+        let mut resplits_blocked = 0;
         let mut total_bet_units = 1.0;
         let mut hand_index = 0usize;
         let mut initial_action: Option<Action> = None; // Track the actual initial action
         let mut initial_action_set = false; // Track if we've set the initial action yet
+        let mut index_plays = Vec::new();
+        let mut chart_cells = Vec::new();
+        let mut zapped = false;
+        let mut first_draw = None;
 
         while hand_index < hands.len() {
-            // Check if we've split by seeing if there are multiple hands
-            let has_split = hands.len() > 1;
-            // Determine if this hand can be split
-            // For the first hand before any splits: can always split if it's a pair
-            // For hands after a split: can resplit if resplitting is allowed
-            let is_pair = self.can_split(&hands[hand_index].cards);
-            let is_ace_pair = is_pair && hands[hand_index].cards.len() == 2 && 
-                             hands[hand_index].cards[0].rank == "A";
-            let can_resplit = if has_split {
-                if is_ace_pair {
-                    self.rules._resplit_aces
-                } else {
-                    self.rules.allow_resplit
-                }
-            } else {
-                true // First hand can always split if it's a pair
-            };
-            let can_split = is_pair && can_resplit;
             loop {
                 // Recalculate can_double each iteration (important after splits)
                 // If we've split (hands.len() > 1), all hands should use double_after_split rule
@@ -241,24 +1074,24 @@ impl BlackjackGame {
                 let has_split_now = hands.len() > 1;
                 // For the original first hand before any splits: can always double
                 // For any hand after a split: can double only if double_after_split rule is enabled
-                let can_double = if hands[hand_index].cards.len() == 2 {
+                let can_double = if hands[hand_index].cards.len() == 2 || self.rules.double_after_hit {
                     if !has_split_now {
                         // No split yet, first hand can always double
                         hand_index == 0
                     } else {
-                        // Split has occurred, check double_after_split rule
-                        self.rules.double_after_split
+                        // Split hands keep the split card first
+                        self.rules.doubles_after_split(&hands[hand_index].cards[0])
                     }
                 } else {
                     false
-                };
+                } && self.rules.double_on.allows(&hands[hand_index].cards);
                 
                 let (value, is_soft) = self.calculate_hand_value(&hands[hand_index].cards);
                 // Recalculate is_pair inside the loop (cards may have been added)
                 let is_pair_now = self.can_split(&hands[hand_index].cards);
                 let is_ace_pair_now = is_pair_now && hands[hand_index].cards.len() == 2 && 
                                      hands[hand_index].cards[0].rank == "A";
-                let can_resplit_now = if has_split_now && is_pair_now {
+                let split_permitted = if has_split_now && is_pair_now {
                     if is_ace_pair_now {
                         self.rules._resplit_aces
                     } else {
@@ -267,15 +1100,27 @@ impl BlackjackGame {
                 } else {
                     !has_split_now && is_pair_now // First hand can always split if it's a pair
                 };
+                let can_resplit_now = split_permitted && self.rules.split_allowed(hands.len(), is_ace_pair_now);
                 // Use pair strategy if it's a pair and either:
                 // 1. No split has occurred yet, OR
                 // 2. Resplitting is allowed (and for aces, resplit_aces must be enabled)
-                let pair_strategy_label = if is_pair_now && (!has_split_now || can_resplit_now) {
+                // and the split hand limit has not been reached
+                let pair_strategy_label = if is_pair_now && can_resplit_now {
                     Self::strategy_pair_label(&hands[hand_index].cards)
                 } else {
                     None
                 };
-                if value >= 21 {
+                if value >= 21 || self.is_charlie(&hands[hand_index].cards) {
+                    break;
+                }
+                // A split ace that may not be hit stands on its one card; the
+                // only decision left is whether to resplit.
+                let one_card_ace =
+                    has_split_now && hands[hand_index].cards[0].rank == "A" && !self.rules.hit_split_aces;
+                if one_card_ace && !can_resplit_now {
+                    if is_pair_now && self.resplit_wanted(strategy, &hands[hand_index].cards, dealer_label, false) {
+                        resplits_blocked += 1;
+                    }
                     break;
                 }
                 let player_label = if let Some(pair_label) = pair_strategy_label.clone() {
@@ -285,18 +1130,71 @@ impl BlackjackGame {
                 } else {
                     value.to_string()
                 };
-                let dealer_label = Self::dealer_card_value(&dealer_up);
+                let dealer_label = dealer_label.to_string();
                 let count = self.count_range();
                 // can_split_for_strategy: allow split if it's a pair and resplitting is allowed
                 let can_split_for_strategy = is_pair_now && can_resplit_now;
-                let action = strategy.decide_action(
+                // Surrender is only offered as the first decision on the original two cards
+                let can_surrender = self.rules.surrender_allowed()
+                    && !has_split_now
+                    && !zapped
+                    && hands[hand_index].cards.len() == 2;
+                let decision = strategy.decide_action_traced(
                     &player_label,
                     &dealer_label,
                     can_double,
                     can_split_for_strategy,
+                    can_surrender,
                     count,
                 );
-                
+                let (action, source) = (decision.action, decision.source);
+                // Pontoon players may not stand below 15, and a zap that is not
+                // allowed is a hit, like a double.
+                let can_zap = !has_split_now && !zapped && self.rules.zap_allowed(&hands[hand_index].cards);
+                let must_hit = self.rules.pontoon() && action == Action::Stand && value < 15;
+                let action = if must_hit || (action == Action::Zap && !can_zap) {
+                    Action::Hit
+                } else {
+                    self.rules.limit_double(action, hands[hand_index].cards.len())
+                };
+                if self.trace_chart || source == ChartSource::Default {
+                    chart_cells.push(Strategy::chart_cell(source, &player_label, &dealer_label, count));
+                }
+                if count != 0 {
+                    let basic_action = strategy.basic_action(
+                        &player_label,
+                        &dealer_label,
+                        can_double,
+                        can_split_for_strategy,
+                        can_surrender,
+                    );
+                    if basic_action != action {
+                        index_plays.push(IndexPlay {
+                            player: player_label.clone(),
+                            dealer: dealer_label.clone(),
+                            true_count: count,
+                            action,
+                            basic_action,
+                        });
+                    }
+                }
+                if can_double {
+                    doubles_offered += 1;
+                } else if hands[hand_index].cards.len() == 2 && decision.double_blocked {
+                    hands[hand_index].double_blocked = true;
+                    hands[hand_index].double_label = Some(player_label.clone());
+                }
+                if is_pair_now
+                    && has_split_now
+                    && !can_resplit_now
+                    && self.resplit_wanted(strategy, &hands[hand_index].cards, &dealer_label, can_double)
+                {
+                    resplits_blocked += 1;
+                }
+                if one_card_ace && action != Action::Split {
+                    break;
+                }
+
                 // Track the initial action (first decision for the first hand, before any splits)
                 if !initial_action_set && hand_index == 0 && hands.len() == 1 && hands[hand_index].cards.len() == player_cards.len() {
                     initial_action = Some(action);
@@ -305,22 +1203,37 @@ impl BlackjackGame {
 
                 match action {
                     Action::Hit => {
-                        hands[hand_index].cards.push(self.deal_card());
+                        hands[hand_index].cards.push(self.draw(&mut first_draw));
                         if self.calculate_hand_value(&hands[hand_index].cards).0 > 21 {
                             hands[hand_index].result = Some("lose".to_string());
                             break;
                         }
                     }
                     Action::Stand => break,
-                    Action::Double => {
+                    Action::Surrender => {
+                        hands[hand_index].result = Some("surrender".to_string());
+                        break;
+                    }
+                    Action::Zap => {
+                        // The hand plays on with two new cards, which never
+                        // make a natural.
+                        hands[hand_index].cards = vec![self.draw(&mut first_draw), self.draw(&mut first_draw)];
+                        zapped = true;
+                    }
+                    Action::Double | Action::Triple | Action::Quadruple => {
                         // Allow double on first hand or on split hands if double_after_split is enabled
-                        if hands[hand_index].cards.len() == 2 && can_double {
-                            hands[hand_index].bet *= 2.0;
-                            total_bet_units += hands[hand_index].bet / 2.0;
-                            hands[hand_index].cards.push(self.deal_card());
+                        if can_double {
+                            let added = action.double_multiplier().unwrap_or(2) as f64 - 1.0;
+                            hands[hand_index].doubled = true;
+                            hands[hand_index].double_label = Some(player_label.clone());
+                            total_bet_units += hands[hand_index].bet * added;
+                            hands[hand_index].bet *= 1.0 + added;
+                            hands[hand_index].cards.push(self.draw(&mut first_draw));
+                            let hand = &mut hands[hand_index];
+                            total_bet_units -= self.rescue_double(strategy, hand, 1.0 + added, &dealer_label);
                             break;
                         } else {
-                            hands[hand_index].cards.push(self.deal_card());
+                            hands[hand_index].cards.push(self.draw(&mut first_draw));
                             if self.calculate_hand_value(&hands[hand_index].cards).0 > 21 {
                                 hands[hand_index].result = Some("lose".to_string());
                             }
@@ -330,18 +1243,19 @@ impl BlackjackGame {
                     Action::Split => {
                         if hands[hand_index].cards.len() == 2 && can_split_for_strategy {
                             let card = hands[hand_index].cards.pop().unwrap();
-                        let new_hand = HandRecord {
-                                cards: vec![card, self.deal_card()],
+                            let new_hand = HandRecord {
+                                cards: vec![card, self.draw(&mut first_draw)],
                                 bet: hands[hand_index].bet,
-                                result: None,
+                                ..Default::default()
                             };
-                            hands[hand_index].cards.push(self.deal_card());
+                            hands[hand_index].cards.push(self.draw(&mut first_draw));
                             total_bet_units += new_hand.bet;
                             hands.push(new_hand);
+                            splits += 1;
                             // has_split is now automatically true since hands.len() > 1
                             continue;
                         } else {
-                            hands[hand_index].cards.push(self.deal_card());
+                            hands[hand_index].cards.push(self.draw(&mut first_draw));
                             if self.calculate_hand_value(&hands[hand_index].cards).0 > 21 {
                                 hands[hand_index].result = Some("lose".to_string());
                                 break;
@@ -353,71 +1267,230 @@ impl BlackjackGame {
             hand_index += 1;
         }
 
-        // Now check for dealer blackjack (after player has made decisions)
-        // Player blackjack was already handled earlier, so we only check dealer here
-        let dealer_has_blackjack = self.is_blackjack(&dealer_cards);
-        
-        if dealer_has_blackjack {
-            // Dealer has blackjack, player doesn't - player loses all hands
-            let mut total_winnings = 0.0;
-            for hand in &hands {
-                total_winnings -= bet_size * hand.bet;
-            }
-            return GameResult {
-                outcome: "lose".to_string(),
-                winnings: total_winnings,
-                bet: bet_size * total_bet_units,
-                player_cards: player_cards.clone(),
-                dealer_cards: dealer_cards.clone(),
-                dealer_up_card: dealer_up,
-                initial_action: initial_action, // Player made decision before dealer revealed
-                hands: hands.clone(),
-            };
+        SpotPlay {
+            hands,
+            doubles_offered,
+            splits,
+            resplits_blocked,
+            total_bet_units,
+            initial_action,
+            index_plays,
+            chart_cells,
+            first_draw,
         }
-        
-        // No blackjack, play dealer normally
-        let dealer_final = self.play_dealer(&dealer_cards);
-        let dealer_value = self.calculate_hand_value(&dealer_final).0;
-        let dealer_bust = dealer_value > 21;
+    }
 
-        let mut total_winnings = 0.0;
-        for hand in &mut hands {
-            let bet = bet_size * hand.bet;
-            if let Some(result) = &hand.result {
-                if result == "lose" {
-                    total_winnings -= bet;
-                    continue;
-                }
-            }
-            let player_value = self.calculate_hand_value(&hand.cards).0;
-            if player_value > 21 {
-                total_winnings -= bet;
-            } else if dealer_bust || player_value > dealer_value {
-                total_winnings += bet;
-            } else if player_value < dealer_value {
-                total_winnings -= bet;
-            }
-        }
+    /// Deals to the spot being played, keeping the first card it draws.
+    fn draw(&mut self, first_draw: &mut Option<Card>) -> Card {
+        let card = self.deal_card();
+        first_draw.get_or_insert_with(|| card.clone());
+        card
+    }
+}
 
-        let outcome = if total_winnings > 0.0 {
-            "win"
-        } else if total_winnings < 0.0 {
-            "lose"
-        } else {
-            "push"
-        }
-        .to_string();
+/// Decisions made for one spot, before settlement against the dealer.
+struct SpotPlay {
+    hands: Vec<HandRecord>,
+    doubles_offered: u32,
+    splits: u32,
+    resplits_blocked: u32,
+    total_bet_units: f64,
+    initial_action: Option<Action>,
+    index_plays: Vec<IndexPlay>,
+    chart_cells: Vec<ChartCell>,
+    first_draw: Option<Card>,
+}
 
-        GameResult {
-            outcome,
-            winnings: total_winnings,
-            bet: bet_size * total_bet_units,
-            player_cards,
-            dealer_cards: dealer_final,
-            dealer_up_card: dealer_up,
-            initial_action,
-            hands,
+impl SpotPlay {
+    /// A spot that never got to act because the peek found a dealer blackjack.
+    fn unplayed(player_cards: &[Card]) -> SpotPlay {
+        SpotPlay {
+            hands: vec![HandRecord {
+                cards: player_cards.to_vec(),
+                bet: 1.0,
+                original_bet: 1.0,
+                ..Default::default()
+            }],
+            doubles_offered: 0,
+            splits: 0,
+            resplits_blocked: 0,
+            total_bet_units: 1.0,
+            // Count as Stand, like a player blackjack, so the loss reaches the totals
+            initial_action: Some(Action::Stand),
+            index_plays: Vec::new(),
+            chart_cells: Vec::new(),
+            first_draw: None,
         }
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::{to_game_rules, RulesInput};
+
+    /// Plays one spot betting 10 from a shoe dealing the player's two cards,
+    /// then the dealer's two, then `draws`.
+    fn play(rules: serde_json::Value, ranks: &[&str]) -> GameResult {
+        play_with(rules, serde_json::json!({"hard": {}, "soft": {}, "pairs": {}}), ranks)
+    }
+
+    /// `play` with the given strategy instead of empty charts.
+    fn play_with(rules: serde_json::Value, strategy: serde_json::Value, ranks: &[&str]) -> GameResult {
+        let rules: RulesInput = serde_json::from_value(rules).unwrap();
+        let strategy = Strategy::from_input(serde_json::from_value(strategy).unwrap()).unwrap();
+        let mut game = BlackjackGame::new(Deck::stacked(ranks), to_game_rules(&rules), None);
+        game.play_round(&strategy, &[10.0]).remove(0)
+    }
+
+    #[test]
+    fn blackjack_pays_three_to_two() {
+        let result = play(serde_json::json!({}), &["A", "K", "9", "8"]);
+        assert_eq!(result.outcome, "blackjack");
+        assert_eq!(result.winnings, 15.0);
+        assert_eq!(result.dealer_cards.len(), 2);
+    }
+
+    #[test]
+    fn blackjack_pays_the_configured_payout() {
+        let result = play(serde_json::json!({"blackjack_pays": "6:5"}), &["A", "K", "9", "8"]);
+        assert_eq!(result.outcome, "blackjack");
+        assert_eq!(result.winnings, 12.0);
+    }
+
+    #[test]
+    fn blackjack_pushes_a_dealer_blackjack() {
+        let result = play(serde_json::json!({}), &["A", "K", "A", "Q"]);
+        assert_eq!(result.outcome, "push");
+        assert_eq!(result.winnings, 0.0);
+        assert_eq!(result.insurance, None);
+    }
+
+    #[test]
+    fn dealer_blackjack_takes_the_bet() {
+        let result = play(serde_json::json!({}), &["10", "9", "A", "K"]);
+        assert_eq!(result.outcome, "lose");
+        assert_eq!(result.winnings, -10.0);
+    }
+
+    #[test]
+    fn equal_totals_push() {
+        let result = play(serde_json::json!({}), &["10", "9", "K", "9"]);
+        assert_eq!(result.outcome, "push");
+        assert_eq!(result.winnings, 0.0);
+    }
+
+    #[test]
+    fn dealer_22_pushes_under_push_22() {
+        let ranks = ["10", "9", "10", "6", "6"];
+        let result = play(serde_json::json!({"push_22": true}), &ranks);
+        assert_eq!(result.outcome, "push");
+        assert_eq!(result.winnings, 0.0);
+        let result = play(serde_json::json!({}), &ranks);
+        assert_eq!(result.outcome, "win");
+        assert_eq!(result.winnings, 10.0);
+    }
+
+    #[test]
+    fn dealer_rule_decides_soft_17() {
+        let ranks = ["10", "8", "A", "6", "A"];
+        let result = play(serde_json::json!({"dealer_rule": "S17"}), &ranks);
+        assert_eq!((result.outcome.as_str(), result.dealer_cards.len()), ("win", 2));
+        let result = play(serde_json::json!({"dealer_rule": "H17"}), &ranks);
+        assert_eq!((result.outcome.as_str(), result.dealer_cards.len()), ("push", 3));
+    }
+
+    #[test]
+    fn double_exposure_pays_blackjack_even_money_over_a_dealer_blackjack() {
+        let result = play(serde_json::json!({"double_exposure": true}), &["A", "K", "A", "Q"]);
+        assert_eq!(result.outcome, "blackjack");
+        assert_eq!(result.winnings, 10.0);
+    }
+
+    #[test]
+    fn double_exposure_dealer_wins_ties() {
+        let result = play(serde_json::json!({"double_exposure": true}), &["10", "9", "K", "9"]);
+        assert_eq!(result.outcome, "lose");
+        assert_eq!(result.winnings, -10.0);
+    }
+
+    #[test]
+    fn pontoon_loses_to_a_dealer_pontoon() {
+        let result = play(serde_json::json!({"variant": "pontoon"}), &["A", "K", "A", "Q"]);
+        assert_eq!(result.outcome, "lose");
+        assert_eq!(result.winnings, -10.0);
+        let result = play(serde_json::json!({"variant": "pontoon"}), &["A", "K", "10", "8"]);
+        assert_eq!(result.outcome, "blackjack");
+        assert_eq!(result.winnings, 20.0);
+    }
+
+    #[test]
+    fn zappit_zaps_only_a_hard_15_to_17() {
+        let zappit = serde_json::json!({"variant": "zappit"});
+        let strategy = serde_json::json!({"hard": {"14": {"10": "Z"}, "16": {"10": "Z"}}, "soft": {}, "pairs": {}});
+        let result = play_with(zappit.clone(), strategy.clone(), &["10", "6", "10", "7", "10", "9"]);
+        let ranks: Vec<&str> = result.hands[0].cards.iter().map(|card| card.rank.as_str()).collect();
+        assert_eq!(ranks, ["10", "9"]);
+        assert_eq!(result.winnings, 10.0);
+        // A 14 is hit instead, as is any zap outside Zappit.
+        let result = play_with(zappit, strategy.clone(), &["10", "4", "10", "7", "5"]);
+        assert_eq!(result.hands[0].cards.len(), 3);
+        assert_eq!(result.winnings, 10.0);
+        let result = play_with(serde_json::json!({}), strategy, &["10", "6", "10", "7", "10"]);
+        assert_eq!(result.hands[0].cards.len(), 3);
+        assert_eq!(result.outcome, "lose");
+    }
+
+    #[test]
+    fn power_doubles_are_limited_by_the_rules() {
+        let strategy = serde_json::json!({"hard": {"11": {"10": "D4"}}, "soft": {}, "pairs": {}});
+        let ranks = ["6", "5", "10", "7", "9"];
+        let result = play_with(serde_json::json!({"max_double_multiplier": 3}), strategy.clone(), &ranks);
+        assert_eq!((result.bet, result.winnings), (30.0, 30.0));
+        let result = play_with(serde_json::json!({}), strategy.clone(), &ranks);
+        assert_eq!((result.bet, result.winnings), (20.0, 20.0));
+        // Past the first two cards only a plain double is left.
+        let rules = serde_json::json!({"max_double_multiplier": 4, "double_after_hit": true});
+        let result = play_with(rules, strategy, &["4", "3", "10", "7", "4", "9"]);
+        assert_eq!(result.hands[0].cards.len(), 4);
+        assert_eq!((result.bet, result.winnings), (20.0, 20.0));
+    }
+
+    #[test]
+    fn insurance_and_even_money_settle_apart_from_the_hand() {
+        let rules = serde_json::json!({"insurance": true});
+        let strategy = serde_json::json!({"hard": {}, "soft": {}, "pairs": {}, "insurance": "always", "even_money": "always"});
+        // Insurance pays 2:1 against the blackjack that takes the hand.
+        let result = play_with(rules.clone(), strategy.clone(), &["10", "9", "A", "K"]);
+        assert_eq!(result.insurance, Some(true));
+        assert_eq!((result.outcome.as_str(), result.bet, result.winnings), ("lose", 15.0, 0.0));
+        // It is lost when the dealer has no blackjack.
+        let result = play_with(rules.clone(), strategy.clone(), &["10", "9", "A", "7"]);
+        assert_eq!((result.outcome.as_str(), result.bet, result.winnings), ("win", 15.0, 5.0));
+        // Even money pays 1:1 on a blackjack whatever the hole card.
+        let result = play_with(rules, strategy, &["A", "K", "A", "Q"]);
+        assert_eq!(result.even_money, Some(true));
+        assert_eq!((result.outcome.as_str(), result.winnings), ("blackjack", 10.0));
+    }
+
+    #[test]
+    fn five_card_charlie_beats_a_higher_dealer_total() {
+        let ranks = ["2", "3", "10", "9", "2", "3", "4"];
+        let result = play(serde_json::json!({"charlie": 5}), &ranks);
+        assert_eq!(result.hands[0].cards.len(), 5);
+        assert_eq!((result.outcome.as_str(), result.winnings), ("charlie", 10.0));
+    }
+
+    #[test]
+    fn double_rescue_forfeits_only_the_original_bet() {
+        let strategy = serde_json::json!({
+            "hard": {"11": {"10": "D"}}, "soft": {}, "pairs": {}, "rescue": {"16": {"10": "R"}}
+        });
+        let ranks = ["6", "5", "10", "7", "5"];
+        let result = play_with(serde_json::json!({"double_rescue": true}), strategy.clone(), &ranks);
+        assert!(result.hands[0].rescued);
+        assert_eq!((result.outcome.as_str(), result.bet, result.winnings), ("lose", 10.0, -10.0));
+        let result = play_with(serde_json::json!({}), strategy, &ranks);
+        assert_eq!((result.bet, result.winnings), (20.0, -20.0));
+    }
+}
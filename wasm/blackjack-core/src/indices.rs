@@ -0,0 +1,552 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    deck::{Card, RANKS},
+    game::BlackjackGame,
+    sim::{self, CountingInput, RulesInput, SpotCheckInput},
+    strategy::{Action, Strategy, StrategyInput},
+    trainer,
+};
+
+fn default_index_iterations() -> u32 {
+    20_000
+}
+
+fn default_min_count() -> i32 {
+    -4
+}
+
+fn default_max_count() -> i32 {
+    8
+}
+
+/// Hands usually worth a late-surrender index.
+const SURRENDER_CANDIDATES: [(&str, &str, &str); 9] = [
+    ("10", "4", "10"),
+    ("10", "5", "9"),
+    ("10", "5", "10"),
+    ("10", "5", "A"),
+    ("10", "6", "8"),
+    ("10", "6", "9"),
+    ("10", "6", "10"),
+    ("10", "6", "A"),
+    ("10", "7", "A"),
+];
+
+/// Playing deviations usually worth an index, after the Illustrious 18: the
+/// hand, the dealer card and the play that replaces the chart's.
+const PLAYING_CANDIDATES: [(&str, &str, &str, &str); 17] = [
+    ("10", "6", "10", "S"),
+    ("10", "5", "10", "S"),
+    ("10", "10", "5", "P"),
+    ("10", "10", "6", "P"),
+    ("6", "4", "10", "D"),
+    ("10", "2", "3", "S"),
+    ("10", "2", "2", "S"),
+    ("6", "5", "A", "D"),
+    ("5", "4", "2", "D"),
+    ("6", "4", "A", "D"),
+    ("5", "4", "7", "D"),
+    ("10", "6", "9", "S"),
+    ("10", "3", "2", "H"),
+    ("10", "2", "4", "H"),
+    ("10", "2", "5", "H"),
+    ("10", "2", "6", "H"),
+    ("10", "3", "3", "H"),
+];
+
+type CountBlock = BTreeMap<String, BTreeMap<String, BTreeMap<String, String>>>;
+
+/// The `*_by_count` tables of a strategy input, as generated indices fill them.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CountTables {
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub hard_by_count: CountBlock,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub soft_by_count: CountBlock,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub pairs_by_count: CountBlock,
+}
+
+impl CountTables {
+    fn block(&mut self, table: CountTable) -> &mut CountBlock {
+        match table {
+            CountTable::Hard => &mut self.hard_by_count,
+            CountTable::Soft => &mut self.soft_by_count,
+            CountTable::Pairs => &mut self.pairs_by_count,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IndexHand {
+    pub player_cards: Vec<String>,
+    pub dealer_card: String,
+    /// The deviation to find an index for ("H", "S", "D", "P" or "R"); only
+    /// read by `generate_indices`.
+    #[serde(default)]
+    pub action: Option<String>,
+}
+
+/// A two-card hand with the labels the chart and count tables use.
+struct LabeledHand {
+    cards: Vec<Card>,
+    player: String,
+    is_pair: bool,
+    dealer: String,
+}
+
+impl LabeledHand {
+    fn new(hand: &IndexHand) -> Result<Self, String> {
+        if hand.player_cards.len() != 2 {
+            return Err("indices need a two-card hand".to_string());
+        }
+        if let Some(rank) = hand
+            .player_cards
+            .iter()
+            .chain(std::iter::once(&hand.dealer_card))
+            .find(|rank| !RANKS.contains(&rank.as_str()))
+        {
+            return Err(format!("unknown card rank '{rank}'"));
+        }
+        let cards: Vec<Card> = hand.player_cards.iter().map(|rank| Card::new(rank)).collect();
+        let pair_label = BlackjackGame::strategy_pair_label(&cards);
+        let is_pair = pair_label.is_some();
+        let player = pair_label.unwrap_or_else(|| trainer::hand_label(&cards));
+        let dealer_card = Card::new(&hand.dealer_card);
+        let dealer = if dealer_card.value == 11 {
+            "A".to_string()
+        } else {
+            dealer_card.value.to_string()
+        };
+        Ok(LabeledHand {
+            cards,
+            player,
+            is_pair,
+            dealer,
+        })
+    }
+
+    /// Which count table holds this hand, and its row key there.
+    fn count_row(&self) -> (CountTable, String) {
+        if self.is_pair {
+            // Pair rows are keyed by card value, as in the chart ("8", "10", "11").
+            (CountTable::Pairs, self.cards[0].value.to_string())
+        } else if let Some(total) = self.player.strip_prefix('S') {
+            (CountTable::Soft, total.to_string())
+        } else {
+            (CountTable::Hard, self.player.clone())
+        }
+    }
+}
+
+enum CountTable {
+    Hard,
+    Soft,
+    Pairs,
+}
+
+/// Settings shared by every spot check of one index search.
+struct SpotSettings<'a> {
+    num_decks: u8,
+    rules: &'a RulesInput,
+    counting: &'a CountingInput,
+    iterations: u32,
+    seed: u64,
+}
+
+impl SpotSettings<'_> {
+    /// EV per unit bet of playing `action` first on `hand` at `true_count`.
+    fn ev(&self, strategy: &Strategy, hand: &IndexHand, action: Action, true_count: i32) -> Result<f64, String> {
+        let spot = SpotCheckInput {
+            num_decks: self.num_decks,
+            iterations: self.iterations.max(1),
+            seed: self.seed,
+            strategy: StrategyInput::default(),
+            rules: self.rules.clone(),
+            bet_size: 1.0,
+            player_cards: hand.player_cards.clone(),
+            dealer_card: hand.dealer_card.clone(),
+            forced_action: action.as_code().to_string(),
+            counting: Some(self.counting.clone()),
+            true_count: Some(true_count as f64),
+        };
+        Ok(sim::spot_check(strategy, &spot)?.expected_value)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SurrenderIndexInput {
+    pub num_decks: u8,
+    pub strategy: StrategyInput,
+    pub rules: RulesInput,
+    pub counting: CountingInput,
+    /// Hands to find indices for; the usual surrender candidates when empty.
+    #[serde(default)]
+    pub hands: Vec<IndexHand>,
+    #[serde(default = "default_min_count")]
+    pub min_count: i32,
+    #[serde(default = "default_max_count")]
+    pub max_count: i32,
+    /// Spot-check rounds per hand and count.
+    #[serde(default = "default_index_iterations")]
+    pub iterations: u32,
+    #[serde(default)]
+    pub seed: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SurrenderPoint {
+    pub true_count: i32,
+    /// What the strategy plays instead of surrendering at this count.
+    pub play_action: Action,
+    pub play_ev: f64,
+    pub surrender_ev: f64,
+    /// Surrender EV minus play EV, in units of the bet.
+    pub gain: f64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SurrenderIndex {
+    pub player: String,
+    pub dealer: String,
+    /// Count at which surrender becomes correct; `None` when it never is in the
+    /// searched range.
+    pub index: Option<i32>,
+    /// Surrender at the index and above; false means at the index and below.
+    pub at_or_above: bool,
+    pub points: Vec<SurrenderPoint>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SurrenderIndexResult {
+    pub indices: Vec<SurrenderIndex>,
+    /// Count-table entries ready to merge into the strategy input. They only
+    /// cover counts where the indices disagree with the chart; count zero always
+    /// plays the chart, so hands with an index at or below zero also need "R"
+    /// in the chart itself.
+    #[serde(flatten)]
+    pub count_tables: CountTables,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IndexInput {
+    pub num_decks: u8,
+    pub strategy: StrategyInput,
+    pub rules: RulesInput,
+    /// Any system `CardCounter` supports, custom tags included.
+    pub counting: CountingInput,
+    /// Deviations to find indices for, each with its `action`; the usual
+    /// playing deviations when empty.
+    #[serde(default)]
+    pub hands: Vec<IndexHand>,
+    #[serde(default = "default_min_count")]
+    pub min_count: i32,
+    #[serde(default = "default_max_count")]
+    pub max_count: i32,
+    /// Spot-check rounds per hand, count and action.
+    #[serde(default = "default_index_iterations")]
+    pub iterations: u32,
+    #[serde(default)]
+    pub seed: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexPoint {
+    pub true_count: i32,
+    pub chart_ev: f64,
+    pub deviation_ev: f64,
+    /// Deviation EV minus chart EV, in units of the bet.
+    pub gain: f64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayingIndex {
+    pub player: String,
+    pub dealer: String,
+    /// What the chart plays at a neutral count.
+    pub chart_action: Action,
+    pub deviation: Action,
+    /// Count at which the deviation becomes correct; `None` when it never is
+    /// in the searched range.
+    pub index: Option<i32>,
+    /// Deviate at the index and above; false means at the index and below.
+    pub at_or_above: bool,
+    pub points: Vec<IndexPoint>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexResult {
+    pub indices: Vec<PlayingIndex>,
+    /// Count-table entries for every count where a deviation applies, ready
+    /// to merge into the strategy input. Count zero always plays the chart, so
+    /// an index at or on the far side of zero means the chart itself is off.
+    #[serde(flatten)]
+    pub count_tables: CountTables,
+}
+
+fn validate_range(counting: &CountingInput, min_count: i32, max_count: i32) -> Result<(), String> {
+    if sim::build_counter(Some(counting.clone())).is_none() {
+        return Err("indices need counting enabled".to_string());
+    }
+    if min_count > max_count {
+        return Err("min_count must not exceed max_count".to_string());
+    }
+    Ok(())
+}
+
+/// Derives playing indices for any rules and counting system by targeted
+/// simulation: each deviation and the chart play are spot-checked on the same
+/// seeds at every true count in the range, and the index is where a
+/// least-squares line through the EV gains crosses zero.
+pub fn generate_indices(input: IndexInput) -> Result<IndexResult, String> {
+    validate_range(&input.counting, input.min_count, input.max_count)?;
+    let strategy = Strategy::from_input(input.strategy.clone())?;
+    let rules = sim::to_game_rules(&input.rules);
+    let hands: Vec<IndexHand> = if input.hands.is_empty() {
+        PLAYING_CANDIDATES
+            .iter()
+            .map(|(first, second, dealer, action)| IndexHand {
+                player_cards: vec![first.to_string(), second.to_string()],
+                dealer_card: dealer.to_string(),
+                action: Some(action.to_string()),
+            })
+            .collect()
+    } else {
+        input.hands
+    };
+    let settings = SpotSettings {
+        num_decks: input.num_decks,
+        rules: &input.rules,
+        counting: &input.counting,
+        iterations: input.iterations,
+        seed: input.seed,
+    };
+
+    let mut result = IndexResult {
+        indices: Vec::with_capacity(hands.len()),
+        count_tables: CountTables::default(),
+    };
+    for hand in &hands {
+        let labeled = LabeledHand::new(hand)?;
+        let code = hand.action.as_deref().ok_or("every hand needs the deviation action to index")?;
+        if !["H", "S", "D", "P", "R"].contains(&code) {
+            return Err(format!("unknown action '{code}', expected H, S, D, P or R"));
+        }
+        let deviation = Action::from_code(code);
+        let chart_action = strategy.basic_action(
+            &labeled.player,
+            &labeled.dealer,
+            true,
+            labeled.is_pair,
+            rules.surrender_allowed(),
+        );
+        match deviation {
+            Action::Split if !labeled.is_pair => return Err("only pairs can be split".to_string()),
+            Action::Surrender if !rules.surrender_allowed() => {
+                return Err("surrender deviations need late or early surrender".to_string())
+            }
+            _ if deviation == chart_action => {
+                return Err(format!(
+                    "{code} is already the chart play for {} vs {}",
+                    labeled.player, labeled.dealer
+                ))
+            }
+            _ => {}
+        }
+
+        let mut points = Vec::new();
+        for true_count in input.min_count..=input.max_count {
+            let chart_ev = settings.ev(&strategy, hand, chart_action, true_count)?;
+            let deviation_ev = settings.ev(&strategy, hand, deviation, true_count)?;
+            points.push(IndexPoint {
+                true_count,
+                chart_ev,
+                deviation_ev,
+                gain: deviation_ev - chart_ev,
+            });
+        }
+
+        let gains: Vec<(i32, f64)> = points.iter().map(|point| (point.true_count, point.gain)).collect();
+        let (index, at_or_above) = crossing(&gains, input.min_count, input.max_count);
+        let (table, key) = labeled.count_row();
+        let block = result.count_tables.block(table);
+        for point in &points {
+            // Count tables are not consulted at zero, where the chart applies.
+            let deviates = match index {
+                Some(index) if at_or_above => point.true_count >= index,
+                Some(index) => point.true_count <= index,
+                None => false,
+            };
+            if point.true_count == 0 || !deviates {
+                continue;
+            }
+            block
+                .entry(point.true_count.to_string())
+                .or_default()
+                .entry(key.clone())
+                .or_default()
+                .insert(labeled.dealer.clone(), code.to_string());
+        }
+
+        result.indices.push(PlayingIndex {
+            player: labeled.player,
+            dealer: labeled.dealer,
+            chart_action,
+            deviation,
+            index,
+            at_or_above,
+            points,
+        });
+    }
+    Ok(result)
+}
+
+/// Finds surrender indices by spot-checking each hand at every true count in
+/// the range, once surrendered and once played as the strategy would at that
+/// count. The index is where a least-squares line through the EV gains
+/// crosses zero, which smooths out spot-check noise.
+pub fn generate_surrender_indices(input: SurrenderIndexInput) -> Result<SurrenderIndexResult, String> {
+    validate_range(&input.counting, input.min_count, input.max_count)?;
+    if !sim::to_game_rules(&input.rules).surrender_allowed() {
+        return Err("surrender indices need late or early surrender".to_string());
+    }
+    let strategy = Strategy::from_input(input.strategy.clone())?;
+    let hands: Vec<IndexHand> = if input.hands.is_empty() {
+        SURRENDER_CANDIDATES
+            .iter()
+            .map(|(first, second, dealer)| IndexHand {
+                player_cards: vec![first.to_string(), second.to_string()],
+                dealer_card: dealer.to_string(),
+                action: None,
+            })
+            .collect()
+    } else {
+        input.hands
+    };
+    let settings = SpotSettings {
+        num_decks: input.num_decks,
+        rules: &input.rules,
+        counting: &input.counting,
+        iterations: input.iterations,
+        seed: input.seed,
+    };
+
+    let mut result = SurrenderIndexResult {
+        indices: Vec::with_capacity(hands.len()),
+        count_tables: CountTables::default(),
+    };
+    for hand in &hands {
+        let labeled = LabeledHand::new(hand)?;
+        let (player, dealer, is_pair) = (&labeled.player, &labeled.dealer, labeled.is_pair);
+
+        let mut points = Vec::new();
+        for true_count in input.min_count..=input.max_count {
+            let play_action = strategy.decide_action(player, dealer, true, is_pair, false, true_count);
+            let play_ev = settings.ev(&strategy, hand, play_action, true_count)?;
+            let surrender_ev = settings.ev(&strategy, hand, Action::Surrender, true_count)?;
+            points.push(SurrenderPoint {
+                true_count,
+                play_action,
+                play_ev,
+                surrender_ev,
+                gain: surrender_ev - play_ev,
+            });
+        }
+
+        let gains: Vec<(i32, f64)> = points.iter().map(|point| (point.true_count, point.gain)).collect();
+        let (index, at_or_above) = crossing(&gains, input.min_count, input.max_count);
+        let chart_surrenders = strategy.basic_action(player, dealer, true, is_pair, true) == Action::Surrender;
+        let (table, key) = labeled.count_row();
+        let block = result.count_tables.block(table);
+        for point in &points {
+            // Count tables are not consulted at zero, where the chart applies.
+            if point.true_count == 0 {
+                continue;
+            }
+            let surrender = match index {
+                Some(index) if at_or_above => point.true_count >= index,
+                Some(index) => point.true_count <= index,
+                None => false,
+            };
+            let code = match (surrender, chart_surrenders) {
+                (true, false) => Action::Surrender.as_code(),
+                (false, true) => point.play_action.as_code(),
+                _ => continue,
+            };
+            block
+                .entry(point.true_count.to_string())
+                .or_default()
+                .entry(key.clone())
+                .or_default()
+                .insert(dealer.clone(), code.to_string());
+        }
+
+        result.indices.push(SurrenderIndex {
+            player: labeled.player,
+            dealer: labeled.dealer,
+            index,
+            at_or_above,
+            points,
+        });
+    }
+    Ok(result)
+}
+
+/// Fits gain against count and returns the first whole count on the
+/// deviation side of the zero crossing, clamped to the searched range.
+fn crossing(gains: &[(i32, f64)], min_count: i32, max_count: i32) -> (Option<i32>, bool) {
+    let n = gains.len() as f64;
+    let mean_x = gains.iter().map(|&(count, _)| count as f64).sum::<f64>() / n;
+    let mean_y = gains.iter().map(|&(_, gain)| gain).sum::<f64>() / n;
+    let sxx: f64 = gains.iter().map(|&(count, _)| (count as f64 - mean_x).powi(2)).sum();
+    let sxy: f64 = gains
+        .iter()
+        .map(|&(count, gain)| (count as f64 - mean_x) * (gain - mean_y))
+        .sum();
+    if sxx <= 0.0 || sxy == 0.0 {
+        // A single count or a flat line: deviate everywhere when it gains,
+        // nowhere when it does not.
+        return ((mean_y > 0.0).then_some(min_count), true);
+    }
+    let slope = sxy / sxx;
+    let root = mean_x - mean_y / slope;
+    if slope > 0.0 {
+        let index = root.ceil() as i32;
+        ((index <= max_count).then_some(index.max(min_count)), true)
+    } else {
+        let index = root.floor() as i32;
+        ((index >= min_count).then_some(index.min(max_count)), false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crossing_finds_the_first_count_that_gains() {
+        // Both lines cross zero half a count from the middle.
+        let rising: Vec<(i32, f64)> = (-2..=2).map(|count| (count, 0.1 * count as f64 - 0.05)).collect();
+        assert_eq!(crossing(&rising, -2, 2), (Some(1), true));
+        let falling: Vec<(i32, f64)> = (-2..=2).map(|count| (count, -0.1 * count as f64 - 0.05)).collect();
+        assert_eq!(crossing(&falling, -2, 2), (Some(-1), false));
+    }
+
+    #[test]
+    fn flat_gain_deviates_only_when_it_gains() {
+        let gains = |gain: f64| [(-1, gain), (0, gain), (1, gain)];
+        assert_eq!(crossing(&gains(0.0), -1, 1).0, None);
+        assert_eq!(crossing(&gains(-0.1), -1, 1).0, None);
+        assert_eq!(crossing(&gains(0.1), -1, 1), (Some(-1), true));
+    }
+}
@@ -0,0 +1,132 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::{
+    sim::{SimulationInput, SimulationResult, SimulationRun},
+    strategy::Strategy,
+};
+
+enum JobState {
+    Running(Box<SimulationRun>),
+    Done(Box<SimulationResult>),
+    Failed(String),
+    Cancelled,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobStatus {
+    pub id: u32,
+    /// "running", "done", "failed" or "cancelled".
+    pub state: &'static str,
+    pub completed: u32,
+    pub total: u32,
+}
+
+struct Job {
+    state: JobState,
+    completed: u32,
+    total: u32,
+}
+
+/// Runs several simulations in one thread by interleaving them in chunks.
+/// Jobs only advance inside `advance`, so the caller controls how long each
+/// call blocks.
+#[derive(Default)]
+pub struct JobManager {
+    next_id: u32,
+    jobs: BTreeMap<u32, Job>,
+}
+
+impl JobManager {
+    pub fn start(&mut self, input: SimulationInput) -> Result<u32, String> {
+        let strategy = Strategy::from_input(input.strategy.clone())?;
+        let run = SimulationRun::new(strategy, input)?;
+        let id = self.next_id;
+        self.next_id += 1;
+        self.jobs.insert(
+            id,
+            Job {
+                completed: 0,
+                total: run.total(),
+                state: JobState::Running(Box::new(run)),
+            },
+        );
+        Ok(id)
+    }
+
+    /// Gives every running job up to `rounds_per_job` rounds, round-robin.
+    /// Returns true while any job is still running.
+    pub fn advance(&mut self, rounds_per_job: u32) -> bool {
+        let mut running = false;
+        for job in self.jobs.values_mut() {
+            let JobState::Running(run) = &mut job.state else {
+                continue;
+            };
+            job.completed = run.step(rounds_per_job.max(1));
+            if !run.is_done() {
+                running = true;
+                continue;
+            }
+            if let JobState::Running(run) = std::mem::replace(&mut job.state, JobState::Cancelled) {
+                job.state = match run.finish() {
+                    Ok(result) => JobState::Done(Box::new(result)),
+                    Err(err) => JobState::Failed(err),
+                };
+            }
+        }
+        running
+    }
+
+    pub fn status(&self, id: u32) -> Option<JobStatus> {
+        let job = self.jobs.get(&id)?;
+        Some(JobStatus {
+            id,
+            state: match job.state {
+                JobState::Running(_) => "running",
+                JobState::Done(_) => "done",
+                JobState::Failed(_) => "failed",
+                JobState::Cancelled => "cancelled",
+            },
+            completed: job.completed,
+            total: job.total,
+        })
+    }
+
+    pub fn statuses(&self) -> Vec<JobStatus> {
+        self.jobs.keys().filter_map(|&id| self.status(id)).collect()
+    }
+
+    /// Stops a running job; its partial result is discarded.
+    pub fn cancel(&mut self, id: u32) -> bool {
+        match self.jobs.get_mut(&id) {
+            Some(job) if matches!(job.state, JobState::Running(_)) => {
+                job.state = JobState::Cancelled;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Removes a finished job and returns its result. Running jobs are left alone.
+    pub fn take_result(&mut self, id: u32) -> Result<SimulationResult, String> {
+        match self.jobs.get(&id).map(|job| &job.state) {
+            None => Err(format!("no job with id {id}")),
+            Some(JobState::Running(_)) => Err(format!("job {id} is still running")),
+            Some(JobState::Cancelled) => {
+                self.jobs.remove(&id);
+                Err(format!("job {id} was cancelled"))
+            }
+            Some(JobState::Failed(err)) => {
+                let err = format!("job {id} failed: {err}");
+                self.jobs.remove(&id);
+                Err(err)
+            }
+            Some(JobState::Done(_)) => match self.jobs.remove(&id).map(|job| job.state) {
+                Some(JobState::Done(result)) => Ok(*result),
+                _ => unreachable!("job state checked above"),
+            },
+        }
+    }
+}
@@ -1,11 +1,35 @@
-use js_sys::Function;
-use wasm_bindgen::prelude::*;
+use js_sys::{Array, Float64Array, Function, Int32Array, Object, Promise, Reflect, Uint16Array, Uint32Array};
+use serde::Serialize;
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_futures::JsFuture;
 
+mod bankroll;
+mod betting;
+mod chart;
+mod clock;
+mod columnar;
+mod compare;
 mod counter;
 mod deck;
+mod drill;
 mod game;
+mod indices;
+mod jobs;
+mod live;
+mod optimizer;
+mod policy;
+mod report;
+mod ruin;
+mod selftest;
+mod sidebets;
 mod strategy;
+mod series;
 mod sim;
+mod stats;
+mod sweep;
+mod tracker;
+mod trainer;
+mod trip;
 
 #[wasm_bindgen]
 pub fn run_simulation(params: &JsValue) -> Result<JsValue, JsValue> {
@@ -44,6 +68,317 @@ pub fn run_simulation_with_progress(
         .map_err(|err| JsValue::from_str(&format!("Serialization failed: {err}")))
 }
 
+/// Same as `run_simulation_with_progress`, but plays `chunk_size` rounds at a
+/// time and yields to the event loop in between, so it can run on the main
+/// thread without freezing the page.
+#[wasm_bindgen]
+pub async fn run_simulation_async(
+    params: JsValue,
+    progress_callback: Option<Function>,
+    chunk_size: Option<u32>,
+) -> Result<JsValue, JsValue> {
+    console_error_panic_hook::set_once();
+    let input: sim::SimulationInput = serde_wasm_bindgen::from_value(params)
+        .map_err(|err| JsValue::from_str(&format!("Invalid input: {err}")))?;
+    let strategy = strategy::Strategy::from_input(input.strategy.clone())
+        .map_err(|err| JsValue::from_str(&format!("Simulation failed: {err}")))?;
+
+    let chunk_size = chunk_size.unwrap_or(input.progress_interval).max(1);
+    let mut throttle = clock::ProgressThrottle::new(chunk_size, input.progress_interval_ms);
+    let mut run = sim::SimulationRun::new(strategy, input)
+        .map_err(|err| JsValue::from_str(&format!("Simulation failed: {err}")))?;
+    while !run.is_done() {
+        let completed = run.step(chunk_size);
+        if let Some(callback) = &progress_callback {
+            if throttle.due(completed, run.total()) {
+                let _ = callback.call2(&JsValue::NULL, &JsValue::from(completed), &JsValue::from(run.total()));
+            }
+        }
+        yield_to_event_loop().await?;
+    }
+
+    let result = run
+        .finish()
+        .map_err(|err| JsValue::from_str(&format!("Simulation failed: {err}")))?;
+    serde_wasm_bindgen::to_value(&result)
+        .map_err(|err| JsValue::from_str(&format!("Serialization failed: {err}")))
+}
+
+/// Resolves on the next macrotask via `setTimeout(0)`, falling back to a
+/// microtask where `setTimeout` is unavailable.
+async fn yield_to_event_loop() -> Result<(), JsValue> {
+    let promise = Promise::new(&mut |resolve, _reject| {
+        let set_timeout = Reflect::get(&js_sys::global(), &JsValue::from_str("setTimeout"))
+            .ok()
+            .and_then(|value| value.dyn_into::<Function>().ok());
+        match set_timeout {
+            Some(set_timeout) => {
+                let _ = set_timeout.call2(&JsValue::NULL, &resolve, &JsValue::from(0));
+            }
+            None => {
+                let _ = resolve.call0(&JsValue::NULL);
+            }
+        }
+    });
+    JsFuture::from(promise).await.map(|_| ())
+}
+
+/// Runs a simulation while publishing live counters into `live`, typically a
+/// `Float64Array` over a `SharedArrayBuffer` that the UI polls each frame.
+/// See `live.rs` for the slot layout; the buffer needs at least 6 slots.
+#[wasm_bindgen]
+pub fn run_simulation_live(
+    params: &JsValue,
+    live: &Float64Array,
+    starting_bankroll: Option<f64>,
+) -> Result<JsValue, JsValue> {
+    console_error_panic_hook::set_once();
+    let input: sim::SimulationInput = serde_wasm_bindgen::from_value(params.clone())
+        .map_err(|err| JsValue::from_str(&format!("Invalid input: {err}")))?;
+    let strategy = strategy::Strategy::from_input(input.strategy.clone())
+        .map_err(|err| JsValue::from_str(&format!("Simulation failed: {err}")))?;
+    if (live.length() as usize) < live::LIVE_SLOTS {
+        return Err(JsValue::from_str(&format!(
+            "Invalid input: live buffer needs {} slots",
+            live::LIVE_SLOTS
+        )));
+    }
+
+    let starting_bankroll = starting_bankroll.unwrap_or(0.0);
+    let mut run = sim::SimulationRun::new(strategy, input)
+        .map_err(|err| JsValue::from_str(&format!("Simulation failed: {err}")))?;
+    let mut sequence = 0.0;
+    while !run.is_done() {
+        run.step(live::UPDATE_ROUNDS);
+        let slots = live::snapshot(&run, starting_bankroll);
+        sequence += 1.0;
+        live.set_index(live::SEQUENCE as u32, sequence);
+        for (slot, value) in slots.iter().enumerate().skip(1) {
+            live.set_index(slot as u32, *value);
+        }
+        sequence += 1.0;
+        live.set_index(live::SEQUENCE as u32, sequence);
+    }
+
+    let result = run
+        .finish()
+        .map_err(|err| JsValue::from_str(&format!("Simulation failed: {err}")))?;
+    serde_wasm_bindgen::to_value(&result)
+        .map_err(|err| JsValue::from_str(&format!("Serialization failed: {err}")))
+}
+
+/// Runs a simulation and returns `cellStats` and `countStats` as parallel
+/// typed arrays (`cellColumns`, `countColumns`) instead of nested objects.
+/// String columns are dictionary-encoded as `{ labels, codes }`.
+#[wasm_bindgen]
+pub fn run_simulation_columnar(params: &JsValue) -> Result<JsValue, JsValue> {
+    console_error_panic_hook::set_once();
+    let input: sim::SimulationInput = serde_wasm_bindgen::from_value(params.clone())
+        .map_err(|err| JsValue::from_str(&format!("Invalid input: {err}")))?;
+
+    let mut result = sim::run(input)
+        .map_err(|err| JsValue::from_str(&format!("Simulation failed: {err}")))?;
+    let cells = columnar::cell_columns(&std::mem::take(&mut result.cell_stats));
+    let counts = result.count_stats.take().map(|stats| columnar::count_columns(&stats));
+
+    let output = serde_wasm_bindgen::to_value(&result)
+        .map_err(|err| JsValue::from_str(&format!("Serialization failed: {err}")))?;
+    Reflect::set(&output, &"cellColumns".into(), &cell_columns_to_js(&cells)?)?;
+    if let Some(counts) = counts {
+        Reflect::set(&output, &"countColumns".into(), &count_columns_to_js(&counts)?)?;
+    }
+    Ok(output)
+}
+
+fn dictionary_to_js(dictionary: &columnar::Dictionary) -> Result<JsValue, JsValue> {
+    let object = Object::new();
+    let labels: Array = dictionary.labels.iter().map(|label| JsValue::from_str(label)).collect();
+    Reflect::set(&object, &"labels".into(), &labels)?;
+    Reflect::set(&object, &"codes".into(), &Uint16Array::from(dictionary.codes.as_slice()))?;
+    Ok(object.into())
+}
+
+fn cell_columns_to_js(cells: &columnar::CellColumns) -> Result<JsValue, JsValue> {
+    let object = Object::new();
+    Reflect::set(&object, &"playerTotal".into(), &dictionary_to_js(&cells.player_total)?)?;
+    Reflect::set(&object, &"dealerCard".into(), &dictionary_to_js(&cells.dealer_card)?)?;
+    Reflect::set(&object, &"action".into(), &dictionary_to_js(&cells.action)?)?;
+    Reflect::set(&object, &"count".into(), &Int32Array::from(cells.count.as_slice()))?;
+    Reflect::set(&object, &"hands".into(), &Uint32Array::from(cells.hands.as_slice()))?;
+    Reflect::set(&object, &"wins".into(), &Uint32Array::from(cells.wins.as_slice()))?;
+    Reflect::set(&object, &"losses".into(), &Uint32Array::from(cells.losses.as_slice()))?;
+    Reflect::set(&object, &"pushes".into(), &Uint32Array::from(cells.pushes.as_slice()))?;
+    Reflect::set(&object, &"totalWinnings".into(), &Float64Array::from(cells.total_winnings.as_slice()))?;
+    Reflect::set(&object, &"totalBet".into(), &Float64Array::from(cells.total_bet.as_slice()))?;
+    Reflect::set(&object, &"standardError".into(), &Float64Array::from(cells.standard_error.as_slice()))?;
+    Ok(object.into())
+}
+
+fn count_columns_to_js(counts: &columnar::CountColumns) -> Result<JsValue, JsValue> {
+    let object = Object::new();
+    Reflect::set(&object, &"trueCount".into(), &Int32Array::from(counts.true_count.as_slice()))?;
+    Reflect::set(&object, &"hands".into(), &Uint32Array::from(counts.hands.as_slice()))?;
+    Reflect::set(&object, &"ev".into(), &Float64Array::from(counts.ev.as_slice()))?;
+    Reflect::set(&object, &"advantage".into(), &Float64Array::from(counts.advantage.as_slice()))?;
+    Reflect::set(&object, &"variance".into(), &Float64Array::from(counts.variance.as_slice()))?;
+    Ok(object.into())
+}
+
+/// Holds a parsed strategy and base configuration so the UI can run many
+/// simulations and spot checks without re-sending the strategy JSON.
+#[wasm_bindgen]
+pub struct Simulator {
+    strategy: strategy::Strategy,
+    base: sim::SimulationInput,
+}
+
+#[wasm_bindgen]
+impl Simulator {
+    #[wasm_bindgen(constructor)]
+    pub fn new(params: &JsValue) -> Result<Simulator, JsValue> {
+        console_error_panic_hook::set_once();
+        let mut base: sim::SimulationInput = serde_wasm_bindgen::from_value(params.clone())
+            .map_err(|err| JsValue::from_str(&format!("Invalid input: {err}")))?;
+        let strategy = strategy::Strategy::from_input(std::mem::take(&mut base.strategy))
+            .map_err(|err| JsValue::from_str(&format!("Invalid input: {err}")))?;
+        Ok(Simulator { strategy, base })
+    }
+
+    /// Runs a simulation; `overrides` may change the seed, iterations, bet size,
+    /// bet ramp or output detail for this call only.
+    pub fn run(&self, overrides: &JsValue) -> Result<JsValue, JsValue> {
+        let overrides: Option<sim::RunOverrides> = serde_wasm_bindgen::from_value(overrides.clone())
+            .map_err(|err| JsValue::from_str(&format!("Invalid input: {err}")))?;
+        let input = overrides.unwrap_or_default().apply(&self.base);
+
+        let result = sim::simulate(&self.strategy, &input, |_, _| {})
+            .map_err(|err| JsValue::from_str(&format!("Simulation failed: {err}")))?;
+
+        serde_wasm_bindgen::to_value(&result)
+            .map_err(|err| JsValue::from_str(&format!("Serialization failed: {err}")))
+    }
+
+    pub fn spot_check(&self, hand: &JsValue) -> Result<JsValue, JsValue> {
+        let hand: sim::SpotCheckHand = serde_wasm_bindgen::from_value(hand.clone())
+            .map_err(|err| JsValue::from_str(&format!("Invalid input: {err}")))?;
+        let input = hand.into_input(&self.base);
+
+        let result = sim::spot_check(&self.strategy, &input)
+            .map_err(|err| JsValue::from_str(&format!("Spot check failed: {err}")))?;
+
+        serde_wasm_bindgen::to_value(&result)
+            .map_err(|err| JsValue::from_str(&format!("Serialization failed: {err}")))
+    }
+}
+
+/// Registry of simulations that share one worker. Call `advance` in a loop
+/// (yielding between calls as needed) and collect results by job id.
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct JobRegistry {
+    manager: jobs::JobManager,
+}
+
+#[wasm_bindgen]
+impl JobRegistry {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> JobRegistry {
+        console_error_panic_hook::set_once();
+        JobRegistry::default()
+    }
+
+    /// Queues a simulation and returns its job id.
+    pub fn start(&mut self, params: &JsValue) -> Result<u32, JsValue> {
+        let input: sim::SimulationInput = serde_wasm_bindgen::from_value(params.clone())
+            .map_err(|err| JsValue::from_str(&format!("Invalid input: {err}")))?;
+        self.manager
+            .start(input)
+            .map_err(|err| JsValue::from_str(&format!("Invalid input: {err}")))
+    }
+
+    /// Advances every running job by up to `rounds_per_job` rounds; returns
+    /// true while any job is still running.
+    pub fn advance(&mut self, rounds_per_job: u32) -> bool {
+        self.manager.advance(rounds_per_job)
+    }
+
+    /// Status of one job, or `undefined` for an unknown id.
+    pub fn poll(&self, id: u32) -> Result<JsValue, JsValue> {
+        serde_wasm_bindgen::to_value(&self.manager.status(id))
+            .map_err(|err| JsValue::from_str(&format!("Serialization failed: {err}")))
+    }
+
+    pub fn poll_all(&self) -> Result<JsValue, JsValue> {
+        serde_wasm_bindgen::to_value(&self.manager.statuses())
+            .map_err(|err| JsValue::from_str(&format!("Serialization failed: {err}")))
+    }
+
+    pub fn cancel(&mut self, id: u32) -> bool {
+        self.manager.cancel(id)
+    }
+
+    /// Returns a finished job's result and forgets the job.
+    pub fn result(&mut self, id: u32) -> Result<JsValue, JsValue> {
+        let result = self
+            .manager
+            .take_result(id)
+            .map_err(|err| JsValue::from_str(&format!("Job unavailable: {err}")))?;
+        serde_wasm_bindgen::to_value(&result)
+            .map_err(|err| JsValue::from_str(&format!("Serialization failed: {err}")))
+    }
+}
+
+/// Logs a real session hand by hand: keeps the count, checks each decision
+/// against the strategy and summarizes results and mistakes.
+#[wasm_bindgen]
+pub struct SessionTracker {
+    session: tracker::Session,
+}
+
+#[wasm_bindgen]
+impl SessionTracker {
+    #[wasm_bindgen(constructor)]
+    pub fn new(params: &JsValue) -> Result<SessionTracker, JsValue> {
+        console_error_panic_hook::set_once();
+        let input: tracker::SessionInput = serde_wasm_bindgen::from_value(params.clone())
+            .map_err(|err| JsValue::from_str(&format!("Invalid input: {err}")))?;
+        let session = tracker::Session::new(input).map_err(|err| JsValue::from_str(&format!("Invalid input: {err}")))?;
+        Ok(SessionTracker { session })
+    }
+
+    /// Records a played hand and returns the decision check for it.
+    pub fn record_hand(&mut self, hand: &JsValue) -> Result<JsValue, JsValue> {
+        let hand: tracker::RecordedHand = serde_wasm_bindgen::from_value(hand.clone())
+            .map_err(|err| JsValue::from_str(&format!("Invalid input: {err}")))?;
+
+        let result = self
+            .session
+            .record_hand(hand)
+            .map_err(|err| JsValue::from_str(&format!("Invalid input: {err}")))?;
+
+        serde_wasm_bindgen::to_value(&result)
+            .map_err(|err| JsValue::from_str(&format!("Serialization failed: {err}")))
+    }
+
+    /// Counts cards seen away from the player's hand, given as ranks.
+    pub fn record_cards(&mut self, ranks: &JsValue) -> Result<(), JsValue> {
+        let ranks: Vec<String> = serde_wasm_bindgen::from_value(ranks.clone())
+            .map_err(|err| JsValue::from_str(&format!("Invalid input: {err}")))?;
+        self.session
+            .record_cards(&ranks)
+            .map_err(|err| JsValue::from_str(&format!("Invalid input: {err}")))
+    }
+
+    pub fn shuffle(&mut self) {
+        self.session.shuffle();
+    }
+
+    pub fn summary(&self) -> Result<JsValue, JsValue> {
+        serde_wasm_bindgen::to_value(&self.session.summary())
+            .map_err(|err| JsValue::from_str(&format!("Serialization failed: {err}")))
+    }
+}
+
 #[wasm_bindgen]
 pub fn run_spot_check(params: &JsValue) -> Result<JsValue, JsValue> {
     console_error_panic_hook::set_once();
@@ -58,22 +393,260 @@ pub fn run_spot_check(params: &JsValue) -> Result<JsValue, JsValue> {
 }
 
 #[wasm_bindgen]
-pub fn play_single_game(params: &JsValue) -> Result<JsValue, JsValue> {
+pub fn compare_rules(params: &JsValue) -> Result<JsValue, JsValue> {
+    console_error_panic_hook::set_once();
+    let input: compare::CompareRulesInput = serde_wasm_bindgen::from_value(params.clone())
+        .map_err(|err| JsValue::from_str(&format!("Invalid input: {err}")))?;
+
+    let result = compare::compare_rules(input)
+        .map_err(|err| JsValue::from_str(&format!("Comparison failed: {err}")))?;
+
+    serde_wasm_bindgen::to_value(&result)
+        .map_err(|err| JsValue::from_str(&format!("Serialization failed: {err}")))
+}
+
+#[wasm_bindgen]
+pub fn calculate_risk_of_ruin(params: &JsValue) -> Result<JsValue, JsValue> {
+    console_error_panic_hook::set_once();
+    let input: ruin::RiskOfRuinInput = serde_wasm_bindgen::from_value(params.clone())
+        .map_err(|err| JsValue::from_str(&format!("Invalid input: {err}")))?;
+
+    let result = ruin::calculate_risk_of_ruin(input)
+        .map_err(|err| JsValue::from_str(&format!("Risk of ruin failed: {err}")))?;
+
+    serde_wasm_bindgen::to_value(&result)
+        .map_err(|err| JsValue::from_str(&format!("Serialization failed: {err}")))
+}
+
+#[wasm_bindgen]
+pub fn optimize_bet_ramp(params: &JsValue) -> Result<JsValue, JsValue> {
+    console_error_panic_hook::set_once();
+    let input: optimizer::RampOptimizerInput = serde_wasm_bindgen::from_value(params.clone())
+        .map_err(|err| JsValue::from_str(&format!("Invalid input: {err}")))?;
+
+    let result = optimizer::optimize_bet_ramp(input)
+        .map_err(|err| JsValue::from_str(&format!("Ramp optimization failed: {err}")))?;
+
+    serde_wasm_bindgen::to_value(&result)
+        .map_err(|err| JsValue::from_str(&format!("Serialization failed: {err}")))
+}
+
+#[wasm_bindgen]
+pub fn run_trip(params: &JsValue) -> Result<JsValue, JsValue> {
+    console_error_panic_hook::set_once();
+    let input: trip::TripInput = serde_wasm_bindgen::from_value(params.clone())
+        .map_err(|err| JsValue::from_str(&format!("Invalid input: {err}")))?;
+
+    let result = trip::run_trip(input)
+        .map_err(|err| JsValue::from_str(&format!("Trip simulation failed: {err}")))?;
+
+    serde_wasm_bindgen::to_value(&result)
+        .map_err(|err| JsValue::from_str(&format!("Serialization failed: {err}")))
+}
+
+#[wasm_bindgen]
+pub fn run_rule_sweep(params: &JsValue) -> Result<JsValue, JsValue> {
+    console_error_panic_hook::set_once();
+    let input: sweep::RuleSweepInput = serde_wasm_bindgen::from_value(params.clone())
+        .map_err(|err| JsValue::from_str(&format!("Invalid input: {err}")))?;
+
+    let result = sweep::run_rule_sweep(input)
+        .map_err(|err| JsValue::from_str(&format!("Sweep failed: {err}")))?;
+
+    serde_wasm_bindgen::to_value(&result)
+        .map_err(|err| JsValue::from_str(&format!("Serialization failed: {err}")))
+}
+
+#[wasm_bindgen]
+pub fn run_counting_sweep(params: &JsValue) -> Result<JsValue, JsValue> {
+    console_error_panic_hook::set_once();
+    let input: sweep::CountingSweepInput = serde_wasm_bindgen::from_value(params.clone())
+        .map_err(|err| JsValue::from_str(&format!("Invalid input: {err}")))?;
+
+    let result = sweep::run_counting_sweep(input)
+        .map_err(|err| JsValue::from_str(&format!("Sweep failed: {err}")))?;
+
+    serde_wasm_bindgen::to_value(&result)
+        .map_err(|err| JsValue::from_str(&format!("Serialization failed: {err}")))
+}
+
+#[wasm_bindgen]
+pub fn run_bet_spread_sweep(params: &JsValue) -> Result<JsValue, JsValue> {
+    console_error_panic_hook::set_once();
+    let input: sweep::BetSpreadSweepInput = serde_wasm_bindgen::from_value(params.clone())
+        .map_err(|err| JsValue::from_str(&format!("Invalid input: {err}")))?;
+
+    let result = sweep::run_bet_spread_sweep(input)
+        .map_err(|err| JsValue::from_str(&format!("Sweep failed: {err}")))?;
+
+    serde_wasm_bindgen::to_value(&result)
+        .map_err(|err| JsValue::from_str(&format!("Serialization failed: {err}")))
+}
+
+#[wasm_bindgen]
+pub fn run_penetration_sweep(params: &JsValue) -> Result<JsValue, JsValue> {
+    console_error_panic_hook::set_once();
+    let input: sweep::PenetrationSweepInput = serde_wasm_bindgen::from_value(params.clone())
+        .map_err(|err| JsValue::from_str(&format!("Invalid input: {err}")))?;
+
+    let result = sweep::run_penetration_sweep(input)
+        .map_err(|err| JsValue::from_str(&format!("Sweep failed: {err}")))?;
+
+    serde_wasm_bindgen::to_value(&result)
+        .map_err(|err| JsValue::from_str(&format!("Serialization failed: {err}")))
+}
+
+#[wasm_bindgen]
+pub fn run_deck_count_sweep(params: &JsValue) -> Result<JsValue, JsValue> {
+    console_error_panic_hook::set_once();
+    let input: sweep::DeckCountSweepInput = serde_wasm_bindgen::from_value(params.clone())
+        .map_err(|err| JsValue::from_str(&format!("Invalid input: {err}")))?;
+
+    let result = sweep::run_deck_count_sweep(input)
+        .map_err(|err| JsValue::from_str(&format!("Sweep failed: {err}")))?;
+
+    serde_wasm_bindgen::to_value(&result)
+        .map_err(|err| JsValue::from_str(&format!("Serialization failed: {err}")))
+}
+
+#[wasm_bindgen]
+pub fn check_trainer_action(params: &JsValue) -> Result<JsValue, JsValue> {
+    console_error_panic_hook::set_once();
+    let input: trainer::TrainerInput = serde_wasm_bindgen::from_value(params.clone())
+        .map_err(|err| JsValue::from_str(&format!("Invalid input: {err}")))?;
+
+    let result = trainer::check_action(input)
+        .map_err(|err| JsValue::from_str(&format!("Trainer check failed: {err}")))?;
+
+    serde_wasm_bindgen::to_value(&result)
+        .map_err(|err| JsValue::from_str(&format!("Serialization failed: {err}")))
+}
+
+/// Serializes maps as plain objects. The index results flatten their count
+/// tables in, which would otherwise come out as a `Map`, and the tables are
+/// meant to be merged into a strategy input as objects.
+fn to_plain_object<T: Serialize>(value: &T) -> Result<JsValue, JsValue> {
+    value
+        .serialize(&serde_wasm_bindgen::Serializer::new().serialize_maps_as_objects(true))
+        .map_err(|err| JsValue::from_str(&format!("Serialization failed: {err}")))
+}
+
+#[wasm_bindgen]
+pub fn generate_surrender_indices(params: &JsValue) -> Result<JsValue, JsValue> {
+    console_error_panic_hook::set_once();
+    let input: indices::SurrenderIndexInput = serde_wasm_bindgen::from_value(params.clone())
+        .map_err(|err| JsValue::from_str(&format!("Invalid input: {err}")))?;
+
+    let result = indices::generate_surrender_indices(input)
+        .map_err(|err| JsValue::from_str(&format!("Index generation failed: {err}")))?;
+
+    to_plain_object(&result)
+}
+
+/// Finds count indices for playing deviations under any rules and counting
+/// system, with a count-table block ready to load into the strategy.
+#[wasm_bindgen]
+pub fn generate_indices(params: &JsValue) -> Result<JsValue, JsValue> {
+    console_error_panic_hook::set_once();
+    let input: indices::IndexInput = serde_wasm_bindgen::from_value(params.clone())
+        .map_err(|err| JsValue::from_str(&format!("Invalid input: {err}")))?;
+
+    let result = indices::generate_indices(input)
+        .map_err(|err| JsValue::from_str(&format!("Index generation failed: {err}")))?;
+
+    to_plain_object(&result)
+}
+
+#[wasm_bindgen]
+pub fn run_self_test(params: &JsValue) -> Result<JsValue, JsValue> {
+    console_error_panic_hook::set_once();
+    let input: selftest::SelfTestInput = serde_wasm_bindgen::from_value(params.clone())
+        .map_err(|err| JsValue::from_str(&format!("Invalid input: {err}")))?;
+
+    let result = selftest::run_self_test(input)
+        .map_err(|err| JsValue::from_str(&format!("Self-test failed: {err}")))?;
+
+    serde_wasm_bindgen::to_value(&result)
+        .map_err(|err| JsValue::from_str(&format!("Serialization failed: {err}")))
+}
+
+/// Runs a simulation and returns a report document as JSON text: the input
+/// with defaults filled in, engine version, RNG, seed and full result.
+#[wasm_bindgen]
+pub fn export_report(params: &JsValue) -> Result<String, JsValue> {
+    console_error_panic_hook::set_once();
+    let input: sim::SimulationInput = serde_wasm_bindgen::from_value(params.clone())
+        .map_err(|err| JsValue::from_str(&format!("Invalid input: {err}")))?;
+
+    let report = report::build_report(input)
+        .map_err(|err| JsValue::from_str(&format!("Simulation failed: {err}")))?;
+
+    serde_json::to_string(&report).map_err(|err| JsValue::from_str(&format!("Serialization failed: {err}")))
+}
+
+/// Re-runs a report from `export_report` and says whether it still reproduces.
+#[wasm_bindgen]
+pub fn verify_report(report: &str) -> Result<JsValue, JsValue> {
+    console_error_panic_hook::set_once();
+    let report: report::Report =
+        serde_json::from_str(report).map_err(|err| JsValue::from_str(&format!("Invalid input: {err}")))?;
+
+    let result = report::verify_report(report)
+        .map_err(|err| JsValue::from_str(&format!("Verification failed: {err}")))?;
+
+    serde_wasm_bindgen::to_value(&result)
+        .map_err(|err| JsValue::from_str(&format!("Serialization failed: {err}")))
+}
+
+#[wasm_bindgen]
+pub fn run_count_drill(params: &JsValue) -> Result<JsValue, JsValue> {
+    console_error_panic_hook::set_once();
+    let input: drill::CountDrillInput = serde_wasm_bindgen::from_value(params.clone())
+        .map_err(|err| JsValue::from_str(&format!("Invalid input: {err}")))?;
+
+    let result = drill::run_count_drill(input)
+        .map_err(|err| JsValue::from_str(&format!("Count drill failed: {err}")))?;
+
+    serde_wasm_bindgen::to_value(&result)
+        .map_err(|err| JsValue::from_str(&format!("Serialization failed: {err}")))
+}
+
+/// Plays one round. Pass the `session` returned by the previous call to keep
+/// dealing from the same shoe with the same running count; omit it to start
+/// from a freshly shuffled shoe.
+#[wasm_bindgen]
+pub fn play_single_game(params: &JsValue, session: &JsValue) -> Result<JsValue, JsValue> {
     console_error_panic_hook::set_once();
     let input: sim::SimulationInput = serde_wasm_bindgen::from_value(params.clone())
         .map_err(|err| JsValue::from_str(&format!("Invalid input: {err}")))?;
+    let session: Option<game::SessionState> = serde_wasm_bindgen::from_value(session.clone())
+        .map_err(|err| JsValue::from_str(&format!("Invalid input: {err}")))?;
 
+    if input.num_decks == 0 {
+        return Err(JsValue::from_str("Invalid input: a game needs at least one deck"));
+    }
+    input.bet_size.validate()
+        .and_then(|()| input.rules.validate())
+        .map_err(|err| JsValue::from_str(&format!("Invalid input: {err}")))?;
     let strategy = strategy::Strategy::from_input(input.strategy)
         .map_err(|err| JsValue::from_str(&format!("Strategy error: {err}")))?;
-    let penetration = input.rules.penetration_threshold.unwrap_or(75);
-    let deck = deck::Deck::new(input.num_decks, penetration, input.seed);
+    let deck = sim::build_deck(input.num_decks, &input.rules, input.seed);
     let game_rules = sim::to_game_rules(&input.rules);
     let counter = sim::build_counter(input.counting);
     let mut game = game::BlackjackGame::new(deck, game_rules, counter);
+    if let Some(session) = &session {
+        game.restore_session(session)
+            .map_err(|err| JsValue::from_str(&format!("Invalid input: {err}")))?;
+    }
 
-    let bet_size = input.bet_size.max(1.0);
-    let result = game.play_game(&strategy, bet_size);
+    let bet_size = input.bet_size.unit().max(1.0);
+    let mut result = game.play_game(&strategy, bet_size);
+    result.shoe_composition = Some(game.shoe_composition());
 
-    serde_wasm_bindgen::to_value(&result)
-        .map_err(|err| JsValue::from_str(&format!("Serialization failed: {err}")))
+    let output = serde_wasm_bindgen::to_value(&result)
+        .map_err(|err| JsValue::from_str(&format!("Serialization failed: {err}")))?;
+    let session = serde_wasm_bindgen::to_value(&game.save_session())
+        .map_err(|err| JsValue::from_str(&format!("Serialization failed: {err}")))?;
+    Reflect::set(&output, &"session".into(), &session)?;
+    Ok(output)
 }
\ No newline at end of file
@@ -0,0 +1,28 @@
+use crate::sim::SimulationRun;
+
+/// Slot layout of the live stats buffer. Readers should copy the slots and
+/// retry if `SEQUENCE` was odd or changed while they were reading.
+pub const SEQUENCE: usize = 0;
+pub const ROUNDS_COMPLETED: usize = 1;
+pub const ROUNDS_TOTAL: usize = 2;
+pub const EXPECTED_VALUE: usize = 3;
+pub const NET_WINNINGS: usize = 4;
+pub const BANKROLL: usize = 5;
+pub const LIVE_SLOTS: usize = 6;
+
+/// Rounds between writes to the live buffer.
+pub const UPDATE_ROUNDS: u32 = 512;
+
+/// Current values for every slot except `SEQUENCE`.
+pub fn snapshot(run: &SimulationRun, starting_bankroll: f64) -> [f64; LIVE_SLOTS] {
+    let played = run.rounds_played();
+    let net = run.net_winnings();
+    let mut slots = [0.0; LIVE_SLOTS];
+    // Hands rather than rounds when the run counts iterations in hands.
+    slots[ROUNDS_COMPLETED] = run.progress() as f64;
+    slots[ROUNDS_TOTAL] = run.total() as f64;
+    slots[EXPECTED_VALUE] = if played > 0 { net / played as f64 } else { 0.0 };
+    slots[NET_WINNINGS] = net;
+    slots[BANKROLL] = starting_bankroll + net;
+    slots
+}
@@ -0,0 +1,242 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    betting::{RampStep, RecommendedStep},
+    ruin::CountEdge,
+    sim::{self, CountStats, OutputDetail, SimulationInput},
+};
+
+/// Grid points tried between the smallest and largest bet scale that change the ramp.
+const SCALE_STEPS: usize = 400;
+
+fn default_optimizer_bet_size() -> f64 {
+    1.0
+}
+
+fn default_optimizer_kelly_fraction() -> f64 {
+    1.0
+}
+
+/// What the optimized ramp maximizes.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RampObjective {
+    /// Win rate per 100 rounds at optimal betting with a 10,000-unit bankroll,
+    /// i.e. 1,000,000 x EV^2 / variance; the ramp's shape alone sets it.
+    #[default]
+    Score,
+    /// Win per round less the risk cost of its variance for `bankroll` at
+    /// `kelly_fraction`.
+    CertaintyEquivalent,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RampOptimizerInput {
+    #[serde(default)]
+    pub objective: RampObjective,
+    /// Per-count edges to optimize over, or a counting simulation to measure them from.
+    #[serde(default)]
+    pub counts: Vec<CountEdge>,
+    #[serde(default)]
+    pub simulation: Option<SimulationInput>,
+    /// Smallest bet, and the unit of the returned ramp.
+    #[serde(default = "default_optimizer_bet_size")]
+    pub bet_size: f64,
+    /// Largest bet over the smallest.
+    pub max_spread: f64,
+    #[serde(default)]
+    pub table_min: Option<f64>,
+    #[serde(default)]
+    pub table_max: Option<f64>,
+    /// Needed for the certainty-equivalent objective.
+    #[serde(default)]
+    pub bankroll: Option<f64>,
+    #[serde(default = "default_optimizer_kelly_fraction")]
+    pub kelly_fraction: f64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RampOptimization {
+    pub objective: RampObjective,
+    /// The best ramp in units of `bet_size`, ready to use as `bet_ramp`.
+    pub bet_ramp: Vec<RampStep>,
+    pub steps: Vec<RecommendedStep>,
+    pub spread: f64,
+    pub ev_per_round: f64,
+    pub std_dev_per_round: f64,
+    pub score: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub certainty_equivalent: Option<f64>,
+}
+
+fn edges_from_count_stats(stats: &CountStats) -> Vec<CountEdge> {
+    let total = stats.total_hands.max(1) as f64;
+    stats
+        .hands_by_count
+        .iter()
+        .filter_map(|(key, &hands)| {
+            Some(CountEdge {
+                true_count: key.parse().ok()?,
+                frequency: hands as f64 / total,
+                advantage: stats.advantage_by_count.get(key).copied().unwrap_or(0.0),
+                variance: stats.variance_by_count.get(key).copied().unwrap_or(0.0),
+            })
+        })
+        .collect()
+}
+
+/// Win rate and variance per round of betting `bets` at the matching counts.
+fn moments(edges: &[CountEdge], bets: &[f64]) -> (f64, f64) {
+    let total: f64 = edges.iter().map(|edge| edge.frequency).sum();
+    let mut ev = 0.0;
+    let mut second_moment = 0.0;
+    for (edge, bet) in edges.iter().zip(bets) {
+        let frequency = edge.frequency / total;
+        ev += frequency * bet * edge.advantage;
+        second_moment += frequency * bet * bet * (edge.variance + edge.advantage * edge.advantage);
+    }
+    (ev, (second_moment - ev * ev).max(0.0))
+}
+
+/// Weighted isotonic fit of `values`, so it never falls from one entry to the
+/// next; sparse counts with noisy edges get pooled with their neighbours.
+fn non_decreasing(values: &[f64], weights: &[f64]) -> Vec<f64> {
+    // Pools of (mean, weight, entries), merged while a pool sits below the one before it.
+    let mut pools: Vec<(f64, f64, usize)> = Vec::new();
+    for (&value, &weight) in values.iter().zip(weights) {
+        let mut pool = (value, weight, 1);
+        while let Some(&(mean, total, len)) = pools.last().filter(|last| last.0 >= pool.0) {
+            pools.pop();
+            let merged = total + pool.1;
+            let mean = if merged > 0.0 { (mean * total + pool.0 * pool.1) / merged } else { mean.max(pool.0) };
+            pool = (mean, merged, len + pool.2);
+        }
+        pools.push(pool);
+    }
+    pools.into_iter().flat_map(|(mean, _, len)| std::iter::repeat_n(mean, len)).collect()
+}
+
+fn score(ev: f64, variance: f64) -> f64 {
+    if variance > 0.0 && ev > 0.0 {
+        1_000_000.0 * ev * ev / variance
+    } else {
+        0.0
+    }
+}
+
+/// Searches bets proportional to each count's advantage over variance, held
+/// between the smallest and largest allowed bet, for the best objective. The
+/// ramp never drops as the count rises.
+pub fn optimize_bet_ramp(input: RampOptimizerInput) -> Result<RampOptimization, String> {
+    if !input.max_spread.is_finite() || input.max_spread < 1.0 {
+        return Err("max_spread must be at least 1".to_string());
+    }
+    if !input.bet_size.is_finite() || input.bet_size <= 0.0 {
+        return Err("bet_size must be positive".to_string());
+    }
+    let risk_bankroll = match input.objective {
+        RampObjective::Score => None,
+        RampObjective::CertaintyEquivalent => match input.bankroll.filter(|b| b.is_finite() && *b > 0.0) {
+            Some(bankroll) if input.kelly_fraction.is_finite() && input.kelly_fraction > 0.0 => {
+                Some(bankroll * input.kelly_fraction)
+            }
+            _ => return Err("certainty equivalent needs a positive bankroll and kelly_fraction".to_string()),
+        },
+    };
+
+    let mut edges = match (&input.simulation, input.counts.is_empty()) {
+        (_, false) => input.counts,
+        (Some(simulation), true) => {
+            let mut simulation = simulation.clone();
+            simulation.output_detail = OutputDetail::Standard;
+            let result = sim::run(simulation)?;
+            let stats = result.count_stats.ok_or("the simulation needs counting enabled")?;
+            edges_from_count_stats(&stats)
+        }
+        (None, true) => return Err("ramp optimizer needs counts or a counting simulation".to_string()),
+    };
+    if edges.iter().any(|edge| !edge.frequency.is_finite() || edge.frequency < 0.0) {
+        return Err("count frequencies must be zero or positive".to_string());
+    }
+    if !edges.iter().any(|edge| edge.frequency > 0.0) {
+        return Err("ramp optimizer needs at least one count with a positive frequency".to_string());
+    }
+    edges.sort_by_key(|edge| edge.true_count);
+
+    let min_bet = input.table_min.map_or(input.bet_size, |min| input.bet_size.max(min));
+    let max_bet = input.table_max.map_or(min_bet * input.max_spread, |max| max.min(min_bet * input.max_spread));
+    if max_bet < min_bet {
+        return Err(format!("table_max {max_bet} is below the smallest bet {min_bet}"));
+    }
+
+    let ratios: Vec<f64> = edges
+        .iter()
+        .map(|edge| if edge.variance > 0.0 { edge.advantage / edge.variance } else { 0.0 })
+        .collect();
+    let weights: Vec<f64> = edges.iter().map(|edge| edge.frequency).collect();
+    let ratios = non_decreasing(&ratios, &weights);
+    let bets_at = |scale: f64| -> Vec<f64> {
+        ratios.iter().map(|ratio| (scale * ratio).clamp(min_bet, max_bet)).collect()
+    };
+    let objective = |bets: &[f64]| {
+        let (ev, variance) = moments(&edges, bets);
+        match risk_bankroll {
+            Some(bankroll) => ev - variance / (2.0 * bankroll),
+            None => score(ev, variance),
+        }
+    };
+
+    // Scales below the first breakpoint bet the minimum everywhere and scales
+    // past the last bet the maximum wherever there is an edge.
+    let positive = ratios.iter().copied().filter(|ratio| *ratio > 0.0);
+    let low = positive.clone().map(|ratio| min_bet / ratio).fold(f64::INFINITY, f64::min);
+    let high = positive.map(|ratio| max_bet / ratio).fold(0.0, f64::max);
+    let mut best = bets_at(0.0);
+    let mut best_value = objective(&best);
+    if low.is_finite() && high > 0.0 {
+        for step in 0..=SCALE_STEPS {
+            let scale = low * (high / low).powf(step as f64 / SCALE_STEPS as f64);
+            let bets = bets_at(scale);
+            let value = objective(&bets);
+            if value > best_value {
+                best_value = value;
+                best = bets;
+            }
+        }
+    }
+
+    let (ev, variance) = moments(&edges, &best);
+    let total: f64 = edges.iter().map(|edge| edge.frequency).sum();
+    let steps = edges
+        .iter()
+        .zip(&best)
+        .map(|(edge, &bet)| RecommendedStep {
+            true_count: edge.true_count,
+            bet,
+            advantage: edge.advantage,
+            variance: edge.variance,
+            frequency: edge.frequency / total,
+        })
+        .collect();
+    let bet_ramp = edges
+        .iter()
+        .zip(&best)
+        .map(|(edge, bet)| RampStep {
+            true_count: edge.true_count,
+            units: bet / input.bet_size,
+        })
+        .collect();
+    let lowest = best.iter().copied().fold(f64::INFINITY, f64::min);
+    let highest = best.iter().copied().fold(0.0, f64::max);
+    Ok(RampOptimization {
+        objective: input.objective,
+        bet_ramp,
+        steps,
+        spread: highest / lowest,
+        ev_per_round: ev,
+        std_dev_per_round: variance.sqrt(),
+        score: score(ev, variance),
+        certainty_equivalent: risk_bankroll.map(|bankroll| ev - variance / (2.0 * bankroll)),
+    })
+}
@@ -0,0 +1,256 @@
+use serde::{Deserialize, Serialize};
+
+/// Outcome of the previous round that was bet.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundOutcome {
+    Win,
+    Loss,
+    Push,
+}
+
+/// What a matching rule does to the bet, in units of the base bet.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BetAction {
+    /// Bet this many units; zero sits the round out.
+    Set(f64),
+    Multiply(f64),
+    /// Back to one unit.
+    Reset,
+}
+
+/// Conditions a rule needs; every one that is given must hold.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BetCondition {
+    #[serde(default)]
+    pub last_result: Option<RoundOutcome>,
+    /// Consecutive wins or losses up to the last round; a push ends both.
+    #[serde(default)]
+    pub min_win_streak: Option<u32>,
+    #[serde(default)]
+    pub min_loss_streak: Option<u32>,
+    /// Rounded true count bounds, both inclusive. Need counting.
+    #[serde(default)]
+    pub min_true_count: Option<i32>,
+    #[serde(default)]
+    pub max_true_count: Option<i32>,
+    /// Bankroll bounds: the policy's starting bankroll plus the net result so far.
+    #[serde(default)]
+    pub bankroll_below: Option<f64>,
+    #[serde(default)]
+    pub bankroll_at_least: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BetRule {
+    #[serde(default)]
+    pub when: BetCondition,
+    pub then: BetAction,
+}
+
+/// A user betting system: before each round the first rule whose conditions
+/// hold changes the bet, e.g.
+/// `{"rules": [{"when": {"last_result": "loss"}, "then": {"multiply": 2}}, {"then": "reset"}]}`.
+/// Without a matching rule the bet stays as it was.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BetPolicy {
+    pub rules: Vec<BetRule>,
+    #[serde(default)]
+    pub starting_bankroll: f64,
+    /// Largest bet in base-bet units; larger bets are cut to it.
+    #[serde(default)]
+    pub max_units: Option<f64>,
+}
+
+impl BetPolicy {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.rules.is_empty() {
+            return Err("bet policy needs at least one rule".to_string());
+        }
+        if !self.starting_bankroll.is_finite() {
+            return Err("bet policy starting_bankroll must be finite".to_string());
+        }
+        if self.max_units.is_some_and(|max_units| !max_units.is_finite() || max_units < 0.0) {
+            return Err("bet policy max_units must be zero or positive".to_string());
+        }
+        for (index, rule) in self.rules.iter().enumerate() {
+            let amount = match rule.then {
+                BetAction::Set(units) | BetAction::Multiply(units) => units,
+                BetAction::Reset => 0.0,
+            };
+            if !amount.is_finite() || amount < 0.0 {
+                return Err(format!("bet policy rule {index} needs a finite, non-negative amount"));
+            }
+            let bounds = [rule.when.bankroll_below, rule.when.bankroll_at_least];
+            if bounds.iter().flatten().any(|bound| !bound.is_finite()) {
+                return Err(format!("bet policy rule {index} has a non-finite bankroll bound"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether any rule looks at the true count.
+    pub fn uses_count(&self) -> bool {
+        self.rules.iter().any(|rule| rule.when.min_true_count.is_some() || rule.when.max_true_count.is_some())
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BetPolicyStats {
+    pub rounds: u32,
+    /// Mean and largest bet per spot.
+    pub mean_bet: f64,
+    pub largest_bet: f64,
+    pub final_bankroll: f64,
+    /// Rounds each rule set the bet for, in rule order.
+    pub rule_fires: Vec<u32>,
+}
+
+/// A bet policy applied through a run. The bet worked out for a round only
+/// sticks once that round is played, so rounds sat out leave it unchanged.
+pub struct PolicyBettor {
+    policy: BetPolicy,
+    units: f64,
+    pending: Option<(usize, f64)>,
+    last_result: Option<RoundOutcome>,
+    win_streak: u32,
+    loss_streak: u32,
+    bankroll: f64,
+    rounds: u32,
+    total_bet: f64,
+    largest_bet: f64,
+    rule_fires: Vec<u32>,
+}
+
+impl PolicyBettor {
+    pub fn new(policy: BetPolicy) -> Self {
+        PolicyBettor {
+            units: 1.0,
+            pending: None,
+            last_result: None,
+            win_streak: 0,
+            loss_streak: 0,
+            bankroll: policy.starting_bankroll,
+            rounds: 0,
+            total_bet: 0.0,
+            largest_bet: 0.0,
+            rule_fires: vec![0; policy.rules.len()],
+            policy,
+        }
+    }
+
+    fn holds(&self, when: &BetCondition, true_count: i32) -> bool {
+        when.last_result.is_none_or(|result| self.last_result == Some(result))
+            && when.min_win_streak.is_none_or(|streak| self.win_streak >= streak)
+            && when.min_loss_streak.is_none_or(|streak| self.loss_streak >= streak)
+            && when.min_true_count.is_none_or(|count| true_count >= count)
+            && when.max_true_count.is_none_or(|count| true_count <= count)
+            && when.bankroll_below.is_none_or(|bound| self.bankroll < bound)
+            && when.bankroll_at_least.is_none_or(|bound| self.bankroll >= bound)
+    }
+
+    /// Units of the base bet for the next round at `true_count`.
+    pub fn units(&mut self, true_count: i32) -> f64 {
+        let matched = self.policy.rules.iter().position(|rule| self.holds(&rule.when, true_count));
+        let units = match matched.map(|index| &self.policy.rules[index].then) {
+            Some(BetAction::Set(units)) => *units,
+            Some(BetAction::Multiply(factor)) => self.units * factor,
+            Some(BetAction::Reset) => 1.0,
+            None => self.units,
+        };
+        let units = self.policy.max_units.map_or(units, |max_units| units.min(max_units));
+        self.pending = matched.map(|index| (index, units));
+        units
+    }
+
+    /// Applies one played round: `bet` is what was staked per spot after table
+    /// limits and `winnings` the net result of the whole round.
+    pub fn record(&mut self, bet: f64, winnings: f64) {
+        if let Some((index, units)) = self.pending.take() {
+            self.rule_fires[index] += 1;
+            self.units = units;
+        }
+        self.rounds += 1;
+        self.total_bet += bet;
+        self.largest_bet = self.largest_bet.max(bet);
+        self.bankroll += winnings;
+        let result = if winnings > 0.0 {
+            RoundOutcome::Win
+        } else if winnings < 0.0 {
+            RoundOutcome::Loss
+        } else {
+            RoundOutcome::Push
+        };
+        self.win_streak = if result == RoundOutcome::Win { self.win_streak + 1 } else { 0 };
+        self.loss_streak = if result == RoundOutcome::Loss { self.loss_streak + 1 } else { 0 };
+        self.last_result = Some(result);
+    }
+
+    pub fn finish(self) -> BetPolicyStats {
+        BetPolicyStats {
+            rounds: self.rounds,
+            mean_bet: if self.rounds > 0 { self.total_bet / self.rounds as f64 } else { 0.0 },
+            largest_bet: self.largest_bet,
+            final_bankroll: self.bankroll,
+            rule_fires: self.rule_fires,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bettor(rules: serde_json::Value) -> PolicyBettor {
+        let policy: BetPolicy = serde_json::from_value(serde_json::json!({ "rules": rules })).unwrap();
+        policy.validate().unwrap();
+        PolicyBettor::new(policy)
+    }
+
+    #[test]
+    fn martingale_doubles_after_losses_and_resets_on_a_win() {
+        let mut bettor = bettor(serde_json::json!([
+            {"when": {"last_result": "loss"}, "then": {"multiply": 2}},
+            {"then": "reset"}
+        ]));
+        let mut bets = Vec::new();
+        for winnings in [-1.0, -2.0, 4.0] {
+            let units = bettor.units(0);
+            bets.push(units);
+            bettor.record(units, winnings);
+        }
+        bets.push(bettor.units(0));
+        assert_eq!(bets, [1.0, 2.0, 4.0, 1.0]);
+        assert_eq!(bettor.finish().rule_fires, [2, 1]);
+    }
+
+    #[test]
+    fn push_ends_a_streak() {
+        let mut bettor = bettor(serde_json::json!([
+            {"when": {"min_loss_streak": 2}, "then": {"set": 3}},
+            {"then": {"set": 1}}
+        ]));
+        let mut bets = Vec::new();
+        for winnings in [-1.0, -1.0, 0.0, -1.0] {
+            let units = bettor.units(0);
+            bets.push(units);
+            bettor.record(units, winnings);
+        }
+        bets.push(bettor.units(0));
+        assert_eq!(bets, [1.0, 1.0, 3.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn rounds_sat_out_leave_the_bet_unchanged() {
+        let mut bettor = bettor(serde_json::json!([
+            {"when": {"min_true_count": 2}, "then": {"multiply": 2}}
+        ]));
+        assert_eq!(bettor.units(3), 2.0);
+        assert_eq!(bettor.units(3), 2.0);
+        bettor.record(2.0, 2.0);
+        assert_eq!(bettor.units(3), 4.0);
+        assert_eq!(bettor.units(0), 2.0);
+    }
+}
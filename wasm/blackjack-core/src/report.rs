@@ -0,0 +1,135 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    selftest::{run_hashed, HashedRun},
+    sim::{self, SimulationInput},
+};
+
+/// Bumped whenever the report layout changes.
+pub const REPORT_VERSION: u32 = 1;
+/// Generator behind every shuffle; a different one deals different cards
+/// from the same seed.
+const RNG_SPEC: &str = "rand 0.8 SmallRng seeded with seed_from_u64, Fisher-Yates shuffle";
+/// Relative tolerance for numbers in a re-verified result, which may have
+/// lost their last bit on a trip through JSON text.
+const NUMBER_TOLERANCE: f64 = 1e-9;
+
+/// Everything needed to reproduce a simulation: the input with every default
+/// filled in, the engine and generator it ran on, the outcome hash and the
+/// full result. Archive it as JSON and hand it back to `verify_report`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Report {
+    pub report_version: u32,
+    pub engine_version: String,
+    pub rng: String,
+    pub seed: u64,
+    /// Kept in the snake_case input format so it can be run again as is.
+    pub input: SimulationInput,
+    /// The rules the engine actually dealt under, every default resolved, so
+    /// a changed engine default shows up on verification.
+    #[serde(default)]
+    pub resolved_rules: Value,
+    pub outcome_hash: String,
+    pub result: Value,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportVerification {
+    /// The outcome hash and the result both match.
+    pub passed: bool,
+    pub hash_matches: bool,
+    pub outcome_hash: String,
+    /// Engine the report was made with, when it differs from this one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recorded_engine_version: Option<String>,
+    /// Paths into `result` whose values changed, e.g. `expectedValue`, or into
+    /// the resolved rules, e.g. `resolvedRules.insurance`.
+    pub differences: Vec<String>,
+}
+
+pub fn build_report(input: SimulationInput) -> Result<Report, String> {
+    let HashedRun { result, outcome_hash, .. } = run_hashed(input.clone())?;
+    let result = serde_json::to_value(&result).map_err(|err| err.to_string())?;
+    let resolved_rules = serde_json::to_value(sim::to_game_rules(&input.rules)).map_err(|err| err.to_string())?;
+    Ok(Report {
+        report_version: REPORT_VERSION,
+        engine_version: env!("CARGO_PKG_VERSION").to_string(),
+        rng: RNG_SPEC.to_string(),
+        seed: input.seed,
+        input,
+        resolved_rules,
+        outcome_hash: outcome_hash.hex(),
+        result,
+    })
+}
+
+/// Re-runs a report's input and checks the outcome hash and every result
+/// field against the recorded ones.
+pub fn verify_report(report: Report) -> Result<ReportVerification, String> {
+    if report.report_version > REPORT_VERSION {
+        return Err(format!(
+            "report version {} is newer than this engine supports ({REPORT_VERSION})",
+            report.report_version
+        ));
+    }
+    if report.seed != report.input.seed {
+        return Err(format!("report seed {} does not match its input seed {}", report.seed, report.input.seed));
+    }
+    let engine_version = env!("CARGO_PKG_VERSION");
+    let rebuilt = build_report(report.input)?;
+    let mut differences = Vec::new();
+    diff_values("", &report.result, &rebuilt.result, &mut differences);
+    // Reports from before the rules were recorded have nothing to compare.
+    if !report.resolved_rules.is_null() {
+        diff_values("resolvedRules", &report.resolved_rules, &rebuilt.resolved_rules, &mut differences);
+    }
+    let hash_matches = report.outcome_hash.trim().eq_ignore_ascii_case(&rebuilt.outcome_hash);
+    Ok(ReportVerification {
+        passed: hash_matches && differences.is_empty(),
+        hash_matches,
+        outcome_hash: rebuilt.outcome_hash,
+        recorded_engine_version: (report.engine_version != engine_version).then_some(report.engine_version),
+        differences,
+    })
+}
+
+fn diff_values(path: &str, recorded: &Value, actual: &Value, differences: &mut Vec<String>) {
+    let child = |key: &dyn std::fmt::Display| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{path}.{key}")
+        }
+    };
+    match (recorded, actual) {
+        (Value::Object(recorded), Value::Object(actual)) => {
+            let mut keys: Vec<&String> = recorded.keys().chain(actual.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                match (recorded.get(key), actual.get(key)) {
+                    (Some(recorded), Some(actual)) => diff_values(&child(key), recorded, actual, differences),
+                    _ => differences.push(child(key)),
+                }
+            }
+        }
+        (Value::Array(recorded), Value::Array(actual)) if recorded.len() == actual.len() => {
+            for (index, (recorded, actual)) in recorded.iter().zip(actual).enumerate() {
+                diff_values(&child(&index), recorded, actual, differences);
+            }
+        }
+        (Value::Number(recorded), Value::Number(actual)) => {
+            let (Some(recorded), Some(actual)) = (recorded.as_f64(), actual.as_f64()) else {
+                return;
+            };
+            if (recorded - actual).abs() > NUMBER_TOLERANCE * recorded.abs().max(actual.abs()).max(1.0) {
+                differences.push(path.to_string());
+            }
+        }
+        _ if recorded != actual => differences.push(path.to_string()),
+        _ => {}
+    }
+}
@@ -0,0 +1,195 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bankroll::{BankrollEvaluationInput, BankrollOutcome},
+    betting::{self, RampStep},
+    sim::{self, SimulationInput},
+};
+
+fn default_ruin_bet_size() -> f64 {
+    1.0
+}
+
+/// Player edge and spread at one true count, per unit bet.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CountEdge {
+    pub true_count: i32,
+    /// Share of rounds dealt at this count; rescaled to sum to one.
+    pub frequency: f64,
+    pub advantage: f64,
+    pub variance: f64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RiskOfRuinInput {
+    pub bankroll: f64,
+    /// Win rate and spread per round, e.g. `evPerRound` and `stdDevPerRound`
+    /// from a simulation result.
+    #[serde(default)]
+    pub ev_per_round: Option<f64>,
+    #[serde(default)]
+    pub std_dev_per_round: Option<f64>,
+    /// A bet ramp in units of `bet_size`. With `counts` its win rate and spread
+    /// are worked out from them; with `simulation` it replaces the simulated ramp.
+    #[serde(default)]
+    pub bet_ramp: Option<Vec<RampStep>>,
+    #[serde(default)]
+    pub counts: Vec<CountEdge>,
+    #[serde(default = "default_ruin_bet_size")]
+    pub bet_size: f64,
+    /// Bankroll to reach before going broke, for the chance of getting there.
+    #[serde(default)]
+    pub target_bankroll: Option<f64>,
+    /// Rounds per session, for the risk of going broke within one session.
+    #[serde(default)]
+    pub session_rounds: Option<u32>,
+    /// Simulated and cut into sessions of `session_rounds` for an empirical
+    /// estimate; its win rate and spread are used when none are given.
+    #[serde(default)]
+    pub simulation: Option<SimulationInput>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RiskOfRuinResult {
+    pub bankroll: f64,
+    pub ev_per_round: f64,
+    pub std_dev_per_round: f64,
+    /// Chance of ever going broke playing on without end.
+    pub risk_of_ruin: f64,
+    /// Chance of going broke within `session_rounds`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_risk_of_ruin: Option<f64>,
+    /// Share of simulated sessions that went broke.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub empirical: Option<BankrollOutcome>,
+    /// Chance of reaching `target_bankroll` before going broke, and the share
+    /// of simulated attempts that did.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_probability: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub empirical_target_probability: Option<f64>,
+}
+
+/// Standard normal CDF, from the Abramowitz and Stegun erfc approximation.
+fn normal_cdf(x: f64) -> f64 {
+    let z = x.abs() / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.327_591_1 * z);
+    let poly = t
+        * (0.254_829_592 + t * (-0.284_496_736 + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let erfc = poly * (-z * z).exp();
+    if x >= 0.0 {
+        1.0 - erfc / 2.0
+    } else {
+        erfc / 2.0
+    }
+}
+
+/// Chance a random walk with this drift and variance per round falls by
+/// `bankroll` within `rounds` rounds.
+fn session_risk_of_ruin(bankroll: f64, ev: f64, variance: f64, rounds: u32) -> f64 {
+    if variance <= 0.0 || rounds == 0 {
+        return if ev * rounds as f64 <= -bankroll { 1.0 } else { 0.0 };
+    }
+    let n = rounds as f64;
+    let spread = (variance * n).sqrt();
+    let risk = normal_cdf((-bankroll - ev * n) / spread)
+        + (-2.0 * ev * bankroll / variance).exp() * normal_cdf((-bankroll + ev * n) / spread);
+    if risk.is_nan() {
+        1.0
+    } else {
+        risk.clamp(0.0, 1.0)
+    }
+}
+
+/// Win rate and variance per round of `ramp` over the per-count edges.
+fn ramp_moments(ramp: &[RampStep], counts: &[CountEdge], bet_size: f64) -> (f64, f64) {
+    let total: f64 = counts.iter().map(|count| count.frequency).sum();
+    let mut ev = 0.0;
+    let mut second_moment = 0.0;
+    for count in counts {
+        let bet = bet_size * betting::ramp_units(ramp, count.true_count);
+        let frequency = count.frequency / total;
+        ev += frequency * bet * count.advantage;
+        second_moment += frequency * bet * bet * (count.variance + count.advantage * count.advantage);
+    }
+    (ev, (second_moment - ev * ev).max(0.0))
+}
+
+pub fn calculate_risk_of_ruin(input: RiskOfRuinInput) -> Result<RiskOfRuinResult, String> {
+    if !input.bankroll.is_finite() || input.bankroll <= 0.0 {
+        return Err("risk of ruin needs a positive bankroll".to_string());
+    }
+    if input.target_bankroll.is_some_and(|target| !target.is_finite() || target <= input.bankroll) {
+        return Err("target_bankroll must be above the bankroll".to_string());
+    }
+    if let Some(ramp) = &input.bet_ramp {
+        betting::validate_ramp(ramp)?;
+    }
+    if input.counts.iter().any(|count| !count.frequency.is_finite() || count.frequency < 0.0) {
+        return Err("count frequencies must be zero or positive".to_string());
+    }
+    if !input.counts.is_empty() && !input.counts.iter().any(|count| count.frequency > 0.0) {
+        return Err("count frequencies must not all be zero".to_string());
+    }
+
+    let empirical = match &input.simulation {
+        Some(simulation) => {
+            let Some(session_rounds) = input.session_rounds.filter(|&rounds| rounds > 0) else {
+                return Err("simulation needs session_rounds to cut it into sessions".to_string());
+            };
+            let mut simulation = simulation.clone();
+            if let Some(ramp) = &input.bet_ramp {
+                simulation.bet_ramp = Some(ramp.clone());
+            }
+            simulation.bankroll_evaluation = Some(BankrollEvaluationInput {
+                bankrolls: vec![input.bankroll],
+                trip_rounds: Some(session_rounds),
+                win_goal: None,
+            });
+            if input.target_bankroll.is_some() {
+                simulation.starting_bankroll = Some(input.bankroll);
+                simulation.target_bankroll = input.target_bankroll;
+                simulation.stop_on_ruin = false;
+            }
+            let result = sim::run(simulation)?;
+            Some(result)
+        }
+        None => None,
+    };
+
+    let (ev, variance) = match (input.ev_per_round, input.std_dev_per_round, &input.bet_ramp, &empirical) {
+        (Some(ev), Some(sd), _, _) => (ev, sd * sd),
+        (_, _, Some(ramp), _) if !input.counts.is_empty() => ramp_moments(ramp, &input.counts, input.bet_size),
+        (_, _, _, Some(result)) => {
+            let ev = result.total_winnings / result.rounds_played.max(1) as f64;
+            (ev, result.std_dev_per_round * result.std_dev_per_round)
+        }
+        _ => {
+            return Err(
+                "risk of ruin needs ev_per_round and std_dev_per_round, a bet ramp with counts, or a simulation"
+                    .to_string(),
+            )
+        }
+    };
+    if !ev.is_finite() || !variance.is_finite() {
+        return Err("win rate and spread must be finite".to_string());
+    }
+
+    Ok(RiskOfRuinResult {
+        bankroll: input.bankroll,
+        ev_per_round: ev,
+        std_dev_per_round: variance.sqrt(),
+        risk_of_ruin: betting::risk_of_ruin(input.bankroll, ev, variance),
+        session_risk_of_ruin: input
+            .session_rounds
+            .map(|rounds| session_risk_of_ruin(input.bankroll, ev, variance, rounds)),
+        target_probability: input
+            .target_bankroll
+            .map(|target| betting::target_probability(input.bankroll, target, ev, variance)),
+        empirical_target_probability: empirical
+            .as_ref()
+            .and_then(|result| result.bankroll.as_ref()?.target_probability),
+        empirical: empirical.and_then(|result| result.bankroll_outcomes.into_iter().next()),
+    })
+}
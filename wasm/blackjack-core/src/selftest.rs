@@ -0,0 +1,134 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    deck::Card,
+    game::GameResult,
+    sim::{SimulationInput, SimulationResult, SimulationRun},
+    strategy::Strategy,
+};
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+#[derive(Debug, Deserialize)]
+pub struct SelfTestInput {
+    /// Configuration to play; with a fixed seed the outcome stream is fixed too.
+    pub simulation: SimulationInput,
+    /// Hash an earlier engine build produced for the same input.
+    #[serde(default)]
+    pub expected_hash: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfTestResult {
+    /// 64-bit FNV-1a hash of every hand's cards, outcome and winnings, as hex.
+    pub hash: String,
+    /// Whether `hash` matches the expected one; absent when none was given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub passed: Option<bool>,
+    pub rounds: u32,
+    pub hands: u32,
+    pub expected_value: f64,
+}
+
+/// Running hash over the per-hand outcome stream of a simulation. It only
+/// reads cards, results and exact bit patterns of amounts, so any change to
+/// dealing, play or settlement shows up as a different hash.
+#[derive(Clone, Debug)]
+pub struct OutcomeHash {
+    state: u64,
+    hands: u32,
+}
+
+impl Default for OutcomeHash {
+    fn default() -> Self {
+        OutcomeHash {
+            state: FNV_OFFSET,
+            hands: 0,
+        }
+    }
+}
+
+impl OutcomeHash {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.state ^= *byte as u64;
+            self.state = self.state.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn write_cards(&mut self, cards: &[Card]) {
+        for card in cards {
+            self.write(card.rank.as_bytes());
+            self.write(b",");
+        }
+        self.write(b";");
+    }
+
+    pub fn record(&mut self, result: &GameResult) {
+        self.hands += 1;
+        self.write_cards(&result.dealer_cards);
+        for hand in &result.hands {
+            self.write_cards(&hand.cards);
+            self.write(hand.result.as_deref().unwrap_or("").as_bytes());
+            self.write(&hand.bet.to_bits().to_le_bytes());
+        }
+        self.write(result.outcome.as_bytes());
+        self.write(&result.winnings.to_bits().to_le_bytes());
+        self.write(b"|");
+    }
+
+    pub fn hands(&self) -> u32 {
+        self.hands
+    }
+
+    pub fn hex(&self) -> String {
+        format!("{:016x}", self.state)
+    }
+}
+
+/// A simulation played to the end with its outcome stream hashed.
+pub struct HashedRun {
+    pub result: SimulationResult,
+    pub outcome_hash: OutcomeHash,
+    /// Rounds dealt, bet or not.
+    pub rounds: u32,
+}
+
+pub fn run_hashed(input: SimulationInput) -> Result<HashedRun, String> {
+    let strategy = Strategy::from_input(input.strategy.clone())?;
+    let mut run = SimulationRun::new(strategy, input)?;
+    run.enable_outcome_hash();
+    while !run.is_done() {
+        run.play_round();
+    }
+    let outcome_hash = run.outcome_hash().cloned().unwrap_or_default();
+    let rounds = run.completed();
+    Ok(HashedRun {
+        result: run.finish()?,
+        outcome_hash,
+        rounds,
+    })
+}
+
+/// Plays the simulation while hashing its outcomes and checks the hash
+/// against the expected one, so frontends and CI can spot engine drift.
+pub fn run_self_test(input: SelfTestInput) -> Result<SelfTestResult, String> {
+    let HashedRun {
+        result,
+        outcome_hash,
+        rounds,
+    } = run_hashed(input.simulation)?;
+    let hash = outcome_hash.hex();
+    let passed = input
+        .expected_hash
+        .map(|expected| expected.trim().eq_ignore_ascii_case(&hash));
+    Ok(SelfTestResult {
+        hash,
+        passed,
+        rounds,
+        hands: outcome_hash.hands(),
+        expected_value: result.expected_value,
+    })
+}
@@ -0,0 +1,198 @@
+use serde::{Deserialize, Serialize};
+
+fn default_series_points() -> u32 {
+    500
+}
+
+/// Raw samples kept per series, as a multiple of the requested point count.
+const RAW_POINTS_FACTOR: usize = 16;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Decimation {
+    /// Largest-triangle-three-buckets: keeps the visual shape of the curve.
+    #[default]
+    Lttb,
+    /// Evenly spaced samples.
+    Stride,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeriesInput {
+    /// Target number of points per series.
+    #[serde(default = "default_series_points")]
+    pub points: u32,
+    #[serde(default)]
+    pub method: Decimation,
+}
+
+/// One decimated series as parallel arrays of round numbers and values.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeriesData {
+    pub rounds: Vec<u32>,
+    pub values: Vec<f64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulationSeries {
+    /// Cumulative net winnings after each round.
+    pub bankroll: SeriesData,
+    /// Running EV per played round.
+    pub expected_value: SeriesData,
+    /// True count before each round, when counting is enabled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub true_count: Option<SeriesData>,
+}
+
+/// Keeps a bounded sample of a series. Under LTTB every sample is recorded
+/// and a full buffer is decimated to half its size with LTTB itself, so the
+/// peaks and troughs survive; under stride every other sample is dropped and
+/// the sampling stride doubles.
+struct SampleBuffer {
+    method: Decimation,
+    stride: u32,
+    cap: usize,
+    rounds: Vec<u32>,
+    values: Vec<f64>,
+    last: Option<(u32, f64)>,
+}
+
+impl SampleBuffer {
+    fn new(cap: usize, method: Decimation) -> Self {
+        SampleBuffer {
+            method,
+            stride: 1,
+            cap: cap.max(4),
+            rounds: Vec::new(),
+            values: Vec::new(),
+            last: None,
+        }
+    }
+
+    fn push(&mut self, round: u32, value: f64) {
+        self.last = Some((round, value));
+        if !(round - 1).is_multiple_of(self.stride) {
+            return;
+        }
+        self.rounds.push(round);
+        self.values.push(value);
+        if self.rounds.len() > self.cap {
+            match self.method {
+                Decimation::Lttb => {
+                    let kept = lttb(&self.rounds, &self.values, self.cap / 2);
+                    (self.rounds, self.values) = (kept.rounds, kept.values);
+                }
+                Decimation::Stride => {
+                    self.rounds = self.rounds.iter().copied().step_by(2).collect();
+                    self.values = self.values.iter().copied().step_by(2).collect();
+                    self.stride *= 2;
+                }
+            }
+        }
+    }
+
+    fn finish(mut self, points: usize) -> SeriesData {
+        if let Some((round, value)) = self.last {
+            if self.rounds.last() != Some(&round) {
+                self.rounds.push(round);
+                self.values.push(value);
+            }
+        }
+        match self.method {
+            Decimation::Lttb => lttb(&self.rounds, &self.values, points),
+            Decimation::Stride => stride(&self.rounds, &self.values, points),
+        }
+    }
+}
+
+pub struct SeriesRecorder {
+    points: usize,
+    bankroll: SampleBuffer,
+    expected_value: SampleBuffer,
+    true_count: Option<SampleBuffer>,
+}
+
+impl SeriesRecorder {
+    pub fn new(input: &SeriesInput, counting: bool) -> Self {
+        let points = input.points.max(3) as usize;
+        let cap = points * RAW_POINTS_FACTOR;
+        let method = input.method;
+        SeriesRecorder {
+            points,
+            bankroll: SampleBuffer::new(cap, method),
+            expected_value: SampleBuffer::new(cap, method),
+            true_count: counting.then(|| SampleBuffer::new(cap, method)),
+        }
+    }
+
+    /// `round` counts from 1.
+    pub fn record(&mut self, round: u32, net_winnings: f64, expected_value: f64, true_count: f64) {
+        self.bankroll.push(round, net_winnings);
+        self.expected_value.push(round, expected_value);
+        if let Some(buffer) = &mut self.true_count {
+            buffer.push(round, true_count);
+        }
+    }
+
+    pub fn finish(self) -> SimulationSeries {
+        let points = self.points;
+        SimulationSeries {
+            bankroll: self.bankroll.finish(points),
+            expected_value: self.expected_value.finish(points),
+            true_count: self.true_count.map(|buffer| buffer.finish(points)),
+        }
+    }
+}
+
+fn stride(rounds: &[u32], values: &[f64], points: usize) -> SeriesData {
+    let n = rounds.len();
+    if n <= points {
+        return SeriesData {
+            rounds: rounds.to_vec(),
+            values: values.to_vec(),
+        };
+    }
+    let step = (n - 1) as f64 / (points - 1) as f64;
+    let picks = (0..points).map(|i| ((i as f64 * step).round() as usize).min(n - 1));
+    let (rounds, values) = picks.map(|i| (rounds[i], values[i])).unzip();
+    SeriesData { rounds, values }
+}
+
+/// Largest-triangle-three-buckets downsampling (Steinarsson, 2013).
+fn lttb(rounds: &[u32], values: &[f64], points: usize) -> SeriesData {
+    let n = rounds.len();
+    if n <= points || points < 3 {
+        return stride(rounds, values, points.max(2));
+    }
+    let x = |i: usize| rounds[i] as f64;
+    let bucket_size = (n - 2) as f64 / (points - 2) as f64;
+    let mut picked = Vec::with_capacity(points);
+    picked.push(0);
+    let mut a = 0;
+    for bucket in 0..points - 2 {
+        let start = (bucket as f64 * bucket_size) as usize + 1;
+        let end = (((bucket + 1) as f64 * bucket_size) as usize + 1).min(n - 1);
+        let next_start = end;
+        let next_end = (((bucket + 2) as f64 * bucket_size) as usize + 1).min(n);
+        let span = (next_end - next_start).max(1) as f64;
+        let avg_x = (next_start..next_end).map(x).sum::<f64>() / span;
+        let avg_y = values[next_start..next_end].iter().sum::<f64>() / span;
+
+        let mut best = start;
+        let mut best_area = -1.0;
+        for i in start..end.max(start + 1) {
+            let area = ((x(a) - avg_x) * (values[i] - values[a]) - (x(a) - x(i)) * (avg_y - values[a])).abs();
+            if area > best_area {
+                best_area = area;
+                best = i;
+            }
+        }
+        picked.push(best);
+        a = best;
+    }
+    picked.push(n - 1);
+    let (rounds, values) = picked.into_iter().map(|i| (rounds[i], values[i])).unzip();
+    SeriesData { rounds, values }
+}
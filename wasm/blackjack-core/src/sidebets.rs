@@ -0,0 +1,484 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    betting::{self, RampStep},
+    deck::{Card, RANKS},
+    game::GameResult,
+};
+
+fn default_side_bet() -> f64 {
+    1.0
+}
+
+/// A side bet described as a payout table, e.g. `{"name": "suited",
+/// "payouts": [{"when": ["player_suited"], "pays": 3}]}`. Each round the rows
+/// are checked in order and the first one whose conditions all hold pays
+/// `pays` to one; when none matches the stake is lost.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SideBetInput {
+    pub name: String,
+    /// Stake per spot per round; placed only on rounds with a main bet.
+    #[serde(default = "default_side_bet")]
+    pub bet: f64,
+    /// Count-based stake in units of `bet`, for countable bets such as
+    /// Over 13; a zero-unit rung skips the bet at that count.
+    #[serde(default)]
+    pub bet_ramp: Option<Vec<RampStep>>,
+    /// Standard bet whose paytable is used when `payouts` is empty.
+    #[serde(default)]
+    pub preset: Option<SideBetPreset>,
+    #[serde(default)]
+    pub payouts: Vec<SideBetPayout>,
+    /// Shoe sizes to work out the exact EV off the top of, e.g. `[1, 2, 6, 8]`,
+    /// to see how the number of decks moves the bet.
+    #[serde(default)]
+    pub deck_counts: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SideBetPreset {
+    /// The player's two cards and the dealer's up card as a three-card poker
+    /// hand, on the common 100-40-30-10-5 paytable.
+    #[serde(rename = "21+3")]
+    TwentyOnePlusThree,
+    /// Each player card matching the dealer's up card in rank pays 4:1, or
+    /// 11:1 when the suit matches too; both cards can pay.
+    #[serde(rename = "match_the_dealer")]
+    MatchTheDealer,
+    /// Three-card totals of 19 to 21 with the dealer's up card, topped by
+    /// 6-7-8 and 7-7-7, paying 200 for suited 7-7-7 down to 2 for 19 or 20.
+    #[serde(rename = "lucky_lucky")]
+    LuckyLucky,
+    /// The live-dealer variant of the same idea: 100 for 7-7-7, 20 for a
+    /// suited 21, 4 for any other 21, 2 for 20 and 1 for 19.
+    #[serde(rename = "hot_3")]
+    Hot3,
+    /// Even money that the player's first two cards, aces counting one,
+    /// total more than 13 or less than 13; exactly 13 loses both.
+    #[serde(rename = "over_13")]
+    Over13,
+    #[serde(rename = "under_13")]
+    Under13,
+    /// The player's first two cards pair: 15:1 suited, 10:1 otherwise.
+    #[serde(rename = "pair_square")]
+    PairSquare,
+    /// Sevens from the player's first card on: 3 for one, 50 for two (100
+    /// suited), 500 for three (5000 suited).
+    #[serde(rename = "super_sevens")]
+    SuperSevens,
+}
+
+impl SideBetPreset {
+    pub fn payouts(self) -> Vec<SideBetPayout> {
+        use SideBetCondition::*;
+        let row = |when: Vec<SideBetCondition>, pays: f64| SideBetPayout { when, pays };
+        match self {
+            SideBetPreset::TwentyOnePlusThree => vec![
+                row(vec![ThreeOfAKind, Flush], 100.0),
+                row(vec![Straight, Flush], 40.0),
+                row(vec![ThreeOfAKind], 30.0),
+                row(vec![Straight], 10.0),
+                row(vec![Flush], 5.0),
+            ],
+            SideBetPreset::MatchTheDealer => vec![
+                row(vec![SuitedMatches(2)], 22.0),
+                row(vec![SuitedMatches(1), UnsuitedMatches(1)], 15.0),
+                row(vec![SuitedMatches(1)], 11.0),
+                row(vec![UnsuitedMatches(2)], 8.0),
+                row(vec![UnsuitedMatches(1)], 4.0),
+            ],
+            SideBetPreset::LuckyLucky => {
+                let ranks = |ranks: [&str; 3]| ThreeCardRanks(ranks.map(String::from).to_vec());
+                vec![
+                    row(vec![ranks(["7", "7", "7"]), Flush], 200.0),
+                    row(vec![ranks(["6", "7", "8"]), Flush], 100.0),
+                    row(vec![ranks(["7", "7", "7"])], 50.0),
+                    row(vec![ranks(["6", "7", "8"])], 30.0),
+                    row(vec![ThreeCardTotal(21), Flush], 15.0),
+                    row(vec![ThreeCardTotal(21)], 3.0),
+                    row(vec![ThreeCardTotal(20)], 2.0),
+                    row(vec![ThreeCardTotal(19)], 2.0),
+                ]
+            }
+            SideBetPreset::Hot3 => vec![
+                row(vec![ThreeCardRanks(vec!["7".to_string(); 3])], 100.0),
+                row(vec![ThreeCardTotal(21), Flush], 20.0),
+                row(vec![ThreeCardTotal(21)], 4.0),
+                row(vec![ThreeCardTotal(20)], 2.0),
+                row(vec![ThreeCardTotal(19)], 1.0),
+            ],
+            SideBetPreset::Over13 => vec![row(vec![PlayerOver(13)], 1.0)],
+            SideBetPreset::Under13 => vec![row(vec![PlayerUnder(13)], 1.0)],
+            SideBetPreset::PairSquare => vec![row(vec![PlayerPair, PlayerSuited], 15.0), row(vec![PlayerPair], 10.0)],
+            SideBetPreset::SuperSevens => vec![
+                row(vec![LeadingSevens(3), SevensSuited], 5000.0),
+                row(vec![LeadingSevens(3)], 500.0),
+                row(vec![LeadingSevens(2), SevensSuited], 100.0),
+                row(vec![LeadingSevens(2)], 50.0),
+                row(vec![LeadingSevens(1)], 3.0),
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SideBetPayout {
+    pub when: Vec<SideBetCondition>,
+    /// Won per unit staked, e.g. 3 for 3:1.
+    pub pays: f64,
+}
+
+/// Something true of the player's first two cards and the dealer's cards.
+/// Suits 0 and 1 count as red, 2 and 3 as black. The player's third card is
+/// the first one drawn after the deal, when there is one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SideBetCondition {
+    /// Both player cards share a rank (K-K, not K-Q).
+    PlayerPair,
+    PlayerSuited,
+    PlayerSameColor,
+    PlayerBlackjack,
+    /// Two-card total; soft hands count the ace as 11.
+    PlayerTotal(u8),
+    /// Two-card total above or below this, aces counting one.
+    PlayerOver(u8),
+    PlayerUnder(u8),
+    /// The dealer's up card has the rank of either player card.
+    DealerUpMatchesRank,
+    /// The dealer's up card has the suit of either player card.
+    DealerUpMatchesSuit,
+    /// Exactly this many player cards match the up card's rank in another suit.
+    UnsuitedMatches(u8),
+    /// Exactly this many player cards match the up card in rank and suit.
+    SuitedMatches(u8),
+    /// The dealer's up card rank, with "10" standing for every ten-value card.
+    DealerUp(String),
+    DealerBlackjack,
+    /// Poker hands made by the player's two cards and the dealer's up card.
+    Flush,
+    Straight,
+    ThreeOfAKind,
+    /// Blackjack total of the player's two cards and the dealer's up card.
+    ThreeCardTotal(u8),
+    /// The player's two cards and the dealer's up card are these ranks, in
+    /// any order, e.g. `["6", "7", "8"]`.
+    ThreeCardRanks(Vec<String>),
+    /// The player's cards open with exactly this many sevens, third card
+    /// included.
+    LeadingSevens(u8),
+    /// Those opening sevens all share a suit.
+    SevensSuited,
+}
+
+impl SideBetInput {
+    /// The payout rows, taken from the preset when none are given.
+    fn paytable(&self) -> Vec<SideBetPayout> {
+        match self.preset.filter(|_| self.payouts.is_empty()) {
+            Some(preset) => preset.payouts(),
+            None => self.payouts.clone(),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.bet.is_finite() || self.bet <= 0.0 {
+            return Err(format!("side bet '{}' needs a positive bet", self.name));
+        }
+        if self.payouts.is_empty() && self.preset.is_none() {
+            return Err(format!("side bet '{}' has no payouts or preset", self.name));
+        }
+        if let Some(row) = self.payouts.iter().find(|row| !row.pays.is_finite() || row.pays < 0.0) {
+            return Err(format!("side bet '{}' pays {}, expected zero or more", self.name, row.pays));
+        }
+        let ramp = self.bet_ramp.iter().flatten();
+        if let Some(step) = ramp.clone().find(|step| !step.units.is_finite() || step.units < 0.0) {
+            return Err(format!("side bet '{}' ramps to {} units, expected zero or more", self.name, step.units));
+        }
+        if self.deck_counts.contains(&0) {
+            return Err(format!("side bet '{}' needs at least one deck per deck count", self.name));
+        }
+        let later_cards = self.paytable().iter().flat_map(|row| &row.when).any(SideBetCondition::needs_later_cards);
+        if !self.deck_counts.is_empty() && later_cards {
+            let name = &self.name;
+            return Err(format!("side bet '{name}' depends on cards after the deal, so has no exact EV by deck count"));
+        }
+        Ok(())
+    }
+}
+
+/// Rank order for straights; aces also play high.
+fn rank_order(card: &Card) -> u8 {
+    match card.rank.as_str() {
+        "A" => 1,
+        "J" => 11,
+        "Q" => 12,
+        "K" => 13,
+        rank => rank.parse().unwrap_or(0),
+    }
+}
+
+fn is_red(card: &Card) -> bool {
+    card.suit < 2
+}
+
+fn hard_value(card: &Card) -> u8 {
+    if card.rank == "A" {
+        1
+    } else {
+        card.value
+    }
+}
+
+/// Blackjack total, an ace counting 11 where that does not bust.
+fn soft_total<'a>(cards: impl Iterator<Item = &'a Card> + Clone) -> u8 {
+    let aces = cards.clone().filter(|card| card.rank == "A").count();
+    let hard: u8 = cards.map(hard_value).sum();
+    if aces > 0 && hard + 10 <= 21 {
+        hard + 10
+    } else {
+        hard
+    }
+}
+
+fn is_natural(cards: &[Card]) -> bool {
+    cards.len() >= 2 && cards[..2].iter().any(|c| c.rank == "A") && cards[..2].iter().any(|c| c.value == 10)
+}
+
+fn is_straight(cards: &[&Card]) -> bool {
+    let mut ranks: Vec<u8> = cards.iter().map(|card| rank_order(card)).collect();
+    ranks.sort_unstable();
+    let consecutive = |ranks: &[u8]| ranks.windows(2).all(|pair| pair[1] == pair[0] + 1);
+    consecutive(&ranks) || ranks == [1, 12, 13]
+}
+
+impl SideBetCondition {
+    /// Depends on more than the player's two cards and the dealer's up card.
+    fn needs_later_cards(&self) -> bool {
+        match self {
+            SideBetCondition::DealerBlackjack => true,
+            SideBetCondition::LeadingSevens(count) => *count > 2,
+            _ => false,
+        }
+    }
+
+    fn holds(&self, player: &[Card], dealer: &[Card], up: &Card) -> bool {
+        let (first, second) = (&player[0], &player[1]);
+        let three = [first, second, up];
+        match self {
+            SideBetCondition::PlayerPair => first.rank == second.rank,
+            SideBetCondition::PlayerSuited => first.suit == second.suit,
+            SideBetCondition::PlayerSameColor => is_red(first) == is_red(second),
+            SideBetCondition::PlayerBlackjack => is_natural(player),
+            SideBetCondition::PlayerTotal(total) => soft_total([first, second].into_iter()) == *total,
+            SideBetCondition::PlayerOver(total) => hard_value(first) + hard_value(second) > *total,
+            SideBetCondition::PlayerUnder(total) => hard_value(first) + hard_value(second) < *total,
+            SideBetCondition::DealerUpMatchesRank => up.rank == first.rank || up.rank == second.rank,
+            SideBetCondition::DealerUpMatchesSuit => up.suit == first.suit || up.suit == second.suit,
+            SideBetCondition::UnsuitedMatches(count) => {
+                [first, second].iter().filter(|card| card.rank == up.rank && card.suit != up.suit).count()
+                    == *count as usize
+            }
+            SideBetCondition::SuitedMatches(count) => {
+                [first, second].iter().filter(|card| card.rank == up.rank && card.suit == up.suit).count()
+                    == *count as usize
+            }
+            SideBetCondition::DealerUp(rank) => {
+                up.rank == *rank || (rank == "10" && up.value == 10 && up.rank != "A")
+            }
+            SideBetCondition::DealerBlackjack => is_natural(dealer),
+            SideBetCondition::Flush => three.iter().all(|card| card.suit == first.suit),
+            SideBetCondition::Straight => is_straight(&three),
+            SideBetCondition::ThreeOfAKind => three.iter().all(|card| card.rank == first.rank),
+            SideBetCondition::ThreeCardTotal(total) => soft_total(three.into_iter()) == *total,
+            SideBetCondition::ThreeCardRanks(ranks) => {
+                let mut wanted: Vec<&str> = ranks.iter().map(String::as_str).collect();
+                let mut dealt: Vec<&str> = three.iter().map(|card| card.rank.as_str()).collect();
+                wanted.sort_unstable();
+                dealt.sort_unstable();
+                wanted == dealt
+            }
+            SideBetCondition::LeadingSevens(count) => {
+                player.iter().take(3).take_while(|card| card.rank == "7").count() == *count as usize
+            }
+            SideBetCondition::SevensSuited => {
+                player.iter().take(3).take_while(|card| card.rank == "7").all(|card| card.suit == first.suit)
+            }
+        }
+    }
+}
+
+/// Index of the first payout row whose conditions all hold.
+fn paying_row(payouts: &[SideBetPayout], player: &[Card], dealer: &[Card], up: &Card) -> Option<usize> {
+    payouts.iter().position(|row| row.when.iter().all(|condition| condition.holds(player, dealer, up)))
+}
+
+/// Exact return per unit staked off the top of a full shoe of `decks` decks,
+/// over every deal of the player's two cards and the dealer's up card.
+fn exact_ev(payouts: &[SideBetPayout], decks: u8) -> f64 {
+    let cards: Vec<Card> = RANKS.iter().flat_map(|rank| (0..4).map(|suit| Card::with_suit(rank, suit))).collect();
+    let copies = decks as f64;
+    let total = copies * cards.len() as f64;
+    let deals = total * (total - 1.0) * (total - 2.0);
+    let mut ev = 0.0;
+    for (i, first) in cards.iter().enumerate() {
+        for (j, second) in cards.iter().enumerate() {
+            let player = [first.clone(), second.clone()];
+            for (k, up) in cards.iter().enumerate() {
+                let ways = copies
+                    * (copies - (j == i) as u8 as f64)
+                    * (copies - (k == i) as u8 as f64 - (k == j) as u8 as f64);
+                if ways <= 0.0 {
+                    continue;
+                }
+                let row = paying_row(payouts, &player, std::slice::from_ref(up), up);
+                let units = row.map_or(-1.0, |row| payouts[row].pays);
+                ev += units * ways / deals;
+            }
+        }
+    }
+    ev
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeckCountEv {
+    pub decks: u8,
+    pub expected_value: f64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SideBetRowStats {
+    pub pays: f64,
+    pub hits: u32,
+    pub frequency: f64,
+}
+
+/// Results of one side bet, kept apart from the main game's figures.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SideBetStats {
+    pub name: String,
+    pub bets: u32,
+    pub total_bet: f64,
+    pub total_winnings: f64,
+    /// Mean result per unit staked; the house edge is its negative.
+    pub expected_value: f64,
+    /// Standard deviation of one bet in units of the stake, weighted by
+    /// stake like the EV.
+    pub std_dev: f64,
+    pub payouts: Vec<SideBetRowStats>,
+    /// Exact EV off the top of each requested shoe size.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub exact_ev_by_decks: Vec<DeckCountEv>,
+    /// Sum of squared results in units, each weighted by its stake.
+    #[serde(skip)]
+    weighted_squared_units: f64,
+    #[serde(skip)]
+    input: SideBetInput,
+}
+
+impl SideBetStats {
+    pub fn new(mut input: SideBetInput) -> Self {
+        input.payouts = input.paytable();
+        SideBetStats {
+            name: input.name.clone(),
+            bets: 0,
+            total_bet: 0.0,
+            total_winnings: 0.0,
+            expected_value: 0.0,
+            std_dev: 0.0,
+            payouts: input
+                .payouts
+                .iter()
+                .map(|row| SideBetRowStats {
+                    pays: row.pays,
+                    hits: 0,
+                    frequency: 0.0,
+                })
+                .collect(),
+            exact_ev_by_decks: input
+                .deck_counts
+                .iter()
+                .map(|&decks| DeckCountEv {
+                    decks,
+                    expected_value: exact_ev(&input.payouts, decks),
+                })
+                .collect(),
+            weighted_squared_units: 0.0,
+            input,
+        }
+    }
+
+    /// Settles the side bet for one spot's deal at `true_count`.
+    pub fn record(&mut self, result: &GameResult, true_count: i32) {
+        let stake = match &self.input.bet_ramp {
+            Some(ramp) => self.input.bet * betting::ramp_units(ramp, true_count),
+            None => self.input.bet,
+        };
+        if result.player_cards.len() < 2 || stake <= 0.0 {
+            return;
+        }
+        let mut player = result.player_cards.clone();
+        player.extend(result.third_card.clone());
+        let row = paying_row(&self.input.payouts, &player, &result.dealer_cards, &result.dealer_up_card);
+        let units = match row {
+            Some(row) => {
+                self.payouts[row].hits += 1;
+                self.input.payouts[row].pays
+            }
+            None => -1.0,
+        };
+        self.bets += 1;
+        self.total_bet += stake;
+        self.total_winnings += units * stake;
+        self.weighted_squared_units += units * units * stake;
+    }
+
+    pub fn finalize(&mut self) {
+        if self.bets == 0 {
+            return;
+        }
+        let bets = self.bets as f64;
+        self.expected_value = self.total_winnings / self.total_bet;
+        let second_moment = self.weighted_squared_units / self.total_bet;
+        self.std_dev = (second_moment - self.expected_value * self.expected_value).max(0.0).sqrt();
+        for row in &mut self.payouts {
+            row.frequency = row.hits as f64 / bets;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{deck::Deck, game::BlackjackGame, sim::to_game_rules, strategy::Strategy};
+
+    fn deal(ranks: &[&str]) -> GameResult {
+        let strategy = serde_json::from_value(serde_json::json!({"hard": {}, "soft": {}, "pairs": {}})).unwrap();
+        let strategy = Strategy::from_input(strategy).unwrap();
+        let rules = to_game_rules(&serde_json::from_value(serde_json::json!({})).unwrap());
+        let mut game = BlackjackGame::new(Deck::stacked(ranks), rules, None);
+        game.play_round(&strategy, &[10.0]).remove(0)
+    }
+
+    #[test]
+    fn std_dev_weights_results_by_stake() {
+        let input: SideBetInput = serde_json::from_value(serde_json::json!({
+            "name": "pairs",
+            "payouts": [{"when": ["player_pair"], "pays": 3}],
+            "bet_ramp": [{"true_count": 0, "units": 1}, {"true_count": 2, "units": 4}],
+        }))
+        .unwrap();
+        let mut stats = SideBetStats::new(input);
+        // A pair pays 3 on one unit, then a miss loses four units.
+        stats.record(&deal(&["8", "8", "10", "7"]), 0);
+        stats.record(&deal(&["10", "9", "10", "7"]), 2);
+        stats.finalize();
+
+        assert_eq!(stats.total_bet, 5.0);
+        assert!((stats.expected_value + 0.2).abs() < 1e-12);
+        // Second moment (9 * 1 + 1 * 4) / 5 = 2.6, less 0.2 squared.
+        assert!((stats.std_dev - 1.6).abs() < 1e-12);
+    }
+}
@@ -1,40 +1,243 @@
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::rc::Rc;
 
-use serde::{Deserialize, Serialize};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::{
+    bankroll::{BankrollEvaluationInput, BankrollEvaluator, BankrollOutcome, BankrollTracker, BankrollTracking},
+    betting::{
+        self, BetRecommendation, BetRecommendationInput, BetSize, ChipRounding, ChipRoundingStats, KellyBettingInput,
+        KellyBettor, KellyOutcome, Progression, ProgressionInput, ProgressionStats, RampStep, TableLimitStats,
+        TableLimits, Wonging,
+    },
+    chart::{self, ChartOverlay},
+    clock::ProgressThrottle,
     counter::CardCounter,
-    deck::{Card, Deck},
-    game::{BlackjackGame, GameResult, GameRules},
-    strategy::{Strategy, StrategyInput},
+    deck::{Card, Deck, ReshufflePolicy, ShoeBias, RANKS},
+    game::{
+        BlackjackGame, BlackjackPayout, DealerRule, DealerWinsTies, DoubleOn, GameResult, GameRules, HandBonuses,
+        ShoeBoundary, Variant,
+    },
+    policy::{BetPolicy, BetPolicyStats, PolicyBettor},
+    selftest::OutcomeHash,
+    series::{SeriesInput, SeriesRecorder, SimulationSeries},
+    sidebets::{SideBetInput, SideBetStats},
+    stats::{
+        fallback_message, fallback_warnings, BonusStats, DepthStats, DeviationStats, DoubleStats, EvenMoneyStats,
+        FallbackWarning, InsuranceStats, MultiSpotStats, SessionModel, SessionRecorder, SessionStats, ShoeStats,
+        SplitStats, StartingHandStats, StrategyCoverage,
+    },
+    strategy::{ChartCell, ChartSource, Strategy, StrategyInput},
 };
 
 fn default_bet_size() -> f64 {
     100.0
 }
 
+/// Fresh seed for inputs that leave it out, kept below 2^53 so it survives a
+/// round trip through a JavaScript number.
+fn entropy_seed() -> u64 {
+    rand::random::<u64>() >> 11
+}
+
+fn default_flat_bet() -> BetSize {
+    BetSize::Flat(default_bet_size())
+}
+
 fn default_progress_interval() -> u32 {
     10_000
 }
 
-#[derive(Debug, Deserialize)]
+/// Rounds dealt per hand asked for before a run counted in hands gives up,
+/// e.g. when the bet sizing never puts money out.
+const MAX_ROUNDS_PER_HAND: u32 = 1_000;
+
+/// Rounds dealt without a single hand played before such a run gives up.
+const MAX_ROUNDS_WITHOUT_PLAY: u32 = 100_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RulesInput {
-    pub dealer_hits_soft_17: bool,
-    #[serde(default)]
-    pub dealer_stands_on: Option<String>,
+    /// S17 (the default), H17 or a custom standing total, read from
+    /// `dealer_rule` or the older `dealer_stands_on`/`dealer_hits_soft_17`
+    /// pair.
+    #[serde(flatten, deserialize_with = "deserialize_dealer_rule", serialize_with = "serialize_dealer_rule")]
+    pub dealer_rule: DealerRule,
     #[serde(default)]
     pub double_after_split: Option<bool>,
+    /// Doubling on split aces; follows `double_after_split` when omitted.
+    #[serde(default)]
+    pub double_split_aces: Option<bool>,
+    /// Play split aces on like other hands (the default, matching older
+    /// results); `false` deals each split ace one card only.
+    #[serde(default)]
+    pub hit_split_aces: Option<bool>,
+    /// Split only cards of the same rank, playing K-10 as a hard 20; any two
+    /// ten-value cards split by default.
+    #[serde(default)]
+    pub split_identical_ranks: Option<bool>,
+    /// Restrict doubling to "9-11" or "10-11"; "any" two cards by default.
+    #[serde(default)]
+    pub double_on: Option<DoubleOn>,
+    /// Allow doubling after hitting, on three or more cards.
+    #[serde(default)]
+    pub double_after_hit: Option<bool>,
+    /// Double down rescue: give up a doubled hand, losing only the bet before
+    /// the double, wherever the strategy's `rescue` chart says so.
+    #[serde(default)]
+    pub double_rescue: Option<bool>,
+    /// Power Blackjack: 3 or 4 allows tripling ("D3") or quadrupling ("D4")
+    /// the bet on the first two cards; 2 by default.
+    #[serde(default)]
+    pub max_double_multiplier: Option<u8>,
+    /// N-card Charlie: a hand of 5, 6 or 7 cards that has not busted wins.
+    #[serde(default)]
+    pub charlie: Option<u8>,
+    /// A dealer 22 pushes the hands still standing rather than busting.
+    #[serde(default)]
+    pub push_22: Option<bool>,
+    /// Double Exposure: both dealer cards face up and the dealer wins ties.
+    /// Blackjack then pays even money unless `blackjack_pays` says otherwise.
+    /// Strategy columns named for dealer totals ("H12", "S17") are used when
+    /// present, the up card otherwise.
+    #[serde(default)]
+    pub double_exposure: Option<bool>,
+    /// Let the dealer win ties, all of them (`true`) or only on the listed
+    /// totals, e.g. `[17, 18, 19]`.
+    #[serde(default)]
+    pub dealer_wins_ties: Option<DealerWinsTies>,
+    /// "blackjack" (the default), "pontoon" or "zappit". Pontoon also changes
+    /// the defaults: pontoon pays 2:1 and a five-card trick wins. Its
+    /// decisions read the strategy's "hidden" dealer column.
+    /// Zappit reads "Z" chart entries as zaps, pays blackjack even money and
+    /// pushes on a dealer 22.
+    #[serde(default)]
+    pub variant: Option<Variant>,
     #[serde(default)]
     pub allow_resplit: Option<bool>,
     #[serde(default)]
     pub resplit_aces: Option<bool>,
+    /// Most hands a spot may split into, e.g. 4 for "resplit to four hands";
+    /// unlimited when omitted.
+    #[serde(default)]
+    pub max_split_hands: Option<u8>,
+    /// Most hands when splitting aces; follows `max_split_hands` when omitted.
+    #[serde(default)]
+    pub max_split_ace_hands: Option<u8>,
+    /// 3:2 when omitted; see `BlackjackPayout` for the accepted forms.
+    #[serde(default)]
+    pub blackjack_pays: Option<BlackjackPayout>,
     #[serde(default)]
-    pub blackjack_pays: Option<String>,
+    pub penetration_threshold: Option<f64>,
     #[serde(default)]
-    pub penetration_threshold: Option<u8>,
+    pub reshuffle_policy: Option<ReshufflePolicy>,
+    #[serde(default)]
+    pub late_surrender: Option<bool>,
+    /// Surrender before the dealer peeks, saving half the bet against a dealer
+    /// blackjack too. Uses the strategy's surrender entries.
+    #[serde(default)]
+    pub early_surrender: Option<bool>,
+    /// Lose only the original bet to a dealer blackjack (OBO) rather than
+    /// doubles and splits as well.
+    #[serde(default)]
+    pub original_bets_only: Option<bool>,
+    /// Whether the dealer peeks for blackjack under an ace and under a ten.
+    /// Neither is peeked by default, so a dealer blackjack is found after the
+    /// players have acted.
+    #[serde(default)]
+    pub peek_aces: Option<bool>,
+    #[serde(default)]
+    pub peek_tens: Option<bool>,
+    /// Pay an ace and a ten after a split as blackjack instead of as 21.
+    #[serde(default)]
+    pub split_blackjack_pays: Option<bool>,
+    /// Bonus payouts for 6-7-8 and 7-7-7; none by default.
+    #[serde(default)]
+    pub hand_bonuses: Option<HandBonuses>,
+    /// Offer insurance against a dealer ace, taken only as the strategy's
+    /// `insurance` policy says; off by default.
+    #[serde(default)]
+    pub insurance: Option<bool>,
+}
+
+/// The dealer keys of a `RulesInput`. `dealer_stands_on` is the older name
+/// of `dealer_rule`; older payloads sent it as "17" next to
+/// `dealer_hits_soft_17`, which then decided between S17 and H17.
+#[derive(Deserialize)]
+struct DealerRuleKeys {
+    #[serde(default)]
+    dealer_rule: Option<DealerRule>,
+    #[serde(default)]
+    dealer_stands_on: Option<DealerRule>,
+    #[serde(default)]
+    dealer_hits_soft_17: Option<bool>,
+}
+
+impl TryFrom<DealerRuleKeys> for DealerRule {
+    type Error = String;
+
+    fn try_from(keys: DealerRuleKeys) -> Result<Self, String> {
+        let rule = match (keys.dealer_rule, keys.dealer_stands_on) {
+            (Some(_), Some(_)) => return Err("give dealer_rule or dealer_stands_on, not both".to_string()),
+            (rule, stands_on) => rule.or(stands_on),
+        };
+        match (rule, keys.dealer_hits_soft_17) {
+            (None | Some(DealerRule::Total(17)), Some(true)) => Ok(DealerRule::H17),
+            (None | Some(DealerRule::Total(17)), _) => Ok(DealerRule::S17),
+            (Some(rule), Some(hits)) if rule.hits_soft_17() != hits => Err(format!(
+                "dealer_hits_soft_17 is {hits} but the dealer rule {rule:?} {} soft 17",
+                if rule.hits_soft_17() { "hits" } else { "stands on" }
+            )),
+            (Some(rule), _) => Ok(rule),
+        }
+    }
+}
+
+fn deserialize_dealer_rule<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DealerRule, D::Error> {
+    DealerRuleKeys::deserialize(deserializer)?
+        .try_into()
+        .map_err(D::Error::custom)
+}
+
+fn serialize_dealer_rule<S: Serializer>(rule: &DealerRule, serializer: S) -> Result<S::Ok, S::Error> {
+    #[derive(Serialize)]
+    struct DealerRuleKey<'a> {
+        dealer_rule: &'a DealerRule,
+    }
+    DealerRuleKey { dealer_rule: rule }.serialize(serializer)
+}
+
+impl RulesInput {
+    /// Split limits count hands including the first, so a limit of zero
+    /// would describe nothing a table could deal.
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, limit) in [
+            ("max_split_hands", self.max_split_hands),
+            ("max_split_ace_hands", self.max_split_ace_hands),
+        ] {
+            if limit == Some(0) {
+                return Err(format!("{name} must be at least 1"));
+            }
+        }
+        if let Some(multiplier) = self.max_double_multiplier.filter(|multiplier| !(2..=4).contains(multiplier)) {
+            return Err(format!("max_double_multiplier must be 2, 3 or 4, got {multiplier}"));
+        }
+        if let Some(charlie) = self.charlie.filter(|charlie| !(5..=7).contains(charlie)) {
+            return Err(format!("charlie must be 5, 6 or 7 cards, got {charlie}"));
+        }
+        for bonus in self.hand_bonuses.iter().flat_map(|bonuses| &bonuses.hands) {
+            if !bonus.has_condition() {
+                return Err("bonus hand needs ranks, suited, total or min_cards".to_string());
+            }
+            if !bonus.pays.is_finite() || bonus.pays < 0.0 {
+                return Err(format!("bonus hand pays {}, expected zero or more", bonus.pays));
+            }
+        }
+        Ok(())
+    }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CountingInput {
     #[serde(default)]
     pub enabled: bool,
@@ -44,36 +247,267 @@ pub struct CountingInput {
     pub custom_values: Option<HashMap<String, i32>>,
 }
 
-#[derive(Debug, Deserialize)]
+/// How much detail `SimulationResult` carries back to the caller.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputDetail {
+    /// Headline totals only; no count or cell breakdowns.
+    Summary,
+    /// Headline totals plus count and cell breakdowns.
+    #[default]
+    Standard,
+    /// Everything, including per-shoe summaries.
+    Full,
+}
+
+/// What `iterations` counts.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum IterationUnit {
+    /// Dealt rounds, whatever happens in them.
+    #[default]
+    Rounds,
+    /// Hands played, counting every hand a split creates; the run stops at the
+    /// end of the round that reaches the target.
+    Hands,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimulationInput {
     pub num_decks: u8,
     pub iterations: u32,
+    #[serde(default)]
+    pub iteration_unit: IterationUnit,
+    /// Drawn from OS or browser entropy when omitted; the result reports it.
+    #[serde(default = "entropy_seed")]
     pub seed: u64,
     pub strategy: StrategyInput,
     pub rules: RulesInput,
-    #[serde(default = "default_bet_size")]
-    pub bet_size: f64,
+    /// A flat bet or a per-round schedule; see `BetSize`.
+    #[serde(default = "default_flat_bet")]
+    pub bet_size: BetSize,
     #[serde(default = "default_progress_interval")]
     pub progress_interval: u32,
+    /// Report progress at most once per this many milliseconds instead of
+    /// every `progress_interval` rounds.
+    #[serde(default)]
+    pub progress_interval_ms: Option<f64>,
     #[serde(default)]
     pub counting: Option<CountingInput>,
+    #[serde(default)]
+    pub output_detail: OutputDetail,
+    #[serde(default)]
+    pub chart_overlay: bool,
+    #[serde(default)]
+    pub bet_recommendation: Option<BetRecommendationInput>,
+    #[serde(default)]
+    pub bankroll_evaluation: Option<BankrollEvaluationInput>,
+    /// Count-based bet spread in units of `bet_size`; flat betting when omitted.
+    #[serde(default)]
+    pub bet_ramp: Option<Vec<RampStep>>,
+    /// Only play while wonged in; rounds outside are watched and counted but
+    /// not bet. Needs counting.
+    #[serde(default)]
+    pub wonging: Option<Wonging>,
+    /// Sizes every bet from a bankroll carried through the run by the Kelly
+    /// criterion. Needs counting; replaces `bet_ramp`.
+    #[serde(default)]
+    pub kelly_betting: Option<KellyBettingInput>,
+    /// Sizes every bet from the results of the previous rounds, in units of
+    /// `bet_size`. Cannot be combined with `bet_ramp` or `kelly_betting`.
+    #[serde(default)]
+    pub progression: Option<ProgressionInput>,
+    /// User betting rules sizing every bet in units of `bet_size`. Cannot be
+    /// combined with the other bet sizing options.
+    #[serde(default)]
+    pub bet_policy: Option<BetPolicy>,
+    /// Bankroll carried through the run. When it cannot cover a round's bet
+    /// the run stops with `stop_on_ruin`, or rebuys it and plays on.
+    #[serde(default)]
+    pub starting_bankroll: Option<f64>,
+    #[serde(default)]
+    pub stop_on_ruin: bool,
+    /// Bankroll to reach before going broke, e.g. double the starting one;
+    /// each time it is reached the bankroll starts over.
+    #[serde(default)]
+    pub target_bankroll: Option<f64>,
+    /// Table limits every bet is held to, however it was sized.
+    #[serde(default)]
+    pub table_min: Option<f64>,
+    #[serde(default)]
+    pub table_max: Option<f64>,
+    /// Rounds every sized bet to whole chips of this value.
+    #[serde(default)]
+    pub chip_size: Option<f64>,
+    /// Expresses results per hour and per session of table time.
+    #[serde(default)]
+    pub session_model: Option<SessionModel>,
+    /// Replays rounds where an index play fired with the basic chart to
+    /// measure what each deviation is worth. Needs counting and count tables.
+    #[serde(default)]
+    pub deviation_report: bool,
+    /// Also plays every round flat-bet with the basic chart on the same cards,
+    /// when a bet ramp or counting is enabled.
+    #[serde(default)]
+    pub flat_baseline: bool,
+    /// Records bankroll, EV and true-count series decimated to a few hundred points.
+    #[serde(default)]
+    pub series: Option<SeriesInput>,
+    /// Spots played per round against the same dealer hand; one when omitted.
+    #[serde(default)]
+    pub spots: Option<u8>,
+    /// Skews every shoe's composition or order for stress tests.
+    #[serde(default)]
+    pub shoe_bias: Option<ShoeBias>,
+    /// Scenario mode: every round is dealt from a fresh shoe with cards taken
+    /// out to put it at this true count, so the results are conditional on
+    /// that count. Needs counting.
+    #[serde(default)]
+    pub target_true_count: Option<f64>,
+    /// Reports how often each strategy entry was used, which were never used
+    /// and which hands fell back to the default action.
+    #[serde(default)]
+    pub strategy_coverage: bool,
+    /// Fails the simulation on the first hand the strategy has no entry for,
+    /// instead of playing it with the default action and warning.
+    #[serde(default)]
+    pub strict_strategy: bool,
+    /// Side bets settled on every bet spot, reported separately from the
+    /// main game; see `SideBetInput`.
+    #[serde(default)]
+    pub side_bets: Vec<SideBetInput>,
 }
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SimulationResult {
+    /// Spots played, one per spot per bet round; `expected_value` and
+    /// `win_rate` are per spot.
     pub total_games: u32,
     pub wins: u32,
     pub losses: u32,
     pub pushes: u32,
     pub blackjacks: u32,
+    /// Hands pushed by a dealer 22, when the push-22 rule is on.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub push_22_hands: Option<u32>,
     pub total_winnings: f64,
     pub total_bet: f64,
     pub expected_value: f64,
     pub win_rate: f64,
     pub return_rate: f64,
+    /// Rounds watched but not bet because the bet ramp called for zero units
+    /// or the player had wonged out.
+    pub rounds_observed: u32,
+    /// Rounds actually bet, i.e. dealt rounds minus `rounds_observed`.
+    pub rounds_played: u32,
+    /// Hands played in those rounds, counting each hand created by a split.
+    pub hands_played: u32,
+    pub ev_per_round: f64,
+    pub ev_per_hand: f64,
+    /// Standard deviation of the net result of a played round.
+    pub std_dev_per_round: f64,
     pub count_stats: Option<CountStats>,
     pub cell_stats: HashMap<String, CellStats>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chart_overlay: Option<ChartOverlay>,
+    pub dealer_upcard_stats: Vec<DealerUpcardStats>,
+    pub double_stats: DoubleStats,
+    pub split_stats: SplitStats,
+    pub starting_hands: StartingHandStats,
+    pub depth_stats: DepthStats,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deviation_stats: Option<DeviationStats>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flat_baseline: Option<FlatBaseline>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub series: Option<SimulationSeries>,
+    /// Covariance between spots, when more than one is played per round.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multi_spot_stats: Option<MultiSpotStats>,
+    /// Present once a blackjack has faced a dealer ace.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub even_money_stats: Option<EvenMoneyStats>,
+    /// Present once insurance has been offered.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub insurance_stats: Option<InsuranceStats>,
+    /// Present once a hand bonus has been paid.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bonus_stats: Option<BonusStats>,
+    /// Net result per shoe, once at least one shoe has been finished.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shoe_stats: Option<ShoeStats>,
+    /// Seed the run was dealt from, so an entropy-seeded run can be repeated.
+    pub seed: u64,
+    /// The shoe bias the run was dealt from, echoed for reproducibility.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shoe_bias: Option<ShoeBias>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strategy_coverage: Option<StrategyCoverage>,
+    /// Strategy gaps that fell back to the default action; a non-empty list
+    /// means the EV reflects that fallback rather than the chart.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<FallbackWarning>,
+    /// Why a run counted in hands stopped short of `iterations`, when its
+    /// bet sizing sat out too many rounds to get there.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stopped_early: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub side_bets: Vec<SideBetStats>,
+    /// The scenario count every round was dealt at, when one was set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_true_count: Option<f64>,
+    /// Mean true count the scenario rounds were actually dealt at; taking out
+    /// whole cards gets as close to the target as it can but rarely exactly.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub achieved_true_count: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bet_recommendation: Option<BetRecommendation>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub bankroll_outcomes: Vec<BankrollOutcome>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kelly_betting: Option<KellyOutcome>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub progression: Option<ProgressionStats>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bet_policy: Option<BetPolicyStats>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bankroll: Option<BankrollTracking>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub table_limits: Option<TableLimitStats>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chip_rounding: Option<ChipRoundingStats>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_stats: Option<SessionStats>,
+}
+
+/// Flat-bet basic-strategy play of the same shoes, for comparison.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlatBaseline {
+    pub rounds: u32,
+    pub total_winnings: f64,
+    pub total_bet: f64,
+    /// Mean result per dealt round.
+    pub expected_value: f64,
+    pub return_rate: f64,
+    /// This run's winnings per dealt round minus the baseline's.
+    pub ev_gain: f64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DealerUpcardStats {
+    pub dealer_card: String,
+    pub hands: u32,
+    pub frequency: f64,
+    pub wins: u32,
+    pub losses: u32,
+    pub pushes: u32,
+    pub total_winnings: f64,
+    pub total_bet: f64,
+    pub expected_value: f64,
+    pub win_rate: f64,
 }
 
 #[derive(Debug, Serialize)]
@@ -83,6 +517,30 @@ pub struct CountStats {
     pub count_distribution: HashMap<String, u32>,
     pub ev_by_count: HashMap<String, f64>,
     pub hands_by_count: HashMap<String, u32>,
+    /// Mean result per round in units of the base bet.
+    pub advantage_by_count: HashMap<String, f64>,
+    /// Per-round variance in squared units of the base bet.
+    pub variance_by_count: HashMap<String, f64>,
+    #[serde(skip)]
+    squared_units_by_count: HashMap<String, f64>,
+    pub shoes_completed: u32,
+    pub avg_hands_per_shoe: f64,
+    pub final_running_count_distribution: HashMap<String, u32>,
+    /// Per-shoe summaries, collected only for `OutputDetail::Full`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub shoes: Vec<ShoeCountSummary>,
+    #[serde(skip)]
+    hands_in_closed_shoes: u32,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShoeCountSummary {
+    pub shoe_index: u32,
+    pub hands: u32,
+    pub cards_dealt: u32,
+    pub final_running_count: f64,
+    pub true_count_distribution: HashMap<String, u32>,
 }
 
 #[derive(Debug, Serialize)]
@@ -98,141 +556,889 @@ pub struct CellStats {
     pub pushes: u32,
     pub total_winnings: f64,
     pub total_bet: f64,
+    #[serde(skip)]
+    pub total_winnings_sq: f64,
+    /// Standard error of the mean winnings per hand (`total_winnings / hands`),
+    /// from the spread of per-hand outcomes; null below two hands.
+    pub standard_error: Option<f64>,
+}
+
+impl CellStats {
+    fn finalize(&mut self) {
+        if self.hands < 2 {
+            return;
+        }
+        let n = self.hands as f64;
+        let mean = self.total_winnings / n;
+        let variance = ((self.total_winnings_sq - n * mean * mean) / (n - 1.0)).max(0.0);
+        self.standard_error = Some((variance / n).sqrt());
+    }
 }
 
 pub fn run(input: SimulationInput) -> Result<SimulationResult, String> {
     run_with_progress(input, |_current, _total| {})
 }
 
-pub fn run_with_progress<F>(input: SimulationInput, mut progress_cb: F) -> Result<SimulationResult, String>
+pub fn run_with_progress<F>(input: SimulationInput, progress_cb: F) -> Result<SimulationResult, String>
 where
     F: FnMut(u32, u32),
 {
-    let strategy = Strategy::from_input(input.strategy)?;
-    let penetration = input.rules.penetration_threshold.unwrap_or(75);
-    let deck = Deck::new(input.num_decks, penetration, input.seed);
-    let game_rules = to_game_rules(&input.rules);
-    let counter = build_counter(input.counting.clone());
-    let counting_enabled = counter.is_some();
-    let mut game = BlackjackGame::new(deck, game_rules, counter);
+    let strategy = Strategy::from_input(input.strategy.clone())?;
+    simulate(&strategy, &input, progress_cb)
+}
 
-    let mut wins = 0;
-    let mut losses = 0;
-    let mut pushes = 0;
-    let mut blackjacks = 0;
-    let mut total_winnings = 0.0;
-    let mut total_bet = 0.0;
-    let mut cell_stats: HashMap<String, CellStats> = HashMap::new();
-    let mut count_stats = init_count_stats();
+/// Runs a simulation with an already-parsed strategy; `input.strategy` is ignored.
+pub fn simulate<F>(
+    strategy: &Strategy,
+    input: &SimulationInput,
+    mut progress_cb: F,
+) -> Result<SimulationResult, String>
+where
+    F: FnMut(u32, u32),
+{
+    let mut run = SimulationRun::new(strategy.clone(), input.clone())?;
+    let mut throttle = ProgressThrottle::new(input.progress_interval, input.progress_interval_ms);
+    while !run.is_done() {
+        run.play_round();
+        if throttle.due(run.progress(), run.total()) {
+            progress_cb(run.progress(), run.total());
+        }
+    }
+    run.finish()
+}
 
-    let bet_size = input.bet_size.max(1.0);
-    let progress_interval = input.progress_interval.max(1);
+/// Per-call settings for a reusable `Simulator`; anything omitted keeps the
+/// value it was created with.
+#[derive(Debug, Default, Deserialize)]
+pub struct RunOverrides {
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub iterations: Option<u32>,
+    #[serde(default)]
+    pub bet_size: Option<BetSize>,
+    #[serde(default)]
+    pub bet_ramp: Option<Vec<RampStep>>,
+    #[serde(default)]
+    pub output_detail: Option<OutputDetail>,
+}
+
+impl RunOverrides {
+    pub fn apply(&self, base: &SimulationInput) -> SimulationInput {
+        let mut input = base.clone();
+        if let Some(seed) = self.seed {
+            input.seed = seed;
+        }
+        if let Some(iterations) = self.iterations {
+            input.iterations = iterations;
+        }
+        if let Some(bet_size) = &self.bet_size {
+            input.bet_size = bet_size.clone();
+        }
+        if let Some(ramp) = &self.bet_ramp {
+            input.bet_ramp = Some(ramp.clone());
+        }
+        if let Some(detail) = self.output_detail {
+            input.output_detail = detail;
+        }
+        input
+    }
+}
+
+/// Hand to spot-check with a reusable `Simulator`'s rules and strategy.
+#[derive(Debug, Deserialize)]
+pub struct SpotCheckHand {
+    pub player_cards: Vec<String>,
+    pub dealer_card: String,
+    pub forced_action: String,
+    #[serde(default)]
+    pub iterations: Option<u32>,
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+impl SpotCheckHand {
+    pub fn into_input(self, base: &SimulationInput) -> SpotCheckInput {
+        SpotCheckInput {
+            num_decks: base.num_decks,
+            iterations: self.iterations.unwrap_or(base.iterations),
+            seed: self.seed.unwrap_or(base.seed),
+            strategy: StrategyInput::default(),
+            rules: base.rules.clone(),
+            bet_size: base.bet_size.unit(),
+            player_cards: self.player_cards,
+            dealer_card: self.dealer_card,
+            forced_action: self.forced_action,
+            counting: base.counting.clone(),
+            true_count: None,
+        }
+    }
+}
 
-    for game_index in 0..input.iterations {
+/// A simulation that can be advanced a few rounds at a time, so callers can
+/// yield between chunks. `simulate` drives one to completion in a single call.
+pub struct SimulationRun {
+    strategy: Strategy,
+    /// Count-free copy of the strategy, kept when a report needs a replay.
+    basic_strategy: Option<Strategy>,
+    input: SimulationInput,
+    game: BlackjackGame,
+    counting_enabled: bool,
+    shoe_boundaries: Rc<RefCell<Vec<ShoeBoundary>>>,
+    bet_size: f64,
+    spots: usize,
+    completed: u32,
+    blackjacks: u32,
+    push_22_hands: u32,
+    rounds_observed: u32,
+    hands_played: u32,
+    scenario_true_count_sum: f64,
+    round_sum: f64,
+    round_sum_sq: f64,
+    cell_stats: HashMap<String, CellStats>,
+    count_stats: CountStats,
+    current_shoe_tc: HashMap<String, u32>,
+    /// Shoe the player wonged into, while still playing it.
+    wonged_in: Option<u32>,
+    double_stats: DoubleStats,
+    split_stats: SplitStats,
+    starting_hands: StartingHandStats,
+    depth_stats: DepthStats,
+    even_money_stats: EvenMoneyStats,
+    insurance_stats: InsuranceStats,
+    bonus_stats: BonusStats,
+    shoe_stats: ShoeStats,
+    deviation_stats: Option<DeviationStats>,
+    flat_baseline: Option<FlatBaseline>,
+    bankroll_evaluator: Option<BankrollEvaluator>,
+    kelly_bettor: Option<KellyBettor>,
+    progression: Option<Progression>,
+    policy_bettor: Option<PolicyBettor>,
+    bankroll: Option<BankrollTracker>,
+    table_limits: Option<TableLimits>,
+    chip_rounding: Option<ChipRounding>,
+    sessions: Option<SessionRecorder>,
+    series: Option<SeriesRecorder>,
+    multi_spot_stats: Option<MultiSpotStats>,
+    strategy_coverage: Option<StrategyCoverage>,
+    fallbacks: HashMap<ChartCell, u32>,
+    strategy_error: Option<String>,
+    side_bets: Vec<SideBetStats>,
+    outcome_hash: Option<OutcomeHash>,
+}
+
+impl SimulationRun {
+    pub fn new(strategy: Strategy, mut input: SimulationInput) -> Result<Self, String> {
+        if input.num_decks == 0 {
+            return Err("simulation needs at least one deck".to_string());
+        }
+        input.bet_size.validate()?;
+        input.rules.validate()?;
+        if let Some(ramp) = &input.bet_ramp {
+            betting::validate_ramp(ramp)?;
+        }
+        let ramp_never_bets = input
+            .bet_ramp
+            .as_ref()
+            .is_some_and(|ramp| !ramp.is_empty() && ramp.iter().all(|step| step.units <= 0.0));
+        if input.iteration_unit == IterationUnit::Hands && ramp_never_bets {
+            return Err("counting iterations in hands needs a bet ramp that bets at some count".to_string());
+        }
+        let mut deck = build_deck(input.num_decks, &input.rules, input.seed);
+        if let Some(bias) = &input.shoe_bias {
+            deck.set_bias(bias)?;
+        }
+        let game_rules = to_game_rules(&input.rules);
+        let counter = build_counter(input.counting.clone());
+        let counting_enabled = counter.is_some();
+        if input.target_true_count.is_some() && !counting_enabled {
+            return Err("target_true_count needs counting enabled".to_string());
+        }
+        if input.bet_ramp.is_some() && !counting_enabled {
+            return Err("bet_ramp needs counting enabled".to_string());
+        }
+        if let Some(wonging) = &input.wonging {
+            if !counting_enabled {
+                return Err("wonging needs counting enabled".to_string());
+            }
+            wonging.validate()?;
+        }
+        if let Some(kelly) = &input.kelly_betting {
+            if !counting_enabled {
+                return Err("kelly_betting needs counting enabled".to_string());
+            }
+            if input.bet_ramp.is_some() {
+                return Err("kelly_betting and bet_ramp cannot be used together".to_string());
+            }
+            kelly.validate()?;
+        }
+        if let Some(progression) = &input.progression {
+            if input.bet_ramp.is_some() || input.kelly_betting.is_some() {
+                return Err("progression cannot be combined with bet_ramp or kelly_betting".to_string());
+            }
+            progression.validate()?;
+        }
+        if let Some(policy) = &input.bet_policy {
+            if input.bet_ramp.is_some() || input.kelly_betting.is_some() || input.progression.is_some() {
+                return Err("bet_policy cannot be combined with bet_ramp, kelly_betting or progression".to_string());
+            }
+            if policy.uses_count() && !counting_enabled {
+                return Err("bet_policy true count conditions need counting enabled".to_string());
+            }
+            policy.validate()?;
+        }
+        if input.starting_bankroll.is_some_and(|bankroll| !bankroll.is_finite() || bankroll <= 0.0) {
+            return Err("starting_bankroll must be positive".to_string());
+        }
+        if input.stop_on_ruin && input.starting_bankroll.is_none() {
+            return Err("stop_on_ruin needs a starting_bankroll".to_string());
+        }
+        if let Some(target) = input.target_bankroll {
+            match input.starting_bankroll {
+                Some(start) if target.is_finite() && target > start => {}
+                Some(_) => return Err("target_bankroll must be above starting_bankroll".to_string()),
+                None => return Err("target_bankroll needs a starting_bankroll".to_string()),
+            }
+        }
+        let mut game = BlackjackGame::new(deck, game_rules, counter);
+        let strategy_coverage = input.strategy_coverage.then(StrategyCoverage::default);
+        game.set_chart_tracing(input.strategy_coverage);
+        for side_bet in &input.side_bets {
+            side_bet.validate()?;
+        }
+        let side_bets = input.side_bets.iter().cloned().map(SideBetStats::new).collect();
+        let shoe_boundaries: Rc<RefCell<Vec<ShoeBoundary>>> = Rc::new(RefCell::new(Vec::new()));
+        if counting_enabled {
+            let sink = Rc::clone(&shoe_boundaries);
+            game.set_shoe_hook(Box::new(move |boundary| sink.borrow_mut().push(boundary.clone())));
+        }
+
+        let deviation_stats = (input.deviation_report && counting_enabled).then(DeviationStats::default);
+        let varied_bets = input.bet_ramp.is_some() || input.progression.is_some() || input.bet_policy.is_some();
+        let flat_baseline = (input.flat_baseline && (counting_enabled || varied_bets))
+            .then(FlatBaseline::default);
+        let basic_strategy =
+            (deviation_stats.is_some() || flat_baseline.is_some()).then(|| strategy.without_deviations());
+        let bankroll_evaluator = input.bankroll_evaluation.as_ref().map(BankrollEvaluator::new);
+        let kelly_bettor =
+            input.kelly_betting.clone().map(|kelly| KellyBettor::new(kelly, input.bet_size.unit().max(1.0)));
+        let progression = input.progression.clone().map(Progression::new);
+        let policy_bettor = input.bet_policy.clone().map(PolicyBettor::new);
+        let bankroll = input
+            .starting_bankroll
+            .map(|start| BankrollTracker::new(start, input.target_bankroll, input.stop_on_ruin));
+        let table_limits = match (input.table_min, input.table_max) {
+            (None, None) => None,
+            (min, max) => Some(TableLimits::new(min, max)?),
+        };
+        let chip_rounding = input.chip_size.map(ChipRounding::new).transpose()?;
+        let spots = input.spots.unwrap_or(1).max(1) as usize;
+        if let Some(model) = &input.session_model {
+            model.validate()?;
+            if let Some(sessions) = model.sessions {
+                if input.iteration_unit == IterationUnit::Hands {
+                    return Err("a session count needs iterations counted in rounds".to_string());
+                }
+                // An upper bound; the run stops once the sessions are played.
+                input.iterations = sessions.saturating_mul(model.max_rounds_per_session());
+            }
+        }
+        let sessions = input
+            .session_model
+            .as_ref()
+            .map(|model| SessionRecorder::new(model, spots as u32));
+        let series = input.series.as_ref().map(|config| SeriesRecorder::new(config, counting_enabled));
+        let multi_spot_stats = (spots > 1).then(|| MultiSpotStats::new(spots as u32));
+
+        Ok(SimulationRun {
+            strategy,
+            basic_strategy,
+            bet_size: input.bet_size.unit().max(1.0),
+            spots,
+            input,
+            game,
+            counting_enabled,
+            shoe_boundaries,
+            completed: 0,
+            blackjacks: 0,
+            push_22_hands: 0,
+            rounds_observed: 0,
+            hands_played: 0,
+            scenario_true_count_sum: 0.0,
+            round_sum: 0.0,
+            round_sum_sq: 0.0,
+            cell_stats: HashMap::new(),
+            count_stats: init_count_stats(),
+            current_shoe_tc: HashMap::new(),
+            wonged_in: None,
+            double_stats: DoubleStats::default(),
+            split_stats: SplitStats::default(),
+            starting_hands: StartingHandStats::default(),
+            depth_stats: DepthStats::default(),
+            even_money_stats: EvenMoneyStats::default(),
+            insurance_stats: InsuranceStats::default(),
+            bonus_stats: BonusStats::default(),
+            shoe_stats: ShoeStats::default(),
+            deviation_stats,
+            flat_baseline,
+            bankroll_evaluator,
+            kelly_bettor,
+            progression,
+            policy_bettor,
+            bankroll,
+            table_limits,
+            chip_rounding,
+            sessions,
+            series,
+            multi_spot_stats,
+            strategy_coverage,
+            fallbacks: HashMap::new(),
+            strategy_error: None,
+            side_bets,
+            outcome_hash: None,
+        })
+    }
+
+    pub fn completed(&self) -> u32 {
+        self.completed
+    }
+
+    pub fn total(&self) -> u32 {
+        self.input.iterations
+    }
+
+    /// Rounds actually bet so far.
+    pub fn rounds_played(&self) -> u32 {
+        self.completed - self.rounds_observed
+    }
+
+    /// Net result of the rounds played so far, in currency.
+    pub fn net_winnings(&self) -> f64 {
+        self.round_sum
+    }
+
+    /// The bankroll could not cover a bet and the run stopped on ruin.
+    pub fn ruined(&self) -> bool {
+        self.bankroll.as_ref().is_some_and(BankrollTracker::stopped)
+    }
+
+    /// Hashes every hand played from now on, for the engine self-test.
+    pub fn enable_outcome_hash(&mut self) {
+        self.outcome_hash = Some(OutcomeHash::default());
+    }
+
+    pub fn outcome_hash(&self) -> Option<&OutcomeHash> {
+        self.outcome_hash.as_ref()
+    }
+
+    /// Progress towards `total`, in rounds or hands as `iteration_unit` says.
+    pub fn progress(&self) -> u32 {
+        match self.input.iteration_unit {
+            IterationUnit::Rounds => self.completed,
+            IterationUnit::Hands => self.hands_played,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.strategy_error.is_some()
+            || self.ruined()
+            || self.progress() >= self.input.iterations
+            || self.stalled()
+            || self.sessions.as_ref().is_some_and(SessionRecorder::is_complete)
+    }
+
+    /// Whole sessions played so far; zero without a session model.
+    pub fn sessions_completed(&self) -> u32 {
+        self.sessions.as_ref().map_or(0, SessionRecorder::completed_sessions)
+    }
+
+    /// A run counted in hands whose bets sit out (wonging that never enters,
+    /// a zero table maximum, a policy stuck at zero units) would otherwise
+    /// deal forever; it stops once it has dealt far more rounds than asked.
+    fn stalled(&self) -> bool {
+        self.input.iteration_unit == IterationUnit::Hands
+            && (self.completed >= self.input.iterations.saturating_mul(MAX_ROUNDS_PER_HAND)
+                || (self.hands_played == 0 && self.completed >= MAX_ROUNDS_WITHOUT_PLAY))
+    }
+
+    /// Plays up to `rounds` more rounds and returns the progress so far.
+    pub fn step(&mut self, rounds: u32) -> u32 {
+        for _ in 0..rounds {
+            if self.is_done() {
+                break;
+            }
+            self.play_round();
+        }
+        self.progress()
+    }
+
+    pub fn play_round(&mut self) {
+        let played_before = self.round_sum;
+        let observed_before = self.rounds_observed;
+        let true_count = self.play_round_inner();
+        if self.ruined() {
+            return;
+        }
+        if let Some(sessions) = &mut self.sessions {
+            sessions.record(self.round_sum - played_before, self.rounds_observed > observed_before);
+        }
+        if let Some(series) = &mut self.series {
+            let played = self.completed - self.rounds_observed;
+            let ev = if played > 0 { self.round_sum / played as f64 } else { 0.0 };
+            series.record(self.completed, self.round_sum, ev, true_count);
+        }
+    }
+
+    /// Plays one round and returns the true count it was dealt at.
+    fn play_round_inner(&mut self) -> f64 {
+        self.completed += 1;
+        let game = &mut self.game;
+        match self.input.target_true_count {
+            // Every scenario round is a shoe of its own; the first shoe is already fresh.
+            Some(target) => {
+                if self.completed > 1 {
+                    game.reshuffle();
+                }
+                if let Some(counter) = game.counter.as_mut() {
+                    let running_count = burn_to_true_count(&mut game.deck, counter, target);
+                    counter.set_running_count(running_count);
+                }
+            }
+            None => game.prepare_round(),
+        }
         let count_range = game.count_range();
         let true_count = game.get_true_count();
-        if counting_enabled {
-            update_count_stats_pregame(&mut count_stats, true_count);
+        if self.input.target_true_count.is_some() {
+            self.scenario_true_count_sum += true_count;
+        }
+        self.shoe_stats.start_round(game.shoe_index());
+        if self.counting_enabled {
+            for boundary in self.shoe_boundaries.borrow_mut().drain(..) {
+                let true_counts = std::mem::take(&mut self.current_shoe_tc);
+                let keep = self.input.output_detail == OutputDetail::Full;
+                close_shoe(&mut self.count_stats, &boundary, true_counts, keep);
+            }
         }
 
-        let result = game.play_game(&strategy, bet_size);
+        let base_bet = self.input.bet_size.scheduled(self.completed - 1).unwrap_or(self.bet_size);
+        let wanted_bet = if let Some(kelly) = &self.kelly_bettor {
+            kelly.bet(count_range, self.spots)
+        } else if let Some(policy) = &mut self.policy_bettor {
+            base_bet * policy.units(count_range)
+        } else if let Some(ramp) = &self.input.bet_ramp {
+            base_bet * betting::ramp_units(ramp, count_range)
+        } else {
+            base_bet * self.progression.as_ref().map_or(1.0, Progression::units)
+        };
+        let chip_bet = self.chip_rounding.as_ref().map_or(wanted_bet, |chips| chips.round(wanted_bet));
+        let limited = |bet: f64| self.table_limits.as_ref().map_or(bet, |limits| limits.clamp(bet));
+        let (ideal_bet, round_bet) = (limited(wanted_bet), limited(chip_bet));
+        if let Some(wonging) = &self.input.wonging {
+            let stays = self.wonged_in == Some(game.shoe_index()) && true_count >= wonging.exit_true_count;
+            let enters = true_count >= wonging.entry_true_count;
+            self.wonged_in = (stays || enters).then(|| game.shoe_index());
+        }
+        let sits_out = round_bet <= 0.0 || (self.input.wonging.is_some() && self.wonged_in.is_none());
+        if let Some(bankroll) = self.bankroll.as_mut().filter(|_| !sits_out) {
+            if !bankroll.cover(round_bet * self.spots as f64, self.completed) {
+                // Broke with the run stopping on ruin: this round is never dealt.
+                self.completed -= 1;
+                return true_count;
+            }
+        }
+        let mut pre_round = self.basic_strategy.as_ref().map(|_| game.snapshot());
+        if let (Some(baseline), Some(basic_strategy)) = (self.flat_baseline.as_mut(), self.basic_strategy.as_ref()) {
+            // Copy the shoe a second time only when the deviation replay still needs it.
+            let replay_needed = self.deviation_stats.is_some() && !sits_out;
+            let mut table = if replay_needed {
+                pre_round.as_ref().map(BlackjackGame::snapshot)
+            } else {
+                pre_round.take()
+            };
+            let flat = table
+                .as_mut()
+                .map(|table| table.play_round(basic_strategy, &vec![self.bet_size; self.spots]))
+                .unwrap_or_default();
+            baseline.rounds += 1;
+            baseline.total_winnings += flat.iter().map(|r| r.winnings).sum::<f64>();
+            baseline.total_bet += flat.iter().map(|r| r.bet).sum::<f64>();
+        }
+        if sits_out {
+            // A zero-unit rung or wonging out sits the round out: the cards are
+            // still dealt and counted.
+            let watched = game.play_round(&self.strategy, &vec![0.0; self.spots]);
+            if let Some(hash) = &mut self.outcome_hash {
+                watched.iter().for_each(|result| hash.record(result));
+            }
+            self.record_fallbacks(&watched);
+            self.rounds_observed += 1;
+            return true_count;
+        }
 
-        match result.outcome.as_str() {
-            "win" => wins += 1,
-            "lose" => losses += 1,
-            "push" => pushes += 1,
-            "blackjack" => {
-                wins += 1;
-                blackjacks += 1;
+        if let Some(limits) = &mut self.table_limits {
+            limits.record(wanted_bet);
+        }
+        if self.counting_enabled {
+            *self.current_shoe_tc.entry((true_count.round() as i32).to_string()).or_default() += 1;
+        }
+
+        let results = game.play_round(&self.strategy, &vec![round_bet; self.spots]);
+        self.hands_played += results.iter().map(|r| r.hands.len() as u32).sum::<u32>();
+        let basic_results = match (&self.deviation_stats, self.basic_strategy.as_ref(), pre_round) {
+            (Some(_), Some(basic_strategy), Some(mut replay))
+                if results.iter().any(|r| !r.index_plays.is_empty()) =>
+            {
+                Some(replay.play_round(basic_strategy, &vec![round_bet; self.spots]))
+            }
+            _ => None,
+        };
+        if let Some(stats) = &mut self.deviation_stats {
+            for (spot, result) in results.iter().enumerate() {
+                stats.record_round();
+                if let Some(basic) = basic_results.as_ref().filter(|_| !result.index_plays.is_empty()) {
+                    stats.record(result, basic[spot].winnings);
+                }
             }
-            _ => {}
         }
 
-        total_winnings += result.winnings;
-        total_bet += result.bet;
+        if let Some(hash) = &mut self.outcome_hash {
+            results.iter().for_each(|result| hash.record(result));
+        }
+        self.record_fallbacks(&results);
 
-        if counting_enabled {
-            update_count_stats_postgame(&mut count_stats, true_count, result.winnings);
+        let round_winnings: f64 = results.iter().map(|r| r.winnings).sum();
+        self.round_sum += round_winnings;
+        self.round_sum_sq += round_winnings * round_winnings;
+        self.shoe_stats.record(round_winnings / self.bet_size);
+        if let Some(stats) = &mut self.multi_spot_stats {
+            let units: Vec<f64> = results.iter().map(|r| r.winnings / round_bet).collect();
+            stats.record(&units);
         }
 
-        track_cell_stats(&result, count_range, &mut cell_stats);
+        for result in &results {
+            if result.outcome == "blackjack" {
+                self.blackjacks += 1;
+            }
+            if self.game.rules.push_22 && self.game.calculate_hand_value(&result.dealer_cards).0 == 22 {
+                self.push_22_hands +=
+                    result.hands.iter().filter(|hand| hand.result.as_deref() == Some("push")).count() as u32;
+            }
+            if self.counting_enabled {
+                update_count_stats_pregame(&mut self.count_stats, true_count);
+                update_count_stats_postgame(&mut self.count_stats, true_count, result.winnings, result.base_bet);
+            }
 
-        let completed = game_index + 1;
-        if completed % progress_interval == 0 || completed == input.iterations {
-            progress_cb(completed, input.iterations);
+            track_cell_stats(result, count_range, &mut self.cell_stats);
+            self.double_stats.record(result);
+            self.split_stats.record(result, &self.game.rules);
+            self.starting_hands.record(result);
+            self.depth_stats.record(result, self.input.num_decks);
+            self.even_money_stats.record(result, self.game.rules.blackjack_payout());
+            self.insurance_stats.record(result);
+            self.bonus_stats.record(result);
+            if let Some(coverage) = &mut self.strategy_coverage {
+                coverage.record(result);
+            }
+            self.side_bets.iter_mut().for_each(|side_bet| side_bet.record(result, count_range));
+        }
+        if let Some(evaluator) = &mut self.bankroll_evaluator {
+            evaluator.record(round_bet * self.spots as f64, round_winnings);
+        }
+        if let Some(kelly) = &mut self.kelly_bettor {
+            kelly.settle(round_bet * self.spots as f64, round_winnings);
+        }
+        if let Some(progression) = &mut self.progression {
+            progression.record(base_bet, round_bet, round_winnings / self.spots as f64);
         }
+        if let Some(policy) = &mut self.policy_bettor {
+            policy.record(round_bet, round_winnings);
+        }
+        if let Some(bankroll) = &mut self.bankroll {
+            bankroll.record(round_winnings);
+        }
+        if let Some(chips) = &mut self.chip_rounding {
+            chips.record(ideal_bet, round_bet, round_winnings);
+        }
+        true_count
     }
 
-    finalize_count_stats(&mut count_stats);
+    fn record_fallbacks(&mut self, results: &[GameResult]) {
+        let cells = results.iter().flat_map(|result| &result.chart_cells);
+        for cell in cells.filter(|cell| cell.table == ChartSource::Default) {
+            if self.input.strict_strategy && self.strategy_error.is_none() {
+                self.strategy_error = Some(format!("{} (round {})", fallback_message(cell), self.completed));
+            }
+            *self.fallbacks.entry(cell.clone()).or_default() += 1;
+        }
+    }
 
-    let mut agg_wins: u32 = 0;
-    let mut agg_losses: u32 = 0;
-    let mut agg_pushes: u32 = 0;
-    let mut agg_hands: u32 = 0;
-    let aggregated_bet: f64 = cell_stats.values().map(|c| c.total_bet).sum();
-    let aggregated_winnings: f64 = cell_stats.values().map(|c| c.total_winnings).sum();
-    for cell in cell_stats.values() {
-        agg_wins += cell.wins;
-        agg_losses += cell.losses;
-        agg_pushes += cell.pushes;
-        agg_hands += cell.hands;
-    }
-    let total_games = agg_hands.max(input.iterations);
-    wins = agg_wins;
-    losses = agg_losses;
-    pushes = agg_pushes;
-    total_bet = aggregated_bet;
-    total_winnings = aggregated_winnings;
-    let expected_value = if total_games > 0 {
-        total_winnings / total_games as f64
-    } else {
-        0.0
-    };
-    let win_rate = if total_games > 0 {
-        (wins as f64 / total_games as f64) * 100.0
-    } else {
-        0.0
-    };
-    let return_rate = if total_bet.abs() > f64::EPSILON {
-        (total_winnings / total_bet) * 100.0
-    } else {
-        0.0
-    };
+    /// Builds the result from the rounds played so far, or the strict-mode
+    /// error that stopped them.
+    pub fn finish(self) -> Result<SimulationResult, String> {
+        if let Some(err) = self.strategy_error {
+            return Err(err);
+        }
+        let stopped_early = (self.stalled() && self.hands_played < self.input.iterations).then(|| {
+            format!(
+                "stopped after {} rounds with {} of {} hands played: the bets sat out almost every round",
+                self.completed, self.hands_played, self.input.iterations
+            )
+        });
+        let SimulationRun {
+            strategy,
+            input,
+            counting_enabled,
+            bet_size,
+            completed,
+            blackjacks,
+            push_22_hands,
+            rounds_observed,
+            hands_played,
+            scenario_true_count_sum,
+            round_sum,
+            round_sum_sq,
+            mut cell_stats,
+            mut count_stats,
+            mut double_stats,
+            mut split_stats,
+            mut starting_hands,
+            mut depth_stats,
+            mut even_money_stats,
+            mut insurance_stats,
+            mut bonus_stats,
+            mut shoe_stats,
+            mut deviation_stats,
+            mut flat_baseline,
+            bankroll_evaluator,
+            kelly_bettor,
+            progression,
+            policy_bettor,
+            bankroll,
+            table_limits,
+            chip_rounding,
+            sessions,
+            series,
+            mut multi_spot_stats,
+            mut strategy_coverage,
+            fallbacks,
+            mut side_bets,
+            ..
+        } = self;
 
-    Ok(SimulationResult {
-        total_games,
-        wins,
-        losses,
-        pushes,
-        blackjacks,
-        total_winnings,
-        total_bet,
-        expected_value,
-        win_rate,
-        return_rate,
-        count_stats: if counting_enabled {
-            Some(count_stats)
+        finalize_count_stats(&mut count_stats);
+        cell_stats.values_mut().for_each(CellStats::finalize);
+        double_stats.finalize();
+        split_stats.finalize();
+        starting_hands.finalize(input.num_decks);
+        depth_stats.finalize();
+        even_money_stats.finalize();
+        insurance_stats.finalize();
+        bonus_stats.finalize();
+        shoe_stats.finalize();
+        if let Some(stats) = &mut deviation_stats {
+            stats.finalize();
+        }
+        if let Some(stats) = &mut multi_spot_stats {
+            stats.finalize();
+        }
+        if let Some(coverage) = &mut strategy_coverage {
+            coverage.finalize(&strategy);
+        }
+        side_bets.iter_mut().for_each(SideBetStats::finalize);
+        let bankroll_outcomes = bankroll_evaluator.map(BankrollEvaluator::finish).unwrap_or_default();
+        let kelly_betting = kelly_bettor.map(KellyBettor::finish);
+        let progression = progression.map(Progression::finish);
+        let bet_policy = policy_bettor.map(PolicyBettor::finish);
+        let table_limits = table_limits.map(TableLimits::finish);
+        let chip_rounding = chip_rounding.map(ChipRounding::finish);
+        let session_stats = sessions.map(SessionRecorder::finish);
+        let series = series.map(SeriesRecorder::finish);
+
+        let mut agg_wins: u32 = 0;
+        let mut agg_losses: u32 = 0;
+        let mut agg_pushes: u32 = 0;
+        let mut agg_hands: u32 = 0;
+        let aggregated_bet: f64 = cell_stats.values().map(|c| c.total_bet).sum();
+        let aggregated_winnings: f64 = cell_stats.values().map(|c| c.total_winnings).sum();
+        for cell in cell_stats.values() {
+            agg_wins += cell.wins;
+            agg_losses += cell.losses;
+            agg_pushes += cell.pushes;
+            agg_hands += cell.hands;
+        }
+        let rounds_played = completed - rounds_observed;
+        let total_games = agg_hands.max(rounds_played);
+        let std_dev_per_round = if rounds_played > 1 {
+            let n = rounds_played as f64;
+            let mean = round_sum / n;
+            ((round_sum_sq / n - mean * mean) * n / (n - 1.0)).max(0.0).sqrt()
+        } else {
+            0.0
+        };
+        let wins = agg_wins;
+        let losses = agg_losses;
+        let pushes = agg_pushes;
+        let total_bet = aggregated_bet;
+        let total_winnings = aggregated_winnings;
+        let expected_value = if total_games > 0 {
+            total_winnings / total_games as f64
+        } else {
+            0.0
+        };
+        let ev_per_round = if rounds_played > 0 {
+            total_winnings / rounds_played as f64
+        } else {
+            0.0
+        };
+        let bankroll = bankroll.map(|tracker| tracker.finish(ev_per_round, std_dev_per_round * std_dev_per_round));
+        let ev_per_hand = if hands_played > 0 {
+            total_winnings / hands_played as f64
+        } else {
+            0.0
+        };
+        let win_rate = if total_games > 0 {
+            (wins as f64 / total_games as f64) * 100.0
+        } else {
+            0.0
+        };
+        let return_rate = if total_bet.abs() > f64::EPSILON {
+            (total_winnings / total_bet) * 100.0
+        } else {
+            0.0
+        };
+
+        if let Some(baseline) = &mut flat_baseline {
+            let rounds = baseline.rounds.max(1) as f64;
+            baseline.expected_value = baseline.total_winnings / rounds;
+            if baseline.total_bet.abs() > f64::EPSILON {
+                baseline.return_rate = baseline.total_winnings / baseline.total_bet * 100.0;
+            }
+            baseline.ev_gain = total_winnings / rounds - baseline.expected_value;
+        }
+
+        let dealer_upcard_stats = summarize_by_dealer_card(&cell_stats);
+        let chart_overlay = if input.chart_overlay {
+            Some(chart::build_overlay(&cell_stats))
         } else {
             None
-        },
-        cell_stats,
-    })
+        };
+        let bet_recommendation = match (&input.bet_recommendation, counting_enabled) {
+            (Some(config), true) => Some(betting::recommend_ramp(config, &count_stats, bet_size)),
+            _ => None,
+        };
+        let mut count_stats = if counting_enabled { Some(count_stats) } else { None };
+        if input.output_detail == OutputDetail::Summary {
+            count_stats = None;
+            cell_stats.clear();
+            double_stats.by_cell.clear();
+            split_stats.by_cell.clear();
+            starting_hands.hands.clear();
+        }
+
+        Ok(SimulationResult {
+            total_games,
+            wins,
+            losses,
+            pushes,
+            blackjacks,
+            push_22_hands: to_game_rules(&input.rules).push_22.then_some(push_22_hands),
+            total_winnings,
+            total_bet,
+            expected_value,
+            win_rate,
+            return_rate,
+            rounds_observed,
+            rounds_played,
+            hands_played,
+            ev_per_round,
+            ev_per_hand,
+            std_dev_per_round,
+            count_stats,
+            cell_stats,
+            chart_overlay,
+            dealer_upcard_stats,
+            double_stats,
+            split_stats,
+            starting_hands,
+            depth_stats,
+            deviation_stats,
+            flat_baseline,
+            series,
+            multi_spot_stats,
+            even_money_stats: (even_money_stats.offered > 0).then_some(even_money_stats),
+            insurance_stats: (insurance_stats.offered > 0).then_some(insurance_stats),
+            bonus_stats: (bonus_stats.paid > 0).then_some(bonus_stats),
+            shoe_stats: (shoe_stats.shoes > 0).then_some(shoe_stats),
+            seed: input.seed,
+            shoe_bias: input.shoe_bias,
+            strategy_coverage,
+            warnings: fallback_warnings(fallbacks),
+            stopped_early,
+            side_bets,
+            target_true_count: input.target_true_count,
+            achieved_true_count: input
+                .target_true_count
+                .filter(|_| completed > 0)
+                .map(|_| scenario_true_count_sum / completed as f64),
+            bet_recommendation,
+            bankroll_outcomes,
+            kelly_betting,
+            progression,
+            bet_policy,
+            bankroll,
+            table_limits,
+            chip_rounding,
+            session_stats,
+        })
+    }
+}
+
+pub fn build_deck(num_decks: u8, rules: &RulesInput, seed: u64) -> Deck {
+    let penetration = rules.penetration_threshold.unwrap_or(75.0);
+    let mut deck = Deck::new(num_decks, penetration, seed);
+    deck.set_reshuffle_policy(rules.reshuffle_policy.unwrap_or_default());
+    deck
 }
 
 pub fn to_game_rules(rules: &RulesInput) -> GameRules {
+    let pontoon = rules.variant == Some(Variant::Pontoon);
+    let zappit = rules.variant == Some(Variant::Zappit);
     GameRules {
-        dealer_hits_soft_17: rules.dealer_hits_soft_17,
-        dealer_stands_on: rules
-            .dealer_stands_on
-            .clone()
-            .unwrap_or_else(|| "17".to_string()),
+        dealer_rule: rules.dealer_rule,
         double_after_split: rules.double_after_split.unwrap_or(true),
+        double_split_aces: rules
+            .double_split_aces
+            .unwrap_or_else(|| rules.double_after_split.unwrap_or(true)),
+        hit_split_aces: rules.hit_split_aces.unwrap_or(true),
+        split_identical_ranks: rules.split_identical_ranks.unwrap_or(false),
+        double_on: rules.double_on.unwrap_or_default(),
+        double_after_hit: rules.double_after_hit.unwrap_or(false),
+        max_double_multiplier: rules.max_double_multiplier.unwrap_or(2),
+        double_rescue: rules.double_rescue.unwrap_or(false),
+        charlie: rules.charlie.or(pontoon.then_some(5)),
+        push_22: rules.push_22.unwrap_or(zappit),
+        double_exposure: rules.double_exposure.unwrap_or(false),
+        dealer_wins_ties: rules.dealer_wins_ties.clone().unwrap_or_default(),
+        variant: rules.variant.unwrap_or_default(),
         allow_resplit: rules.allow_resplit.unwrap_or(true),
         _resplit_aces: rules.resplit_aces.unwrap_or(false),
-        blackjack_pays: rules
-            .blackjack_pays
-            .clone()
-            .unwrap_or_else(|| "3:2".to_string()),
+        max_split_hands: rules.max_split_hands,
+        max_split_ace_hands: rules.max_split_ace_hands,
+        blackjack_pays: rules.blackjack_pays.unwrap_or(if pontoon {
+            BlackjackPayout(2.0)
+        } else if zappit || rules.double_exposure.unwrap_or(false) {
+            BlackjackPayout(1.0)
+        } else {
+            BlackjackPayout::default()
+        }),
+        late_surrender: rules.late_surrender.unwrap_or(false),
+        early_surrender: rules.early_surrender.unwrap_or(false),
+        original_bets_only: rules.original_bets_only.unwrap_or(false),
+        peek_aces: rules.peek_aces.unwrap_or(false),
+        peek_tens: rules.peek_tens.unwrap_or(false),
+        split_blackjack_pays: rules.split_blackjack_pays.unwrap_or(false),
+        hand_bonuses: rules.hand_bonuses.clone().unwrap_or_default(),
+        insurance: rules.insurance.unwrap_or(false),
     }
 }
 
@@ -250,9 +1456,36 @@ fn init_count_stats() -> CountStats {
         count_distribution: HashMap::new(),
         ev_by_count: HashMap::new(),
         hands_by_count: HashMap::new(),
+        advantage_by_count: HashMap::new(),
+        variance_by_count: HashMap::new(),
+        squared_units_by_count: HashMap::new(),
+        shoes_completed: 0,
+        avg_hands_per_shoe: 0.0,
+        final_running_count_distribution: HashMap::new(),
+        shoes: Vec::new(),
+        hands_in_closed_shoes: 0,
     }
 }
 
+/// Counts a finished shoe, keeping its summary only when `keep` is set so
+/// long runs do not grow a list nobody reads.
+fn close_shoe(stats: &mut CountStats, boundary: &ShoeBoundary, true_counts: HashMap<String, u32>, keep: bool) {
+    let rc_key = (boundary.final_running_count.round() as i32).to_string();
+    *stats.final_running_count_distribution.entry(rc_key).or_default() += 1;
+    stats.shoes_completed += 1;
+    stats.hands_in_closed_shoes += boundary.rounds_played;
+    if !keep {
+        return;
+    }
+    stats.shoes.push(ShoeCountSummary {
+        shoe_index: boundary.shoe_index,
+        hands: boundary.rounds_played,
+        cards_dealt: boundary.cards_dealt,
+        final_running_count: boundary.final_running_count,
+        true_count_distribution: true_counts,
+    });
+}
+
 fn update_count_stats_pregame(stats: &mut CountStats, true_count: f64) {
     let count_bucket = true_count.round() as i32;
     let key = count_bucket.to_string();
@@ -261,22 +1494,73 @@ fn update_count_stats_pregame(stats: &mut CountStats, true_count: f64) {
     stats.total_hands += 1;
 }
 
-fn update_count_stats_postgame(stats: &mut CountStats, true_count: f64, winnings: f64) {
+fn update_count_stats_postgame(stats: &mut CountStats, true_count: f64, winnings: f64, base_bet: f64) {
     let count_bucket = true_count.round() as i32;
     let key = count_bucket.to_string();
+    let units = if base_bet > 0.0 { winnings / base_bet } else { 0.0 };
+    *stats.advantage_by_count.entry(key.clone()).or_default() += units;
+    *stats.squared_units_by_count.entry(key.clone()).or_default() += units * units;
     *stats.ev_by_count.entry(key).or_default() += winnings;
 }
 
 fn finalize_count_stats(stats: &mut CountStats) {
+    if stats.shoes_completed > 0 {
+        stats.avg_hands_per_shoe = stats.hands_in_closed_shoes as f64 / stats.shoes_completed as f64;
+    }
     for (key, total) in stats.hands_by_count.clone() {
         if total > 0 {
             if let Some(sum) = stats.ev_by_count.get_mut(&key) {
                 *sum /= total as f64;
             }
+            let mean = stats.advantage_by_count.get(&key).copied().unwrap_or(0.0) / total as f64;
+            let squares = stats.squared_units_by_count.get(&key).copied().unwrap_or(0.0) / total as f64;
+            stats.advantage_by_count.insert(key.clone(), mean);
+            stats.variance_by_count.insert(key, (squares - mean * mean).max(0.0));
         }
     }
 }
 
+fn summarize_by_dealer_card(cell_stats: &HashMap<String, CellStats>) -> Vec<DealerUpcardStats> {
+    let columns = ["2", "3", "4", "5", "6", "7", "8", "9", "10", "A"];
+    let mut rows: Vec<DealerUpcardStats> = columns
+        .iter()
+        .map(|card| DealerUpcardStats {
+            dealer_card: card.to_string(),
+            hands: 0,
+            frequency: 0.0,
+            wins: 0,
+            losses: 0,
+            pushes: 0,
+            total_winnings: 0.0,
+            total_bet: 0.0,
+            expected_value: 0.0,
+            win_rate: 0.0,
+        })
+        .collect();
+    for cell in cell_stats.values() {
+        let Some(row) = rows.iter_mut().find(|r| r.dealer_card == cell.dealer_card) else {
+            continue;
+        };
+        row.hands += cell.hands;
+        row.wins += cell.wins;
+        row.losses += cell.losses;
+        row.pushes += cell.pushes;
+        row.total_winnings += cell.total_winnings;
+        row.total_bet += cell.total_bet;
+    }
+    let total_hands: u32 = rows.iter().map(|r| r.hands).sum();
+    for row in &mut rows {
+        if row.hands > 0 {
+            row.expected_value = row.total_winnings / row.hands as f64;
+            row.win_rate = (row.wins as f64 / row.hands as f64) * 100.0;
+        }
+        if total_hands > 0 {
+            row.frequency = row.hands as f64 / total_hands as f64;
+        }
+    }
+    rows
+}
+
 fn track_cell_stats(result: &GameResult, count_key: i32, cell_stats: &mut HashMap<String, CellStats>) {
     let player_total = describe_player_total(&result.player_cards);
     let dealer_card = describe_dealer_card(&result.dealer_up_card);
@@ -298,14 +1582,17 @@ fn track_cell_stats(result: &GameResult, count_key: i32, cell_stats: &mut HashMa
         pushes: 0,
         total_winnings: 0.0,
         total_bet: 0.0,
+        total_winnings_sq: 0.0,
+        standard_error: None,
     });
 
     entry.hands += 1;
     entry.total_bet += result.bet;
     entry.total_winnings += result.winnings;
+    entry.total_winnings_sq += result.winnings * result.winnings;
 
     match result.outcome.as_str() {
-        "win" | "blackjack" => entry.wins += 1,
+        "win" | "blackjack" | "charlie" => entry.wins += 1,
         "lose" => entry.losses += 1,
         _ => entry.pushes += 1,
     }
@@ -365,6 +1652,10 @@ pub struct SpotCheckInput {
     pub forced_action: String,
     #[serde(default)]
     pub counting: Option<CountingInput>,
+    /// Deals each hand at this true count by first removing cards whose tag
+    /// has the same sign; needs counting.
+    #[serde(default)]
+    pub true_count: Option<f64>,
 }
 
 #[derive(Debug, Serialize)]
@@ -381,8 +1672,54 @@ pub struct SpotCheckResult {
     pub return_rate: f64,
 }
 
+/// Removes cards whose tag has the sign of `true_count`, cycling through those
+/// ranks, until the removed cards put the shoe as close to that true count as
+/// whole cards allow, stopping short rather than overshooting by more. Returns
+/// the running count of the removed cards. The rest of the shoe is reshuffled,
+/// as removing by rank takes cards from the bottom of the shoe.
+fn burn_to_true_count(deck: &mut Deck, counter: &CardCounter, true_count: f64) -> f64 {
+    let sign = true_count.signum() as i32;
+    let ranks: Vec<&str> = RANKS
+        .iter()
+        .copied()
+        .filter(|rank| counter.tag(&Card::new(rank)).signum() == sign)
+        .collect();
+    let mut running_count = 0.0;
+    let mut next = 0;
+    let mut misses = 0;
+    let reached = |running_count: f64, cards: usize| running_count / (cards as f64 / 52.0).max(0.5);
+    while reached(running_count, deck.remaining_cards()).abs() < true_count.abs() && misses < ranks.len() {
+        let rank = ranks[next % ranks.len()];
+        next += 1;
+        if !deck.has_rank(rank) {
+            misses += 1;
+            continue;
+        }
+        let tag = counter.tag(&Card::new(rank)) as f64;
+        let now = reached(running_count, deck.remaining_cards());
+        let after = reached(running_count + tag, deck.remaining_cards() - 1);
+        if (after - true_count).abs() > (now - true_count).abs() {
+            break;
+        }
+        deck.remove_card_by_rank(rank);
+        running_count += tag;
+        misses = 0;
+    }
+    deck.shuffle_remaining();
+    running_count
+}
+
 pub fn run_spot_check(input: SpotCheckInput) -> Result<SpotCheckResult, String> {
-    let strategy = Strategy::from_input(input.strategy)?;
+    let strategy = Strategy::from_input(input.strategy.clone())?;
+    spot_check(&strategy, &input)
+}
+
+/// Runs a spot check with an already-parsed strategy; `input.strategy` is ignored.
+pub fn spot_check(strategy: &Strategy, input: &SpotCheckInput) -> Result<SpotCheckResult, String> {
+    if input.num_decks == 0 {
+        return Err("spot check needs at least one deck".to_string());
+    }
+    input.rules.validate()?;
     let game_rules = to_game_rules(&input.rules);
     
     let mut wins = 0;
@@ -395,7 +1732,7 @@ pub fn run_spot_check(input: SpotCheckInput) -> Result<SpotCheckResult, String>
     let mut rng_seed = input.seed;
     
     for _ in 0..input.iterations {
-        let mut deck = Deck::new(input.num_decks, 100, rng_seed);
+        let mut deck = Deck::new(input.num_decks, 100.0, rng_seed);
         rng_seed = rng_seed.wrapping_add(1);
         
         for card_rank in &input.player_cards {
@@ -403,7 +1740,11 @@ pub fn run_spot_check(input: SpotCheckInput) -> Result<SpotCheckResult, String>
         }
         deck.remove_card_by_rank(&input.dealer_card);
         
-        let counter_for_game = build_counter(input.counting.clone());
+        let mut counter_for_game = build_counter(input.counting.clone());
+        if let (Some(true_count), Some(counter)) = (input.true_count, counter_for_game.as_mut()) {
+            let running_count = burn_to_true_count(&mut deck, counter, true_count);
+            counter.set_running_count(running_count);
+        }
         let mut game = BlackjackGame::new(deck, game_rules.clone(), counter_for_game);
         
         let player_cards: Vec<Card> = input.player_cards.iter()
@@ -415,16 +1756,17 @@ pub fn run_spot_check(input: SpotCheckInput) -> Result<SpotCheckResult, String>
         let dealer_cards = vec![dealer_up.clone(), dealer_hole];
         
         if game.is_blackjack(&player_cards) {
-            if game.is_blackjack(&dealer_cards) {
+            if game.is_blackjack(&dealer_cards) && game_rules.pontoon() {
+                losses += 1;
+                total_winnings -= bet_size;
+                total_bet += bet_size;
+                continue;
+            } else if game.is_blackjack(&dealer_cards) && !game_rules.double_exposure {
                 pushes += 1;
                 total_bet += bet_size;
                 continue;
             } else {
-                let payout = match game_rules.blackjack_pays.as_str() {
-                    "6:5" => 1.2,
-                    "1:1" => 1.0,
-                    _ => 1.5,
-                };
+                let payout = game_rules.blackjack_payout();
                 wins += 1;
                 total_winnings += bet_size * payout;
                 total_bet += bet_size;
@@ -434,7 +1776,9 @@ pub fn run_spot_check(input: SpotCheckInput) -> Result<SpotCheckResult, String>
         
         if game.is_blackjack(&dealer_cards) {
             losses += 1;
-            total_winnings -= bet_size;
+            // An early surrender is made before the dealer's natural is known.
+            let surrendered = game_rules.early_surrender && input.forced_action == "R";
+            total_winnings -= if surrendered { bet_size / 2.0 } else { bet_size };
             total_bet += bet_size;
             continue;
         }
@@ -448,18 +1792,36 @@ pub fn run_spot_check(input: SpotCheckInput) -> Result<SpotCheckResult, String>
         let mut hands = vec![crate::game::HandRecord {
             cards: player_cards.clone(),
             bet: 1.0,
-            result: None,
+            original_bet: 1.0,
+            ..Default::default()
         }];
         
         let action = match input.forced_action.as_str() {
             "D" => crate::strategy::Action::Double,
+            "D3" => crate::strategy::Action::Triple,
+            "D4" => crate::strategy::Action::Quadruple,
             "P" => crate::strategy::Action::Split,
             "S" => crate::strategy::Action::Stand,
+            "R" => crate::strategy::Action::Surrender,
+            "Z" if game_rules.zap_allowed(&player_cards) => crate::strategy::Action::Zap,
             _ => crate::strategy::Action::Hit,
         };
+        if action == crate::strategy::Action::Surrender {
+            // Late surrender: the dealer has already peeked, so half the bet is lost.
+            losses += 1;
+            total_winnings -= bet_size / 2.0;
+            total_bet += bet_size;
+            continue;
+        }
         
-        let can_double = player_cards.len() == 2;
+        let can_double = (player_cards.len() == 2 || game_rules.double_after_hit)
+            && game_rules.double_on.allows(&player_cards);
         let is_pair = player_cards.len() == 2 && game.can_split(&player_cards);
+        let action = if action.double_multiplier().is_some() && !can_double {
+            crate::strategy::Action::Hit
+        } else {
+            game_rules.limit_double(action, player_cards.len())
+        };
         
         match action {
             crate::strategy::Action::Split => {
@@ -468,21 +1830,30 @@ pub fn run_spot_check(input: SpotCheckInput) -> Result<SpotCheckResult, String>
                     let new_hand = crate::game::HandRecord {
                         cards: vec![card, game.deal_card()],
                         bet: 1.0,
-                        result: None,
+                        ..Default::default()
                     };
                     hands[0].cards.push(game.deal_card());
                     hands.push(new_hand);
                 }
             }
-            crate::strategy::Action::Double => {
-                if can_double && player_cards.len() == 2 {
+            crate::strategy::Action::Double
+            | crate::strategy::Action::Triple
+            | crate::strategy::Action::Quadruple => {
+                if can_double {
+                    let multiplier = action.double_multiplier().unwrap_or(2) as f64;
+                    hands[0].doubled = true;
+                    hands[0].bet = multiplier;
                     hands[0].cards.push(game.deal_card());
+                    game.rescue_double(strategy, &mut hands[0], multiplier, &dealer_label);
                 }
             }
             crate::strategy::Action::Hit => {
                 hands[0].cards.push(game.deal_card());
             }
-            crate::strategy::Action::Stand => {}
+            crate::strategy::Action::Zap => {
+                hands[0].cards = vec![game.deal_card(), game.deal_card()];
+            }
+            crate::strategy::Action::Stand | crate::strategy::Action::Surrender => {}
         }
         
         if action == crate::strategy::Action::Split {
@@ -494,7 +1865,9 @@ pub fn run_spot_check(input: SpotCheckInput) -> Result<SpotCheckResult, String>
                     i += 1;
                     continue;
                 }
-                while game.calculate_hand_value(&hands[i].cards).0 < 21 {
+                while game.calculate_hand_value(&hands[i].cards).0 < 21
+                    && !game.is_charlie(&hands[i].cards)
+                {
                     let (value, is_soft) = game.calculate_hand_value(&hands[i].cards);
                     // Check if this is a pair and if resplitting is allowed
                     let is_pair = game.can_split(&hands[i].cards);
@@ -502,13 +1875,18 @@ pub fn run_spot_check(input: SpotCheckInput) -> Result<SpotCheckResult, String>
                                      hands[i].cards[0].rank == "A";
                     // We're already in split hands, so any pair is a potential resplit
                     // Check resplitting rules: aces use resplit_aces, others use allow_resplit
-                    let can_resplit = is_pair ? (
-                        if is_ace_pair {
-                            game_rules._resplit_aces
-                        } else {
-                            game_rules.allow_resplit
-                        }
-                    ) : false;
+                    let split_permitted = if !is_pair {
+                        false
+                    } else if is_ace_pair {
+                        game_rules._resplit_aces
+                    } else {
+                        game_rules.allow_resplit
+                    };
+                    let can_resplit = split_permitted && game_rules.split_allowed(hands.len(), is_ace_pair);
+                    let one_card_ace = hands[i].cards[0].rank == "A" && !game_rules.hit_split_aces;
+                    if one_card_ace && !can_resplit {
+                        break;
+                    }
                     
                     // Use pair strategy if it's a pair and resplitting is allowed
                     let player_label = if is_pair && can_resplit {
@@ -528,26 +1906,44 @@ pub fn run_spot_check(input: SpotCheckInput) -> Result<SpotCheckResult, String>
                         value.to_string()
                     };
                     let count = game.count_range();
-                    let can_double_after_split = game_rules.double_after_split && hands[i].cards.len() == 2;
+                    let das = if hands[i].cards[0].rank == "A" {
+                        game_rules.double_split_aces && game_rules.hit_split_aces
+                    } else {
+                        game_rules.double_after_split
+                    };
+                    let can_double_after_split = das
+                        && (hands[i].cards.len() == 2 || game_rules.double_after_hit)
+                        && game_rules.double_on.allows(&hands[i].cards);
                     let hand_action = strategy.decide_action(
                         &player_label,
                         &dealer_label,
                         can_double_after_split,
                         can_resplit,
+                        false,
                         count,
                     );
+                    let hand_action = game_rules.limit_double(hand_action, hands[i].cards.len());
+                    if one_card_ace && hand_action != crate::strategy::Action::Split {
+                        break;
+                    }
                     
                     match hand_action {
-                        crate::strategy::Action::Hit => {
+                        // A zap is only open on the opening hand
+                        crate::strategy::Action::Hit | crate::strategy::Action::Zap => {
                             hands[i].cards.push(game.deal_card());
                             if game.calculate_hand_value(&hands[i].cards).0 > 21 {
                                 break;
                             }
                         }
-                        crate::strategy::Action::Double => {
+                        crate::strategy::Action::Double
+                        | crate::strategy::Action::Triple
+                        | crate::strategy::Action::Quadruple => {
                             if can_double_after_split {
-                                hands[i].bet *= 2.0;
+                                let multiplier = hand_action.double_multiplier().unwrap_or(2) as f64;
+                                hands[i].doubled = true;
+                                hands[i].bet *= multiplier;
                                 hands[i].cards.push(game.deal_card());
+                                game.rescue_double(strategy, &mut hands[i], multiplier, &dealer_label);
                                 break; // Double gets exactly one card
                             } else {
                                 // Can't double, hit instead
@@ -564,7 +1960,7 @@ pub fn run_spot_check(input: SpotCheckInput) -> Result<SpotCheckResult, String>
                                 let new_hand = crate::game::HandRecord {
                                     cards: vec![card, game.deal_card()],
                                     bet: hands[i].bet,
-                                    result: None,
+                                    ..Default::default()
                                 };
                                 hands[i].cards.push(game.deal_card());
                                 hands.push(new_hand);
@@ -586,11 +1982,13 @@ pub fn run_spot_check(input: SpotCheckInput) -> Result<SpotCheckResult, String>
                 }
                 i += 1;
             }
-        } else if action == crate::strategy::Action::Hit {
+        } else if action == crate::strategy::Action::Hit || action == crate::strategy::Action::Zap {
             if game.calculate_hand_value(&hands[0].cards).0 > 21 {
                 hands[0].result = Some("lose".to_string());
             } else {
-                while game.calculate_hand_value(&hands[0].cards).0 < 21 {
+                while game.calculate_hand_value(&hands[0].cards).0 < 21
+                    && !game.is_charlie(&hands[0].cards)
+                {
                     let (value, is_soft) = game.calculate_hand_value(&hands[0].cards);
                     let player_label = if is_soft {
                         format!("S{}", value)
@@ -598,21 +1996,33 @@ pub fn run_spot_check(input: SpotCheckInput) -> Result<SpotCheckResult, String>
                         value.to_string()
                     };
                     let count = game.count_range();
+                    let can_double_after_hit =
+                        game_rules.double_after_hit && game_rules.double_on.allows(&hands[0].cards);
                     let hand_action = strategy.decide_action(
                         &player_label,
                         &dealer_label,
+                        can_double_after_hit,
                         false,
                         false,
                         count,
                     );
+                    let hand_action = game_rules.limit_double(hand_action, hands[0].cards.len());
                     
                     match hand_action {
-                        crate::strategy::Action::Hit => {
+                        // A zap is only open on the opening hand
+                        crate::strategy::Action::Hit | crate::strategy::Action::Zap => {
                             hands[0].cards.push(game.deal_card());
                             if game.calculate_hand_value(&hands[0].cards).0 > 21 {
                                 break;
                             }
                         }
+                        crate::strategy::Action::Double if can_double_after_hit => {
+                            hands[0].doubled = true;
+                            hands[0].bet *= 2.0;
+                            hands[0].cards.push(game.deal_card());
+                            game.rescue_double(strategy, &mut hands[0], 2.0, &dealer_label);
+                            break;
+                        }
                         _ => break,
                     }
                 }
@@ -635,7 +2045,7 @@ pub fn run_spot_check(input: SpotCheckInput) -> Result<SpotCheckResult, String>
         for hand in &hands {
             let bet_amount = bet_size * hand.bet;
             if let Some(result) = &hand.result {
-                if result == "lose" {
+                if result == "lose" || hand.rescued {
                     hand_winnings -= bet_amount;
                     continue;
                 }
@@ -643,9 +2053,17 @@ pub fn run_spot_check(input: SpotCheckInput) -> Result<SpotCheckResult, String>
             let player_value = game.calculate_hand_value(&hand.cards).0;
             if player_value > 21 {
                 hand_winnings -= bet_amount;
+            } else if game.is_charlie(&hand.cards) {
+                hand_winnings += bet_amount * game_rules.charlie_payout();
+            } else if hands.len() > 1 && game.pays_split_natural(&hand.cards) {
+                hand_winnings += bet_amount * game.rules.blackjack_payout();
+            } else if let Some(bonus) = game.hand_bonus(hand) {
+                hand_winnings += bet_amount * bonus;
+            } else if game_rules.push_22 && dealer_value == 22 {
+                // Push
             } else if dealer_bust || player_value > dealer_value {
                 hand_winnings += bet_amount;
-            } else if player_value < dealer_value {
+            } else if player_value < dealer_value || game_rules.dealer_wins_tie(player_value) {
                 hand_winnings -= bet_amount;
             }
         }
@@ -0,0 +1,1076 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game::{BlackjackGame, GameResult, GameRules, IndexPlay},
+    strategy::{Action, ChartCell, ChartSource, Strategy},
+};
+
+fn dealer_label(result: &GameResult) -> String {
+    let card = &result.dealer_up_card;
+    if card.rank == "A" {
+        "A".to_string()
+    } else {
+        card.value.to_string()
+    }
+}
+
+fn per_hand(total: f64, hands: u32) -> f64 {
+    if hands > 0 {
+        total / hands as f64
+    } else {
+        0.0
+    }
+}
+
+/// Entry `q` of the way through a sorted, non-empty slice.
+pub fn quantile<T: Copy>(sorted: &[T], q: f64) -> T {
+    sorted[((sorted.len() - 1) as f64 * q).round() as usize]
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DoubleStats {
+    pub offered: u32,
+    pub taken: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub pushes: u32,
+    /// Doubles given up under double down rescue, and their share of the
+    /// doubles taken.
+    pub rescues: u32,
+    pub rescue_frequency: f64,
+    pub total_winnings: f64,
+    pub total_bet: f64,
+    pub expected_value: f64,
+    /// Two-card hands where the chart said double but the rules forbade it.
+    pub blocked: u32,
+    pub blocked_winnings: f64,
+    pub blocked_expected_value: f64,
+    /// Estimated EV given up on blocked hands, using the EV of doubles taken
+    /// in the same cell as the counterfactual.
+    pub forfeited_ev_estimate: f64,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub by_cell: HashMap<String, DoubleCellStats>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DoubleCellStats {
+    pub player_total: String,
+    pub dealer_card: String,
+    pub taken: u32,
+    pub total_winnings: f64,
+    pub expected_value: f64,
+    pub blocked: u32,
+    pub blocked_winnings: f64,
+    pub blocked_expected_value: f64,
+}
+
+impl DoubleStats {
+    pub fn record(&mut self, result: &GameResult) {
+        self.offered += result.doubles_offered;
+        let dealer = dealer_label(result);
+        for hand in &result.hands {
+            let Some(label) = &hand.double_label else {
+                continue;
+            };
+            let cell = self
+                .by_cell
+                .entry(format!("{label}_{dealer}"))
+                .or_insert_with(|| DoubleCellStats {
+                    player_total: label.clone(),
+                    dealer_card: dealer.clone(),
+                    taken: 0,
+                    total_winnings: 0.0,
+                    expected_value: 0.0,
+                    blocked: 0,
+                    blocked_winnings: 0.0,
+                    blocked_expected_value: 0.0,
+                });
+            if hand.doubled {
+                cell.taken += 1;
+                cell.total_winnings += hand.winnings;
+                self.taken += 1;
+                self.total_winnings += hand.winnings;
+                self.total_bet += result.base_bet * hand.bet;
+                match hand.result.as_deref() {
+                    Some("win" | "charlie") => self.wins += 1,
+                    Some("lose") => self.losses += 1,
+                    Some("rescue") => self.rescues += 1,
+                    _ => self.pushes += 1,
+                }
+            } else if hand.double_blocked {
+                cell.blocked += 1;
+                cell.blocked_winnings += hand.winnings;
+                self.blocked += 1;
+                self.blocked_winnings += hand.winnings;
+            }
+        }
+    }
+
+    pub fn finalize(&mut self) {
+        self.expected_value = per_hand(self.total_winnings, self.taken);
+        self.rescue_frequency = per_hand(self.rescues as f64, self.taken);
+        self.blocked_expected_value = per_hand(self.blocked_winnings, self.blocked);
+        let mut forfeited = 0.0;
+        for cell in self.by_cell.values_mut() {
+            cell.expected_value = per_hand(cell.total_winnings, cell.taken);
+            cell.blocked_expected_value = per_hand(cell.blocked_winnings, cell.blocked);
+            if cell.taken > 0 && cell.blocked > 0 {
+                forfeited += cell.blocked as f64 * (cell.expected_value - cell.blocked_expected_value);
+            }
+        }
+        self.forfeited_ev_estimate = forfeited;
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitStats {
+    pub rounds: u32,
+    pub pairs_dealt: u32,
+    pub rounds_split: u32,
+    pub split_frequency: f64,
+    pub hands_created: u32,
+    pub avg_hands_per_split: f64,
+    pub resplits: u32,
+    pub rounds_resplit: u32,
+    pub resplit_frequency: f64,
+    pub resplits_blocked: u32,
+    pub total_winnings: f64,
+    pub expected_value: f64,
+    pub by_pair: HashMap<String, SplitPairStats>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub by_cell: HashMap<String, SplitCellStats>,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitPairStats {
+    pub dealt: u32,
+    pub split: u32,
+    pub split_frequency: f64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitCellStats {
+    pub pair: String,
+    pub dealer_card: String,
+    pub splits: u32,
+    pub hands_created: u32,
+    pub resplits: u32,
+    pub resplits_blocked: u32,
+    pub total_winnings: f64,
+    pub expected_value: f64,
+}
+
+impl SplitStats {
+    pub fn record(&mut self, result: &GameResult, rules: &GameRules) {
+        self.rounds += 1;
+        let cards = &result.player_cards;
+        let Some(pair) = BlackjackGame::strategy_pair_label(cards).filter(|_| rules.is_pair(cards)) else {
+            return;
+        };
+        self.pairs_dealt += 1;
+        let pair_stats = self.by_pair.entry(pair.clone()).or_default();
+        pair_stats.dealt += 1;
+        self.resplits_blocked += result.resplits_blocked;
+        if result.initial_action != Some(Action::Split) || result.splits == 0 {
+            return;
+        }
+        pair_stats.split += 1;
+        let resplits = result.splits - 1;
+        self.rounds_split += 1;
+        self.hands_created += result.hands.len() as u32;
+        self.resplits += resplits;
+        if resplits > 0 {
+            self.rounds_resplit += 1;
+        }
+        self.total_winnings += result.winnings;
+
+        let dealer = dealer_label(result);
+        let cell = self
+            .by_cell
+            .entry(format!("{pair}_{dealer}"))
+            .or_insert_with(|| SplitCellStats {
+                pair: pair.clone(),
+                dealer_card: dealer.clone(),
+                splits: 0,
+                hands_created: 0,
+                resplits: 0,
+                resplits_blocked: 0,
+                total_winnings: 0.0,
+                expected_value: 0.0,
+            });
+        cell.splits += 1;
+        cell.hands_created += result.hands.len() as u32;
+        cell.resplits += resplits;
+        cell.resplits_blocked += result.resplits_blocked;
+        cell.total_winnings += result.winnings;
+    }
+
+    pub fn finalize(&mut self) {
+        if self.rounds > 0 {
+            self.split_frequency = self.rounds_split as f64 / self.rounds as f64;
+        }
+        if self.rounds_split > 0 {
+            self.avg_hands_per_split = self.hands_created as f64 / self.rounds_split as f64;
+            self.resplit_frequency = self.rounds_resplit as f64 / self.rounds_split as f64;
+        }
+        self.expected_value = per_hand(self.total_winnings, self.rounds_split);
+        for pair in self.by_pair.values_mut() {
+            if pair.dealt > 0 {
+                pair.split_frequency = pair.split as f64 / pair.dealt as f64;
+            }
+        }
+        for cell in self.by_cell.values_mut() {
+            cell.expected_value = per_hand(cell.total_winnings, cell.splits);
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartingHandStats {
+    pub rounds: u32,
+    /// Keyed by "{player_hand}_{dealer_card}".
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub hands: HashMap<String, StartingHandCell>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartingHandCell {
+    pub player_hand: String,
+    pub dealer_card: String,
+    pub count: u32,
+    pub frequency: f64,
+    /// Probability of this deal off a freshly shuffled shoe, for validating the dealer.
+    pub expected_frequency: f64,
+}
+
+impl StartingHandStats {
+    pub fn record(&mut self, result: &GameResult) {
+        self.rounds += 1;
+        let hand = starting_hand_label(result.player_cards[0].value, result.player_cards[1].value);
+        let dealer = dealer_label(result);
+        self.hands
+            .entry(format!("{hand}_{dealer}"))
+            .or_insert_with(|| StartingHandCell {
+                player_hand: hand,
+                dealer_card: dealer,
+                count: 0,
+                frequency: 0.0,
+                expected_frequency: 0.0,
+            })
+            .count += 1;
+    }
+
+    pub fn finalize(&mut self, num_decks: u8) {
+        let expected = expected_starting_hands(num_decks);
+        for (key, cell) in self.hands.iter_mut() {
+            if self.rounds > 0 {
+                cell.frequency = cell.count as f64 / self.rounds as f64;
+            }
+            cell.expected_frequency = expected.get(key).copied().unwrap_or(0.0);
+        }
+    }
+}
+
+/// Chart label for a two-card hand given card values (ace = 11).
+fn starting_hand_label(first: u8, second: u8) -> String {
+    let symbol = |v: u8| if v == 11 { "A".to_string() } else { v.to_string() };
+    if first == second {
+        return format!("{},{}", symbol(first), symbol(second));
+    }
+    if first == 11 || second == 11 {
+        return format!("S{}", first + second);
+    }
+    (first + second).to_string()
+}
+
+/// Exact deal probabilities for player two cards and dealer up card off a full shoe.
+fn expected_starting_hands(num_decks: u8) -> HashMap<String, f64> {
+    let decks = num_decks.max(1) as f64;
+    let values: [u8; 10] = [2, 3, 4, 5, 6, 7, 8, 9, 10, 11];
+    let count_of = |v: u8| if v == 10 { 16.0 * decks } else { 4.0 * decks };
+    let total = 52.0 * decks;
+    let mut expected = HashMap::new();
+    for &a in &values {
+        for &b in &values {
+            for &d in &values {
+                let same_ab = if a == b { 1.0 } else { 0.0 };
+                let taken_d = (if d == a { 1.0 } else { 0.0 }) + (if d == b { 1.0 } else { 0.0 });
+                let p = count_of(a) / total
+                    * (count_of(b) - same_ab)
+                    / (total - 1.0)
+                    * (count_of(d) - taken_d)
+                    / (total - 2.0);
+                let dealer = if d == 11 { "A".to_string() } else { d.to_string() };
+                let key = format!("{}_{}", starting_hand_label(a, b), dealer);
+                *expected.entry(key).or_insert(0.0) += p;
+            }
+        }
+    }
+    expected
+}
+
+/// Width in percentage points of each deal-depth bucket.
+const DEPTH_BUCKET_WIDTH: f64 = 10.0;
+
+/// Result by how deep into the shoe each round started.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DepthStats {
+    pub buckets: Vec<DepthBucket>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DepthBucket {
+    /// Lower edge of the bucket as a percentage of the shoe already dealt.
+    pub depth: u32,
+    pub rounds: u32,
+    pub frequency: f64,
+    /// Mean result per round in units of the base bet.
+    pub advantage: f64,
+    #[serde(skip)]
+    total_units: f64,
+}
+
+impl DepthStats {
+    pub fn record(&mut self, result: &GameResult, num_decks: u8) {
+        let dealt = 1.0 - result.decks_remaining / num_decks.max(1) as f64;
+        let index = ((dealt * 100.0).max(0.0) / DEPTH_BUCKET_WIDTH) as usize;
+        while self.buckets.len() <= index {
+            self.buckets.push(DepthBucket {
+                depth: (self.buckets.len() as f64 * DEPTH_BUCKET_WIDTH) as u32,
+                rounds: 0,
+                frequency: 0.0,
+                advantage: 0.0,
+                total_units: 0.0,
+            });
+        }
+        let bucket = &mut self.buckets[index];
+        bucket.rounds += 1;
+        if result.base_bet > 0.0 {
+            bucket.total_units += result.winnings / result.base_bet;
+        }
+    }
+
+    pub fn finalize(&mut self) {
+        let rounds: u32 = self.buckets.iter().map(|b| b.rounds).sum();
+        for bucket in &mut self.buckets {
+            bucket.advantage = per_hand(bucket.total_units, bucket.rounds);
+            if rounds > 0 {
+                bucket.frequency = bucket.rounds as f64 / rounds as f64;
+            }
+        }
+    }
+}
+
+/// EV contribution of each index play, measured by replaying rounds where a
+/// deviation fired with the basic chart on the same cards.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviationStats {
+    pub rounds: u32,
+    pub rounds_with_deviation: u32,
+    /// Total gain over basic strategy in units of the base bet.
+    pub total_gain: f64,
+    /// Sorted by total gain, largest first.
+    pub plays: Vec<DeviationPlayStats>,
+    #[serde(skip)]
+    by_key: HashMap<String, usize>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviationPlayStats {
+    pub player: String,
+    pub dealer: String,
+    pub true_count: i32,
+    pub action: Action,
+    pub basic_action: Action,
+    pub fired: u32,
+    /// Share of rounds in which this play fired.
+    pub frequency: f64,
+    /// Units won per firing with the deviation and with the basic play.
+    pub ev_when_fired: f64,
+    pub basic_ev_when_fired: f64,
+    pub ev_gain_per_play: f64,
+    /// Gain per 100 rounds played, in units of the base bet.
+    pub ev_gain_per_100_rounds: f64,
+    #[serde(skip)]
+    total_units: f64,
+    #[serde(skip)]
+    basic_units: f64,
+}
+
+impl DeviationStats {
+    pub fn record_round(&mut self) {
+        self.rounds += 1;
+    }
+
+    /// `basic_winnings` is the same round replayed without deviations. When
+    /// several index plays fire in one round the result is shared between them.
+    pub fn record(&mut self, result: &GameResult, basic_winnings: f64) {
+        if result.index_plays.is_empty() || result.base_bet <= 0.0 {
+            return;
+        }
+        self.rounds_with_deviation += 1;
+        let share = result.index_plays.len() as f64;
+        let units = result.winnings / result.base_bet / share;
+        let basic_units = basic_winnings / result.base_bet / share;
+        self.total_gain += (units - basic_units) * share;
+        for play in &result.index_plays {
+            let entry = self.entry(play);
+            entry.fired += 1;
+            entry.total_units += units;
+            entry.basic_units += basic_units;
+        }
+    }
+
+    fn entry(&mut self, play: &IndexPlay) -> &mut DeviationPlayStats {
+        let key = format!("{}_{}_{}_{}", play.player, play.dealer, play.true_count, play.action.as_code());
+        let index = *self.by_key.entry(key).or_insert_with(|| {
+            self.plays.push(DeviationPlayStats {
+                player: play.player.clone(),
+                dealer: play.dealer.clone(),
+                true_count: play.true_count,
+                action: play.action,
+                basic_action: play.basic_action,
+                fired: 0,
+                frequency: 0.0,
+                ev_when_fired: 0.0,
+                basic_ev_when_fired: 0.0,
+                ev_gain_per_play: 0.0,
+                ev_gain_per_100_rounds: 0.0,
+                total_units: 0.0,
+                basic_units: 0.0,
+            });
+            self.plays.len() - 1
+        });
+        &mut self.plays[index]
+    }
+
+    pub fn finalize(&mut self) {
+        let rounds = self.rounds.max(1) as f64;
+        for play in &mut self.plays {
+            play.frequency = play.fired as f64 / rounds;
+            play.ev_when_fired = per_hand(play.total_units, play.fired);
+            play.basic_ev_when_fired = per_hand(play.basic_units, play.fired);
+            play.ev_gain_per_play = play.ev_when_fired - play.basic_ev_when_fired;
+            play.ev_gain_per_100_rounds = (play.total_units - play.basic_units) / rounds * 100.0;
+        }
+        self.plays
+            .sort_by(|a, b| b.ev_gain_per_100_rounds.total_cmp(&a.ev_gain_per_100_rounds));
+        self.by_key.clear();
+    }
+}
+
+/// How the spots played in the same round move together. Every spot sees the
+/// same dealer hand, so their results are positively correlated and a round's
+/// variance is more than the sum of the per-hand variances.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiSpotStats {
+    pub spots: u32,
+    pub rounds: u32,
+    /// Per-hand figures in units of the base bet.
+    pub mean_per_hand: f64,
+    pub variance_per_hand: f64,
+    /// Covariance and correlation between two spots of the same round.
+    pub covariance: f64,
+    pub correlation: f64,
+    /// Variance of a whole round: `n·var + n(n-1)·cov` for `n` spots.
+    pub variance_per_round: f64,
+    #[serde(skip)]
+    total_units: f64,
+    #[serde(skip)]
+    squared_units: f64,
+    #[serde(skip)]
+    cross_units: f64,
+}
+
+impl MultiSpotStats {
+    pub fn new(spots: u32) -> Self {
+        MultiSpotStats { spots, ..Default::default() }
+    }
+
+    /// `units` holds each spot's result for one round in base-bet units.
+    pub fn record(&mut self, units: &[f64]) {
+        self.rounds += 1;
+        for (i, a) in units.iter().enumerate() {
+            self.total_units += a;
+            self.squared_units += a * a;
+            for b in &units[i + 1..] {
+                self.cross_units += a * b;
+            }
+        }
+    }
+
+    pub fn finalize(&mut self) {
+        let spots = self.spots as f64;
+        let hands = self.rounds as f64 * spots;
+        let pairs = self.rounds as f64 * spots * (spots - 1.0) / 2.0;
+        if hands <= 0.0 {
+            return;
+        }
+        let mean = self.total_units / hands;
+        self.mean_per_hand = mean;
+        self.variance_per_hand = (self.squared_units / hands - mean * mean).max(0.0);
+        if pairs > 0.0 {
+            self.covariance = self.cross_units / pairs - mean * mean;
+        }
+        if self.variance_per_hand > 0.0 {
+            self.correlation = self.covariance / self.variance_per_hand;
+        }
+        self.variance_per_round =
+            spots * self.variance_per_hand + spots * (spots - 1.0) * self.covariance;
+    }
+}
+
+/// Even-money offers on a blackjack against a dealer ace. The hole card is
+/// known after the round, so both choices are priced exactly for every offer.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvenMoneyStats {
+    pub offered: u32,
+    pub taken: u32,
+    /// Offers per hand played.
+    pub frequency: f64,
+    /// Share of offers where the dealer had blackjack.
+    pub dealer_blackjack_rate: f64,
+    /// Units won by the decisions made, compared with always declining.
+    pub gain_vs_never: f64,
+    pub gain_vs_never_per_100_hands: f64,
+    /// Units always taking even money would have won over always declining.
+    pub always_gain_vs_never: f64,
+    #[serde(skip)]
+    hands: u32,
+    #[serde(skip)]
+    dealer_blackjacks: u32,
+}
+
+impl EvenMoneyStats {
+    /// `payout` is what a declined blackjack wins when the dealer has none.
+    pub fn record(&mut self, result: &GameResult, payout: f64) {
+        self.hands += 1;
+        let Some(taken) = result.even_money else {
+            return;
+        };
+        self.offered += 1;
+        let dealer_blackjack = result.dealer_cards.len() == 2
+            && result.dealer_cards.iter().map(|c| c.value as u32).sum::<u32>() == 21;
+        let declined_units = if dealer_blackjack {
+            self.dealer_blackjacks += 1;
+            0.0
+        } else {
+            payout
+        };
+        let gain = 1.0 - declined_units;
+        self.always_gain_vs_never += gain;
+        if taken {
+            self.taken += 1;
+            self.gain_vs_never += gain;
+        }
+    }
+
+    pub fn finalize(&mut self) {
+        self.frequency = per_hand(self.offered as f64, self.hands);
+        self.dealer_blackjack_rate = per_hand(self.dealer_blackjacks as f64, self.offered);
+        self.gain_vs_never_per_100_hands = per_hand(self.gain_vs_never, self.hands) * 100.0;
+    }
+}
+
+/// Insurance offers on hands other than a blackjack against a dealer ace.
+/// Like even money, every offer is priced both ways once the hole card is
+/// known; stakes and winnings are in units of the main bet.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InsuranceStats {
+    pub offered: u32,
+    pub taken: u32,
+    /// Offers per hand played.
+    pub frequency: f64,
+    /// Share of offers where the dealer had blackjack; insurance breaks even
+    /// at one in three.
+    pub dealer_blackjack_rate: f64,
+    pub total_staked: f64,
+    /// Net insurance result of the decisions made, i.e. the gain compared
+    /// with always declining.
+    pub net_winnings: f64,
+    pub net_winnings_per_100_hands: f64,
+    /// Units always insuring would have won over always declining.
+    pub always_gain_vs_never: f64,
+    #[serde(skip)]
+    hands: u32,
+    #[serde(skip)]
+    dealer_blackjacks: u32,
+}
+
+impl InsuranceStats {
+    pub fn record(&mut self, result: &GameResult) {
+        self.hands += 1;
+        let Some(taken) = result.insurance else {
+            return;
+        };
+        self.offered += 1;
+        let dealer_blackjack = result.dealer_cards.len() == 2
+            && result.dealer_cards.iter().map(|c| c.value as u32).sum::<u32>() == 21;
+        let units = if dealer_blackjack {
+            self.dealer_blackjacks += 1;
+            1.0
+        } else {
+            -0.5
+        };
+        self.always_gain_vs_never += units;
+        if taken {
+            self.taken += 1;
+            self.total_staked += 0.5;
+            self.net_winnings += units;
+        }
+    }
+
+    pub fn finalize(&mut self) {
+        self.frequency = per_hand(self.offered as f64, self.hands);
+        self.dealer_blackjack_rate = per_hand(self.dealer_blackjacks as f64, self.offered);
+        self.net_winnings_per_100_hands = per_hand(self.net_winnings, self.hands) * 100.0;
+    }
+}
+
+/// Hands paid a hand bonus, in units of the main bet.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BonusStats {
+    pub paid: u32,
+    /// Bonus hands per hand played.
+    pub frequency: f64,
+    /// Won on bonus hands, and the part of that above an even-money win.
+    pub winnings: f64,
+    pub extra_over_even_money: f64,
+    pub extra_per_100_hands: f64,
+    #[serde(skip)]
+    hands: u32,
+}
+
+impl BonusStats {
+    pub fn record(&mut self, result: &GameResult) {
+        for hand in &result.hands {
+            self.hands += 1;
+            if hand.bonus {
+                let units = hand.winnings / result.base_bet;
+                self.paid += 1;
+                self.winnings += units;
+                self.extra_over_even_money += units - hand.bet;
+            }
+        }
+    }
+
+    pub fn finalize(&mut self) {
+        self.frequency = per_hand(self.paid as f64, self.hands);
+        self.extra_per_100_hands = per_hand(self.extra_over_even_money, self.hands) * 100.0;
+    }
+}
+
+/// Results totalled per shoe, the unit session swings are felt in. Only
+/// shoes that were reshuffled count; the one in play when the run stops is
+/// left out.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShoeStats {
+    pub shoes: u32,
+    pub mean_rounds_per_shoe: f64,
+    /// Per-shoe figures in units of the base bet.
+    pub ev_per_shoe: f64,
+    pub variance_per_shoe: f64,
+    pub std_dev_per_shoe: f64,
+    pub best_shoe: f64,
+    pub worst_shoe: f64,
+    #[serde(skip)]
+    shoe_index: u32,
+    #[serde(skip)]
+    shoe_rounds: u32,
+    #[serde(skip)]
+    shoe_units: f64,
+    #[serde(skip)]
+    rounds: u32,
+    #[serde(skip)]
+    total_units: f64,
+    #[serde(skip)]
+    squared_units: f64,
+}
+
+impl ShoeStats {
+    /// Counts a round dealt from shoe `shoe_index`, closing the previous shoe
+    /// when it has changed.
+    pub fn start_round(&mut self, shoe_index: u32) {
+        if shoe_index != self.shoe_index {
+            if self.shoe_rounds > 0 {
+                self.close_shoe();
+            }
+            self.shoe_index = shoe_index;
+        }
+        self.shoe_rounds += 1;
+    }
+
+    /// Adds a round's net result, in base-bet units, to the current shoe.
+    pub fn record(&mut self, units: f64) {
+        self.shoe_units += units;
+    }
+
+    fn close_shoe(&mut self) {
+        let units = self.shoe_units;
+        if self.shoes == 0 || units > self.best_shoe {
+            self.best_shoe = units;
+        }
+        if self.shoes == 0 || units < self.worst_shoe {
+            self.worst_shoe = units;
+        }
+        self.shoes += 1;
+        self.rounds += self.shoe_rounds;
+        self.total_units += units;
+        self.squared_units += units * units;
+        self.shoe_rounds = 0;
+        self.shoe_units = 0.0;
+    }
+
+    pub fn finalize(&mut self) {
+        if self.shoes == 0 {
+            return;
+        }
+        let shoes = self.shoes as f64;
+        self.mean_rounds_per_shoe = self.rounds as f64 / shoes;
+        self.ev_per_shoe = self.total_units / shoes;
+        self.variance_per_shoe = (self.squared_units / shoes - self.ev_per_shoe * self.ev_per_shoe).max(0.0);
+        self.std_dev_per_shoe = self.variance_per_shoe.sqrt();
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CellUsage {
+    pub cell: ChartCell,
+    pub decisions: u32,
+}
+
+/// How often each strategy entry decided a hand, to reveal chart regions that
+/// never come up and gaps that fell through to `default_action`.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StrategyCoverage {
+    pub decisions: u32,
+    /// Entries in the chart and count tables.
+    pub chart_cells: u32,
+    /// Entries read by at least one decision.
+    pub cells_used: u32,
+    pub used: Vec<CellUsage>,
+    /// Entries no decision ever read.
+    pub unused: Vec<ChartCell>,
+    /// Hands no entry covered, decided by `default_action` instead.
+    pub fallbacks: Vec<CellUsage>,
+    #[serde(skip)]
+    counts: HashMap<ChartCell, u32>,
+}
+
+impl StrategyCoverage {
+    pub fn record(&mut self, result: &GameResult) {
+        for cell in &result.chart_cells {
+            self.decisions += 1;
+            *self.counts.entry(cell.clone()).or_default() += 1;
+        }
+    }
+
+    pub fn finalize(&mut self, strategy: &Strategy) {
+        let cells = strategy.cells();
+        self.chart_cells = cells.len() as u32;
+        self.unused = cells.into_iter().filter(|cell| !self.counts.contains_key(cell)).collect();
+        let mut counts: Vec<(ChartCell, u32)> = self.counts.drain().collect();
+        counts.sort();
+        for (cell, decisions) in counts {
+            let usage = CellUsage { cell, decisions };
+            if usage.cell.table == ChartSource::Default {
+                self.fallbacks.push(usage);
+            } else {
+                self.used.push(usage);
+            }
+        }
+        self.cells_used = self.used.len() as u32;
+    }
+}
+
+/// Hands the strategy had no entry for, which `default_action` decided.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FallbackWarning {
+    pub message: String,
+    pub cell: ChartCell,
+    pub decisions: u32,
+}
+
+pub fn fallback_message(cell: &ChartCell) -> String {
+    format!("strategy has no entry for {} vs dealer {}", cell.row, cell.dealer)
+}
+
+pub fn fallback_warnings(counts: HashMap<ChartCell, u32>) -> Vec<FallbackWarning> {
+    let mut counts: Vec<(ChartCell, u32)> = counts.into_iter().collect();
+    counts.sort();
+    counts
+        .into_iter()
+        .map(|(cell, decisions)| FallbackWarning {
+            message: format!("{}; {decisions} decisions used the default action", fallback_message(&cell)),
+            cell,
+            decisions,
+        })
+        .collect()
+}
+
+fn default_round_seconds() -> f64 {
+    8.6
+}
+
+fn default_seat_seconds() -> f64 {
+    8.6
+}
+
+/// Dealing speed as a function of how many seats are in play. A round takes a
+/// fixed overhead plus a slice per occupied seat; the defaults give about 209
+/// rounds an hour heads-up and 52 at a full seven-seat table.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TableSpeedInput {
+    /// Seats taken by other players; the player's own spots are added to these.
+    #[serde(default)]
+    pub other_players: u8,
+    #[serde(default = "default_round_seconds")]
+    pub round_seconds: f64,
+    #[serde(default = "default_seat_seconds")]
+    pub seat_seconds: f64,
+}
+
+impl Default for TableSpeedInput {
+    fn default() -> Self {
+        TableSpeedInput {
+            other_players: 0,
+            round_seconds: default_round_seconds(),
+            seat_seconds: default_seat_seconds(),
+        }
+    }
+}
+
+impl TableSpeedInput {
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.round_seconds.is_finite() || self.round_seconds < 0.0 {
+            return Err("table round_seconds must be zero or more".to_string());
+        }
+        if !self.seat_seconds.is_finite() || self.seat_seconds < 0.0 {
+            return Err("table seat_seconds must be zero or more".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn rounds_per_hour(&self, seats: u32) -> f64 {
+        3600.0 / (self.round_seconds + self.seat_seconds * seats as f64).max(1.0)
+    }
+
+    /// Table hours for rounds bet on `spots` spots plus rounds sat out. A
+    /// round sat out is back-counted from behind the table, so it is dealt at
+    /// the other players' pace.
+    pub fn hours(&self, spots: u32, rounds_played: u32, rounds_observed: u32) -> f64 {
+        let others = self.other_players as u32;
+        rounds_played as f64 / self.rounds_per_hour(others + spots)
+            + rounds_observed as f64 / self.rounds_per_hour(others)
+    }
+}
+
+/// Session length is compared against summed round times, so allow for
+/// rounding in the sum.
+const SESSION_HOURS_TOLERANCE: f64 = 1e-9;
+
+/// Table time the run is cut into: how fast the table deals and hours per
+/// session. Playing more spots slows the rounds bet; rounds sat out go at
+/// the other players' pace, so a wonging session deals more rounds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionModel {
+    #[serde(default)]
+    pub table_speed: TableSpeedInput,
+    pub hours_per_session: f64,
+    /// Sessions to play; the run then lasts exactly that many sessions.
+    #[serde(default)]
+    pub sessions: Option<u32>,
+}
+
+impl SessionModel {
+    pub fn validate(&self) -> Result<(), String> {
+        self.table_speed.validate()?;
+        if !self.hours_per_session.is_finite() || self.hours_per_session <= 0.0 {
+            return Err("session hours_per_session must be positive".to_string());
+        }
+        if self.sessions == Some(0) {
+            return Err("session count must be at least 1".to_string());
+        }
+        Ok(())
+    }
+
+    /// Most rounds a session can deal, reached when every round is sat out.
+    pub fn max_rounds_per_session(&self) -> u32 {
+        let sat_out_per_hour = self.table_speed.rounds_per_hour(self.table_speed.other_players as u32);
+        (sat_out_per_hour * self.hours_per_session).ceil().max(1.0) as u32
+    }
+}
+
+/// Results per hour and per session. Only whole sessions count towards the
+/// session figures.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionStats {
+    /// Rounds dealt, sat out or not, in an average whole session.
+    pub rounds_per_session: f64,
+    pub sessions: u32,
+    pub hourly_ev: f64,
+    pub hourly_std_dev: f64,
+    pub mean_session: f64,
+    pub std_dev_per_session: f64,
+    pub losing_sessions: u32,
+    pub losing_session_probability: f64,
+    pub worst_session: f64,
+    pub p5_session: f64,
+    pub p25_session: f64,
+    pub median_session: f64,
+    pub p75_session: f64,
+    pub p95_session: f64,
+    pub best_session: f64,
+}
+
+pub struct SessionRecorder {
+    table_speed: TableSpeedInput,
+    spots: u32,
+    hours_per_session: f64,
+    target_sessions: Option<u32>,
+    rounds: u32,
+    hours: f64,
+    total: f64,
+    squared: f64,
+    session_rounds: u32,
+    session_hours: f64,
+    session_total: f64,
+    sessions: Vec<f64>,
+}
+
+impl SessionRecorder {
+    pub fn new(model: &SessionModel, spots: u32) -> Self {
+        SessionRecorder {
+            table_speed: model.table_speed,
+            spots,
+            hours_per_session: model.hours_per_session,
+            target_sessions: model.sessions,
+            rounds: 0,
+            hours: 0.0,
+            total: 0.0,
+            squared: 0.0,
+            session_rounds: 0,
+            session_hours: 0.0,
+            session_total: 0.0,
+            sessions: Vec::new(),
+        }
+    }
+
+    /// Adds a dealt round's net result; zero for a round sat out.
+    pub fn record(&mut self, winnings: f64, sat_out: bool) {
+        let hours = self.table_speed.hours(self.spots, u32::from(!sat_out), u32::from(sat_out));
+        self.rounds += 1;
+        self.hours += hours;
+        self.total += winnings;
+        self.squared += winnings * winnings;
+        self.session_rounds += 1;
+        self.session_hours += hours;
+        self.session_total += winnings;
+        if self.session_hours >= self.hours_per_session - SESSION_HOURS_TOLERANCE {
+            self.sessions.push(self.session_total);
+            self.session_rounds = 0;
+            self.session_hours = 0.0;
+            self.session_total = 0.0;
+        }
+    }
+
+    pub fn completed_sessions(&self) -> u32 {
+        self.sessions.len() as u32
+    }
+
+    /// The session count asked for has been played.
+    pub fn is_complete(&self) -> bool {
+        self.target_sessions.is_some_and(|target| self.completed_sessions() >= target)
+    }
+
+    pub fn finish(mut self) -> SessionStats {
+        let rounds = self.rounds.max(1) as f64;
+        let mean = self.total / rounds;
+        let variance = (self.squared / rounds - mean * mean).max(0.0);
+        let rounds_per_hour = if self.hours > 0.0 { self.rounds as f64 / self.hours } else { 0.0 };
+        self.sessions.sort_by(f64::total_cmp);
+        let sessions = &self.sessions;
+        let count = sessions.len().max(1) as f64;
+        let mean_session = sessions.iter().sum::<f64>() / count;
+        let session_variance = sessions.iter().map(|s| (s - mean_session).powi(2)).sum::<f64>() / count;
+        let losing_sessions = sessions.iter().filter(|&&s| s < 0.0).count() as u32;
+        let at = |q: f64| if sessions.is_empty() { 0.0 } else { quantile(sessions, q) };
+        SessionStats {
+            rounds_per_session: if sessions.is_empty() {
+                0.0
+            } else {
+                (self.rounds - self.session_rounds) as f64 / sessions.len() as f64
+            },
+            sessions: sessions.len() as u32,
+            hourly_ev: mean * rounds_per_hour,
+            hourly_std_dev: (variance * rounds_per_hour).sqrt(),
+            mean_session,
+            std_dev_per_session: session_variance.sqrt(),
+            losing_sessions,
+            losing_session_probability: losing_sessions as f64 / count,
+            worst_session: at(0.0),
+            p5_session: at(0.05),
+            p25_session: at(0.25),
+            median_session: at(0.5),
+            p75_session: at(0.75),
+            p95_session: at(0.95),
+            best_session: at(1.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rounds_sat_out_go_at_the_other_players_pace() {
+        // One other player: a round sat out takes 20s, a round bet takes 30s.
+        let model = SessionModel {
+            table_speed: TableSpeedInput {
+                other_players: 1,
+                round_seconds: 10.0,
+                seat_seconds: 10.0,
+            },
+            hours_per_session: 1.0 / 60.0,
+            sessions: Some(2),
+        };
+        let mut recorder = SessionRecorder::new(&model, 1);
+        for _ in 0..3 {
+            recorder.record(0.0, true);
+        }
+        assert_eq!(recorder.completed_sessions(), 1);
+        recorder.record(10.0, false);
+        assert!(!recorder.is_complete());
+        recorder.record(10.0, false);
+        assert!(recorder.is_complete());
+
+        let stats = recorder.finish();
+        assert_eq!(stats.rounds_per_session, 2.5);
+        assert!((stats.hourly_ev - 600.0).abs() < 1e-6);
+        assert_eq!(stats.worst_session, 0.0);
+        assert_eq!(stats.best_session, 20.0);
+    }
+}
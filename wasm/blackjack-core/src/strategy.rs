@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct StrategyInput {
     #[serde(default)]
     pub count_based: Option<bool>,
@@ -14,6 +14,27 @@ pub struct StrategyInput {
     pub soft_by_count: serde_json::Value,
     #[serde(default)]
     pub pairs_by_count: serde_json::Value,
+    #[serde(default)]
+    pub even_money: EvenMoneyPolicy,
+    /// When to insure a hand other than a blackjack against a dealer ace.
+    #[serde(default)]
+    pub insurance: EvenMoneyPolicy,
+    /// Doubled hands to give up under double down rescue, keyed by total
+    /// ("16", "S18") and dealer card, e.g. `{"16": {"10": "R"}}`.
+    #[serde(default)]
+    pub rescue: serde_json::Value,
+}
+
+/// When to take even money on a blackjack against a dealer ace, or insurance
+/// on any other hand.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EvenMoneyPolicy {
+    #[default]
+    Never,
+    Always,
+    /// Take it at this true count or higher.
+    AtTrueCount(i32),
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Serialize)]
@@ -22,15 +43,28 @@ pub enum Action {
     Hit,
     Stand,
     Double,
+    /// Power Blackjack: double down for three or four times the bet.
+    Triple,
+    Quadruple,
     Split,
+    Surrender,
+    /// Zappit: exchange a hard 15-17 on the first two cards for two new cards.
+    Zap,
 }
 
+/// Codes a player's own action may be given as.
+pub const ACTION_CODES: [&str; 8] = ["H", "S", "D", "D3", "D4", "P", "R", "Z"];
+
 impl Action {
     pub fn from_code(code: &str) -> Action {
         match code {
             "S" => Action::Stand,
-            "D" => Action::Double,
+            "D" | "Dh" | "Ds" => Action::Double,
+            "D3" => Action::Triple,
+            "D4" => Action::Quadruple,
             "P" => Action::Split,
+            "R" | "Rh" | "Rs" => Action::Surrender,
+            "Z" => Action::Zap,
             _ => Action::Hit,
         }
     }
@@ -40,14 +74,71 @@ impl Action {
             Action::Hit => "H",
             Action::Stand => "S",
             Action::Double => "D",
+            Action::Triple => "D3",
+            Action::Quadruple => "D4",
             Action::Split => "P",
+            Action::Surrender => "R",
+            Action::Zap => "Z",
+        }
+    }
+
+    /// Times the original bet a double down leaves in play.
+    pub fn double_multiplier(self) -> Option<u8> {
+        match self {
+            Action::Double => Some(2),
+            Action::Triple => Some(3),
+            Action::Quadruple => Some(4),
+            _ => None,
+        }
+    }
+
+    pub fn double_of(multiplier: u8) -> Action {
+        match multiplier {
+            3 => Action::Triple,
+            4 => Action::Quadruple,
+            _ => Action::Double,
         }
     }
 }
 
+/// Strategy table a decision was read from. `Default` means no table had an
+/// entry and `default_action` decided.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChartSource {
+    Hard,
+    Soft,
+    Pairs,
+    HardByCount,
+    SoftByCount,
+    PairsByCount,
+    Default,
+}
+
+/// A decision, the table it was read from and whether the chart wanted a
+/// double the hand could not make.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Decision {
+    pub action: Action,
+    pub source: ChartSource,
+    pub double_blocked: bool,
+}
+
+/// One strategy table entry, as keyed after normalization.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChartCell {
+    pub table: ChartSource,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<String>,
+    pub row: String,
+    pub dealer: String,
+}
+
 type StrategyTable = HashMap<String, HashMap<String, String>>;
 type StrategyCountTable = HashMap<String, StrategyTable>;
 
+#[derive(Clone)]
 pub struct Strategy {
     count_based: bool,
     hard: StrategyTable,
@@ -56,29 +147,64 @@ pub struct Strategy {
     hard_by_count: StrategyCountTable,
     soft_by_count: StrategyCountTable,
     pairs_by_count: StrategyCountTable,
+    even_money: EvenMoneyPolicy,
+    insurance: EvenMoneyPolicy,
+    rescue: StrategyTable,
 }
 
 impl Strategy {
     pub fn from_input(input: StrategyInput) -> Result<Self, String> {
         Ok(Strategy {
             count_based: input.count_based.unwrap_or(false),
-            hard: value_to_table(input.hard)?,
-            soft: value_to_table(input.soft)?,
-            pairs: value_to_table(input.pairs)?,
-            hard_by_count: value_to_count_table(input.hard_by_count)?,
-            soft_by_count: value_to_count_table(input.soft_by_count)?,
-            pairs_by_count: value_to_count_table(input.pairs_by_count)?,
+            hard: value_to_table(input.hard, TableKind::Hard)?,
+            soft: value_to_table(input.soft, TableKind::Soft)?,
+            pairs: value_to_table(input.pairs, TableKind::Pairs)?,
+            hard_by_count: value_to_count_table(input.hard_by_count, TableKind::Hard)?,
+            soft_by_count: value_to_count_table(input.soft_by_count, TableKind::Soft)?,
+            pairs_by_count: value_to_count_table(input.pairs_by_count, TableKind::Pairs)?,
+            even_money: input.even_money,
+            insurance: input.insurance,
+            rescue: if input.rescue.is_null() {
+                StrategyTable::new()
+            } else {
+                value_to_table(input.rescue, TableKind::Hard)?
+            },
         })
     }
 
+    /// Whether the rescue chart gives up a doubled hand of `player_label`.
+    pub fn rescues(&self, player_label: &str, dealer: &str) -> bool {
+        self.rescue
+            .get(player_label)
+            .and_then(|row| row.get(dealer_column(dealer)))
+            .is_some_and(|code| Action::from_code(code) == Action::Surrender)
+    }
+
     pub fn decide_action(
         &self,
         player_label: &str,
         dealer: &str,
         can_double: bool,
         can_split: bool,
+        can_surrender: bool,
         count: i32,
     ) -> Action {
+        self.decide_action_traced(player_label, dealer, can_double, can_split, can_surrender, count)
+            .action
+    }
+
+    /// `decide_action` that also says which table the action came from and
+    /// whether a double was blocked.
+    pub fn decide_action_traced(
+        &self,
+        player_label: &str,
+        dealer: &str,
+        can_double: bool,
+        can_split: bool,
+        can_surrender: bool,
+        count: i32,
+    ) -> Decision {
+        let dealer = dealer_column(dealer);
         let pair_key = if can_split {
             pair_key_from_label(player_label)
         } else {
@@ -86,30 +212,152 @@ impl Strategy {
         };
         if self.count_based && count != 0 {
             let count_key = count.to_string();
-            if let Some(action) = self.lookup_count_action(
+            if let Some(found) = self.lookup_count_action(
                 &count_key,
                 player_label,
                 pair_key.as_deref(),
                 dealer,
                 can_double,
+                can_surrender,
             ) {
-                return action;
+                return found;
+            }
+        }
+        self.chart_action(player_label, pair_key.as_deref(), dealer, can_double, can_surrender)
+    }
+
+    /// The table entry a decision traced to `source` was read from.
+    pub fn chart_cell(source: ChartSource, player_label: &str, dealer: &str, count: i32) -> ChartCell {
+        let row = match source {
+            ChartSource::Pairs | ChartSource::PairsByCount => {
+                pair_key_from_label(player_label).unwrap_or_else(|| player_label.to_string())
             }
+            ChartSource::Soft | ChartSource::SoftByCount => soft_table_key(player_label).to_string(),
+            ChartSource::Hard | ChartSource::HardByCount | ChartSource::Default => player_label.to_string(),
+        };
+        let by_count = matches!(
+            source,
+            ChartSource::HardByCount | ChartSource::SoftByCount | ChartSource::PairsByCount
+        );
+        ChartCell {
+            table: source,
+            count: by_count.then(|| count.to_string()),
+            row,
+            dealer: dealer_column(dealer).to_string(),
+        }
+    }
+
+    /// Every entry of the chart and count tables.
+    pub fn cells(&self) -> Vec<ChartCell> {
+        let mut cells = Vec::new();
+        let charts = [
+            (ChartSource::Hard, &self.hard),
+            (ChartSource::Soft, &self.soft),
+            (ChartSource::Pairs, &self.pairs),
+        ];
+        let count_tables = [
+            (ChartSource::HardByCount, &self.hard_by_count),
+            (ChartSource::SoftByCount, &self.soft_by_count),
+            (ChartSource::PairsByCount, &self.pairs_by_count),
+        ];
+        let tables = charts.into_iter().map(|(source, table)| (source, None, table)).chain(
+            count_tables.into_iter().flat_map(|(source, by_count)| {
+                by_count.iter().map(move |(count, table)| (source, Some(count), table))
+            }),
+        );
+        for (source, count, table) in tables {
+            for (row, columns) in table {
+                for dealer in columns.keys() {
+                    cells.push(ChartCell {
+                        table: source,
+                        count: count.cloned(),
+                        row: row.clone(),
+                        dealer: dealer.clone(),
+                    });
+                }
+            }
+        }
+        cells.sort();
+        cells
+    }
+
+    /// The count-free chart action, used to tell when an index play fired.
+    pub fn basic_action(
+        &self,
+        player_label: &str,
+        dealer: &str,
+        can_double: bool,
+        can_split: bool,
+        can_surrender: bool,
+    ) -> Action {
+        let dealer = dealer_column(dealer);
+        let pair_key = if can_split {
+            pair_key_from_label(player_label)
+        } else {
+            None
+        };
+        self.chart_action(player_label, pair_key.as_deref(), dealer, can_double, can_surrender)
+            .action
+    }
+
+    /// Whether to take even money at `count`. A count threshold only applies
+    /// when the strategy is count based, like the count tables.
+    pub fn takes_even_money(&self, count: i32) -> bool {
+        self.follows(self.even_money, count)
+    }
+
+    /// Whether to insure a non-blackjack hand at `count`, on the same terms.
+    pub fn takes_insurance(&self, count: i32) -> bool {
+        self.follows(self.insurance, count)
+    }
+
+    fn follows(&self, policy: EvenMoneyPolicy, count: i32) -> bool {
+        match policy {
+            EvenMoneyPolicy::Never => false,
+            EvenMoneyPolicy::Always => true,
+            EvenMoneyPolicy::AtTrueCount(threshold) => self.count_based && count >= threshold,
         }
+    }
+
+    /// Whether any hard row has a `dealer` column, e.g. a Double Exposure
+    /// chart keyed by dealer totals.
+    pub fn has_dealer_column(&self, dealer: &str) -> bool {
+        self.hard.values().any(|row| row.contains_key(dealer))
+    }
+
+    /// Copy of this strategy with the count tables switched off.
+    pub fn without_deviations(&self) -> Strategy {
+        Strategy {
+            count_based: false,
+            ..self.clone()
+        }
+    }
 
-        if let Some(key) = pair_key.as_deref() {
-            if let Some(action) = self.lookup_pair(key, dealer, can_double) {
-                return action;
+    fn chart_action(
+        &self,
+        player_label: &str,
+        pair_key: Option<&str>,
+        dealer: &str,
+        can_double: bool,
+        can_surrender: bool,
+    ) -> Decision {
+        if let Some(key) = pair_key {
+            if let Some(resolved) = self.lookup_pair(key, dealer, can_double, can_surrender) {
+                return resolved.traced(ChartSource::Pairs);
             }
         }
 
-        let soft_or_hard_result = self.lookup_soft_or_hard(player_label, dealer, can_double);
-        if let Some(action) = soft_or_hard_result {
-            return action;
+        let soft_or_hard_result = self.lookup_soft_or_hard(player_label, dealer, can_double, can_surrender);
+        if let Some(found) = soft_or_hard_result {
+            return found;
         }
         
         // If lookup failed, use default
-        default_action(player_label)
+        let resolved = Resolved {
+            action: default_action(player_label),
+            double_blocked: false,
+        };
+        resolved.traced(ChartSource::Default)
     }
 
     fn lookup_count_action(
@@ -119,12 +367,13 @@ impl Strategy {
         pair_key: Option<&str>,
         dealer: &str,
         can_double: bool,
-    ) -> Option<Action> {
+        can_surrender: bool,
+    ) -> Option<Decision> {
         if let Some(key) = pair_key {
-            if let Some(action) =
-                lookup_action(&self.pairs_by_count, count_key, key, dealer, can_double)
+            if let Some(resolved) =
+                lookup_action(&self.pairs_by_count, count_key, key, dealer, can_double, can_surrender)
             {
-                return Some(action);
+                return Some(resolved.traced(ChartSource::PairsByCount));
             }
         }
 
@@ -134,7 +383,9 @@ impl Strategy {
             soft_table_key(player_label),
             dealer,
             can_double,
+            can_surrender,
         )
+        .map(|resolved| resolved.traced(ChartSource::SoftByCount))
         .or_else(|| {
             lookup_action(
                 &self.hard_by_count,
@@ -142,23 +393,32 @@ impl Strategy {
                 player_label,
                 dealer,
                 can_double,
+                can_surrender,
             )
+            .map(|resolved| resolved.traced(ChartSource::HardByCount))
         })
     }
 
-    fn lookup_pair(&self, key: &str, dealer: &str, can_double: bool) -> Option<Action> {
-        lookup_action_map(&self.pairs, key, dealer, can_double)
+    fn lookup_pair(&self, key: &str, dealer: &str, can_double: bool, can_surrender: bool) -> Option<Resolved> {
+        lookup_action_map(&self.pairs, key, dealer, can_double, can_surrender)
     }
 
-    fn lookup_soft_or_hard(&self, player_label: &str, dealer: &str, can_double: bool) -> Option<Action> {
+    fn lookup_soft_or_hard(
+        &self,
+        player_label: &str,
+        dealer: &str,
+        can_double: bool,
+        can_surrender: bool,
+    ) -> Option<Decision> {
         if player_label.starts_with('S') {
             let key = soft_table_key(player_label);
-            let soft_result = lookup_action_map(&self.soft, key, dealer, can_double);
-            if soft_result.is_some() {
-                return soft_result;
+            let soft_result = lookup_action_map(&self.soft, key, dealer, can_double, can_surrender);
+            if let Some(resolved) = soft_result {
+                return Some(resolved.traced(ChartSource::Soft));
             }
         }
-        lookup_action_map(&self.hard, player_label, dealer, can_double)
+        lookup_action_map(&self.hard, player_label, dealer, can_double, can_surrender)
+            .map(|resolved| resolved.traced(ChartSource::Hard))
     }
 }
 
@@ -167,16 +427,13 @@ fn lookup_action_map(
     key: &str,
     dealer: &str,
     can_double: bool,
-) -> Option<Action> {
+    can_surrender: bool,
+) -> Option<Resolved> {
     // Try to get the row for this player total
     let row = table.get(key)?;
     // Try to get the action for this dealer card
     let code = row.get(dealer)?;
-    let mut action = Action::from_code(code);
-    if matches!(action, Action::Double) && !can_double {
-        action = Action::Hit;
-    }
-    Some(action)
+    Some(resolve_code(code, can_double, can_surrender))
 }
 
 fn lookup_action(
@@ -185,21 +442,48 @@ fn lookup_action(
     label: &str,
     dealer: &str,
     can_double: bool,
-) -> Option<Action> {
+    can_surrender: bool,
+) -> Option<Resolved> {
     count_table
         .get(count_key)
         .and_then(|table| table.get(label))
         .and_then(|row| row.get(dealer))
-        .map(|code| {
-            let mut action = Action::from_code(code);
-            if matches!(action, Action::Double) && !can_double {
-                action = Action::Hit;
-            }
-            action
-        })
+        .map(|code| resolve_code(code, can_double, can_surrender))
+}
+
+/// A chart entry resolved against what the hand allows.
+struct Resolved {
+    action: Action,
+    double_blocked: bool,
+}
+
+impl Resolved {
+    fn traced(self, source: ChartSource) -> Decision {
+        Decision {
+            action: self.action,
+            source,
+            double_blocked: self.double_blocked,
+        }
+    }
 }
 
-fn soft_table_key<'a>(label: &'a str) -> &'a str {
+/// Chart code to action when the hand allows it. A double that is not allowed
+/// becomes a stand for "Ds" and a hit otherwise, and is reported as blocked;
+/// a surrender becomes a stand for "Rs" and a hit otherwise.
+fn resolve_code(code: &str, can_double: bool, can_surrender: bool) -> Resolved {
+    let wanted = Action::from_code(code);
+    let double_blocked = wanted.double_multiplier().is_some() && !can_double;
+    let action = match wanted {
+        _ if double_blocked && code == "Ds" => Action::Stand,
+        _ if double_blocked => Action::Hit,
+        Action::Surrender if !can_surrender && code == "Rs" => Action::Stand,
+        Action::Surrender if !can_surrender => Action::Hit,
+        action => action,
+    };
+    Resolved { action, double_blocked }
+}
+
+fn soft_table_key(label: &str) -> &str {
     label.strip_prefix('S').unwrap_or(label)
 }
 
@@ -224,32 +508,118 @@ fn pair_key_from_label(label: &str) -> Option<String> {
     card_value_from_rank(first).map(|value| value.to_string())
 }
 
-fn value_to_table(value: serde_json::Value) -> Result<StrategyTable, String> {
-    let mut table = HashMap::new();
+#[derive(Clone, Copy)]
+enum TableKind {
+    Hard,
+    Soft,
+    Pairs,
+}
+
+/// Card value of a rank token as charts write it ("A", "T", "K", "7"), if valid.
+fn rank_value(token: &str) -> Option<u8> {
+    let value = match token {
+        "A" => 11,
+        "T" | "J" | "Q" | "K" => 10,
+        _ => token.parse::<u8>().ok()?,
+    };
+    (2..=11).contains(&value).then_some(value)
+}
+
+/// Values of a two-card key such as "A,7", "A-7", "A7", "T,T" or "1010".
+fn two_card_values(key: &str) -> Option<(u8, u8)> {
+    let parts: Vec<&str> = key.split([',', '-', '/', ' ']).filter(|part| !part.is_empty()).collect();
+    match parts[..] {
+        [first, second] => Some((rank_value(first)?, rank_value(second)?)),
+        [joined] => (1..joined.len())
+            .filter(|&at| joined.is_char_boundary(at))
+            .find_map(|at| Some((rank_value(&joined[..at])?, rank_value(&joined[at..])?))),
+        _ => None,
+    }
+}
+
+/// Maps the row key variants other charts use onto the ones lookups expect:
+/// hard and soft rows by total ("H16", "S18", "A,7" -> "16", "18", "18") and
+/// pair rows by card value ("A,A", "AA", "A-A" -> "11"; "T,T", "J" -> "10").
+/// Keys that match no known form are kept as written.
+fn normalize_row_key(key: &str, kind: TableKind) -> String {
+    let upper = key.trim().to_ascii_uppercase();
+    let normalized = match kind {
+        TableKind::Hard => upper
+            .strip_prefix('H')
+            .filter(|total| total.parse::<u8>().is_ok())
+            .map(str::to_string),
+        TableKind::Soft => match upper.strip_prefix('S').filter(|total| total.parse::<u8>().is_ok()) {
+            Some(total) => Some(total.to_string()),
+            None => two_card_values(&upper).and_then(|values| match values {
+                (11, 11) => Some("12".to_string()),
+                (11, other) | (other, 11) => Some((11 + other).to_string()),
+                _ => None,
+            }),
+        },
+        TableKind::Pairs => rank_value(&upper)
+            .or_else(|| two_card_values(&upper).and_then(|(a, b)| (a == b).then_some(a)))
+            .map(|value| value.to_string()),
+    };
+    normalized.unwrap_or_else(|| key.to_string())
+}
+
+/// Column a dealer card is looked up under, so callers may pass the rank
+/// itself: tens and face cards share "10".
+fn dealer_column(dealer: &str) -> &str {
+    match dealer {
+        "T" | "J" | "Q" | "K" => "10",
+        "11" => "A",
+        other => other,
+    }
+}
+
+/// Dealer columns as lookups expect them: "2"-"10" and "A", with "T", "J",
+/// "Q" and "K" read as "10". Unknown columns are kept as written.
+fn normalize_dealer_key(key: &str) -> String {
+    match rank_value(&key.trim().to_ascii_uppercase()) {
+        Some(11) => "A".to_string(),
+        Some(value) => value.to_string(),
+        None => key.to_string(),
+    }
+}
+
+fn value_to_table(value: serde_json::Value, kind: TableKind) -> Result<StrategyTable, String> {
+    let mut table = StrategyTable::new();
     let obj = value.as_object().ok_or("strategy table must be an object")?;
     for (key, row_value) in obj {
         let row_obj = row_value
             .as_object()
             .ok_or("strategy row must be an object")?;
-        let mut row = HashMap::new();
+        // Equivalent rows and columns (e.g. "18" and "A,7", or "10" and "K")
+        // are merged; they must agree where both are given.
+        let row = table.entry(normalize_row_key(key, kind)).or_default();
         for (dealer, action) in row_obj {
             if let Some(action_str) = action.as_str() {
-                row.insert(dealer.clone(), action_str.to_string());
+                let column = normalize_dealer_key(dealer);
+                match row.get(&column) {
+                    Some(existing) if existing != action_str => {
+                        return Err(format!(
+                            "strategy row '{key}' has conflicting actions against dealer {column}: '{existing}' and '{action_str}'"
+                        ));
+                    }
+                    _ => {
+                        row.insert(column, action_str.to_string());
+                    }
+                }
             }
         }
-        table.insert(key.clone(), row);
     }
     Ok(table)
 }
 
-fn value_to_count_table(value: serde_json::Value) -> Result<StrategyCountTable, String> {
+fn value_to_count_table(value: serde_json::Value, kind: TableKind) -> Result<StrategyCountTable, String> {
     if value.is_null() {
         return Ok(HashMap::new());
     }
     let mut table = HashMap::new();
     let obj = value.as_object().ok_or("count table must be an object")?;
     for (count, inner) in obj {
-        table.insert(count.clone(), value_to_table(inner.clone())?);
+        table.insert(count.clone(), value_to_table(inner.clone(), kind)?);
     }
     Ok(table)
 }
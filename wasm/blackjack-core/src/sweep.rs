@@ -0,0 +1,459 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    betting::{self, RampStep},
+    game::DealerRule,
+    sim::{self, CountingInput, OutputDetail, SimulationInput, SimulationResult},
+    stats::{DepthBucket, TableSpeedInput},
+    strategy::Strategy,
+};
+
+/// Splits a sweep across workers: this call only runs combinations whose
+/// index modulo `count` equals `index`.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+pub struct Shard {
+    pub index: u32,
+    pub count: u32,
+}
+
+impl Shard {
+    fn includes(shard: Option<Shard>, combination: usize) -> bool {
+        match shard {
+            Some(s) if s.count > 1 => combination as u32 % s.count == s.index,
+            _ => true,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SweepMetrics {
+    pub total_games: u32,
+    pub expected_value: f64,
+    pub return_rate: f64,
+    pub win_rate: f64,
+}
+
+impl SweepMetrics {
+    fn from_result(result: &SimulationResult) -> Self {
+        SweepMetrics {
+            total_games: result.total_games,
+            expected_value: result.expected_value,
+            return_rate: result.return_rate,
+            win_rate: result.win_rate,
+        }
+    }
+}
+
+/// Runs one sweep point with the heavy optional outputs switched off.
+fn run_point(strategy: &Strategy, input: &SimulationInput) -> Result<SimulationResult, String> {
+    let mut input = input.clone();
+    input.output_detail = OutputDetail::Summary;
+    input.chart_overlay = false;
+    input.bet_recommendation = None;
+    input.bankroll_evaluation = None;
+    sim::simulate(strategy, &input, |_, _| {})
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RuleSweepInput {
+    pub base: SimulationInput,
+    #[serde(default)]
+    pub num_decks: Vec<u8>,
+    #[serde(default)]
+    pub dealer_hits_soft_17: Vec<bool>,
+    #[serde(default)]
+    pub double_after_split: Vec<bool>,
+    #[serde(default)]
+    pub penetration: Vec<f64>,
+    #[serde(default)]
+    pub shard: Option<Shard>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleSweepRow {
+    pub combination: u32,
+    pub num_decks: u8,
+    pub dealer_hits_soft_17: bool,
+    pub double_after_split: bool,
+    pub penetration: f64,
+    #[serde(flatten)]
+    pub metrics: SweepMetrics,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleSweepResult {
+    pub total_combinations: u32,
+    pub rows: Vec<RuleSweepRow>,
+}
+
+fn or_base<T: Clone>(values: &[T], base: T) -> Vec<T> {
+    if values.is_empty() {
+        vec![base]
+    } else {
+        values.to_vec()
+    }
+}
+
+/// Runs the cartesian product of the listed rule values. Every combination
+/// uses the base seed so rows are directly comparable.
+pub fn run_rule_sweep(input: RuleSweepInput) -> Result<RuleSweepResult, String> {
+    let strategy = Strategy::from_input(input.base.strategy.clone())?;
+    let base_rules = &input.base.rules;
+    let decks = or_base(&input.num_decks, input.base.num_decks);
+    let h17 = or_base(&input.dealer_hits_soft_17, base_rules.dealer_rule.hits_soft_17());
+    let das = or_base(&input.double_after_split, base_rules.double_after_split.unwrap_or(true));
+    let penetration = or_base(&input.penetration, base_rules.penetration_threshold.unwrap_or(75.0));
+
+    let mut rows = Vec::new();
+    let mut combination = 0usize;
+    for &num_decks in &decks {
+        for &hits_soft_17 in &h17 {
+            for &double_after_split in &das {
+                for &pen in &penetration {
+                    let index = combination;
+                    combination += 1;
+                    if !Shard::includes(input.shard, index) {
+                        continue;
+                    }
+                    let mut point = input.base.clone();
+                    point.num_decks = num_decks;
+                    if !input.dealer_hits_soft_17.is_empty() {
+                        point.rules.dealer_rule = if hits_soft_17 { DealerRule::H17 } else { DealerRule::S17 };
+                    }
+                    point.rules.double_after_split = Some(double_after_split);
+                    point.rules.penetration_threshold = Some(pen);
+                    let result = run_point(&strategy, &point)?;
+                    rows.push(RuleSweepRow {
+                        combination: index as u32,
+                        num_decks,
+                        dealer_hits_soft_17: hits_soft_17,
+                        double_after_split,
+                        penetration: pen,
+                        metrics: SweepMetrics::from_result(&result),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(RuleSweepResult {
+        total_combinations: combination as u32,
+        rows,
+    })
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CountingSweepInput {
+    /// Rules, strategy and `bet_ramp` shared by every system.
+    pub base: SimulationInput,
+    /// Systems to compare; `enabled` is implied.
+    pub systems: Vec<CountingInput>,
+    #[serde(default)]
+    pub shard: Option<Shard>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CountingSweepRow {
+    pub system: String,
+    #[serde(flatten)]
+    pub metrics: SweepMetrics,
+    /// EV per round minus the flat-bet basic-strategy baseline.
+    pub ev_gain: f64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CountingSweepResult {
+    pub baseline: SweepMetrics,
+    pub rows: Vec<CountingSweepRow>,
+}
+
+/// Runs each counting system with the base bet ramp and compares it with a
+/// flat-bet, count-free run of the same strategy on the same seed.
+pub fn run_counting_sweep(input: CountingSweepInput) -> Result<CountingSweepResult, String> {
+    let strategy = Strategy::from_input(input.base.strategy.clone())?;
+
+    let mut baseline_input = input.base.clone();
+    baseline_input.counting = None;
+    baseline_input.bet_ramp = None;
+    let baseline = SweepMetrics::from_result(&run_point(&strategy, &baseline_input)?);
+
+    let mut rows = Vec::new();
+    for (index, system) in input.systems.iter().enumerate() {
+        if !Shard::includes(input.shard, index) {
+            continue;
+        }
+        let mut point = input.base.clone();
+        let mut counting = system.clone();
+        counting.enabled = true;
+        point.counting = Some(counting);
+        let metrics = SweepMetrics::from_result(&run_point(&strategy, &point)?);
+        rows.push(CountingSweepRow {
+            system: system.system.clone().unwrap_or_else(|| "Hi-Lo".to_string()),
+            ev_gain: metrics.expected_value - baseline.expected_value,
+            metrics,
+        });
+    }
+
+    Ok(CountingSweepResult { baseline, rows })
+}
+
+fn default_hands_per_hour() -> f64 {
+    100.0
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RampCandidate {
+    pub name: String,
+    /// Zero-unit rungs sit out (wong out of) those counts.
+    pub ramp: Vec<RampStep>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BetSpreadSweepInput {
+    /// Configuration shared by every candidate; counting must be enabled.
+    pub base: SimulationInput,
+    pub ramps: Vec<RampCandidate>,
+    /// Bankroll used for the risk-of-ruin column.
+    pub bankroll: f64,
+    /// Rounds dealt per hour, including rounds sat out.
+    #[serde(default = "default_hands_per_hour")]
+    pub hands_per_hour: f64,
+    /// Derives the pace from seats in play instead of `hands_per_hour`. Rounds
+    /// sat out are back-counted from behind the table, so they are dealt at the
+    /// other players' pace and the player's spots only slow the rounds they bet.
+    #[serde(default)]
+    pub table_speed: Option<TableSpeedInput>,
+    #[serde(default)]
+    pub shard: Option<Shard>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BetSpreadRow {
+    pub name: String,
+    #[serde(flatten)]
+    pub metrics: SweepMetrics,
+    pub rounds_played: u32,
+    pub ev_per_round: f64,
+    pub std_dev_per_round: f64,
+    /// Rounds bet per hour at the modelled pace.
+    pub rounds_played_per_hour: f64,
+    pub ev_per_hour: f64,
+    pub std_dev_per_hour: f64,
+    /// Rounds needed for EV to equal one standard deviation; null when the
+    /// spread has no edge and never gets there.
+    pub n0: Option<f64>,
+    pub risk_of_ruin: f64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BetSpreadSweepResult {
+    pub rows: Vec<BetSpreadRow>,
+}
+
+/// Runs each candidate ramp on the same seed and reports hourly win rate,
+/// hourly fluctuation, N0 and fixed-bankroll risk of ruin.
+pub fn run_bet_spread_sweep(input: BetSpreadSweepInput) -> Result<BetSpreadSweepResult, String> {
+    if let Some(speed) = &input.table_speed {
+        speed.validate()?;
+    }
+    let strategy = Strategy::from_input(input.base.strategy.clone())?;
+    let mut rows = Vec::new();
+    for (index, candidate) in input.ramps.iter().enumerate() {
+        if !Shard::includes(input.shard, index) {
+            continue;
+        }
+        let mut point = input.base.clone();
+        point.bet_ramp = Some(candidate.ramp.clone());
+        let result = run_point(&strategy, &point)?;
+        let pace = match input.table_speed {
+            Some(speed) => Pace::Seats(speed, point.spots.unwrap_or(1).max(1) as u32),
+            None => Pace::Flat(input.hands_per_hour),
+        };
+        rows.push(hourly_row(candidate.name.clone(), &result, input.bankroll, pace));
+    }
+    Ok(BetSpreadSweepResult { rows })
+}
+
+/// How fast rounds are dealt for the hourly figures.
+#[derive(Clone, Copy)]
+enum Pace {
+    /// Every dealt round takes the same time.
+    Flat(f64),
+    /// Table speed plus the number of spots the player takes when betting.
+    Seats(TableSpeedInput, u32),
+}
+
+impl Pace {
+    /// Rounds bet per hour, given how many rounds were bet and sat out.
+    fn played_per_hour(self, rounds_played: u32, rounds_observed: u32) -> f64 {
+        match self {
+            Pace::Flat(hands_per_hour) => {
+                let dealt = (rounds_observed + rounds_played).max(1) as f64;
+                hands_per_hour * rounds_played as f64 / dealt
+            }
+            Pace::Seats(speed, spots) => {
+                let hours = speed.hours(spots, rounds_played, rounds_observed);
+                if hours > 0.0 {
+                    rounds_played as f64 / hours
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+}
+
+fn hourly_row(name: String, result: &SimulationResult, bankroll: f64, pace: Pace) -> BetSpreadRow {
+    let rounds_played = result.rounds_played;
+    let ev_per_round = if rounds_played > 0 {
+        result.total_winnings / rounds_played as f64
+    } else {
+        0.0
+    };
+    let sd = result.std_dev_per_round;
+    let variance = sd * sd;
+    let played_per_hour = pace.played_per_hour(rounds_played, result.rounds_observed);
+    BetSpreadRow {
+        name,
+        metrics: SweepMetrics::from_result(result),
+        rounds_played,
+        ev_per_round,
+        std_dev_per_round: sd,
+        rounds_played_per_hour: played_per_hour,
+        ev_per_hour: ev_per_round * played_per_hour,
+        std_dev_per_hour: sd * played_per_hour.sqrt(),
+        n0: (ev_per_round > f64::EPSILON).then(|| variance / (ev_per_round * ev_per_round)),
+        risk_of_ruin: betting::risk_of_ruin(bankroll, ev_per_round, variance),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PenetrationSweepInput {
+    pub base: SimulationInput,
+    /// Penetration levels in percent; 60% to 90% in 5% steps when empty.
+    #[serde(default)]
+    pub levels: Vec<f64>,
+    #[serde(default)]
+    pub shard: Option<Shard>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PenetrationSweepRow {
+    pub penetration: f64,
+    #[serde(flatten)]
+    pub metrics: SweepMetrics,
+    pub advantage_by_depth: Vec<DepthBucket>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PenetrationSweepResult {
+    pub rows: Vec<PenetrationSweepRow>,
+}
+
+/// Runs the base game at each penetration level on the same seed.
+pub fn run_penetration_sweep(input: PenetrationSweepInput) -> Result<PenetrationSweepResult, String> {
+    let strategy = Strategy::from_input(input.base.strategy.clone())?;
+    let levels = if input.levels.is_empty() {
+        (0..=6).map(|step| 60.0 + step as f64 * 5.0).collect()
+    } else {
+        input.levels.clone()
+    };
+
+    let mut rows = Vec::new();
+    for (index, &penetration) in levels.iter().enumerate() {
+        if !Shard::includes(input.shard, index) {
+            continue;
+        }
+        let mut point = input.base.clone();
+        point.rules.penetration_threshold = Some(penetration);
+        let result = run_point(&strategy, &point)?;
+        let metrics = SweepMetrics::from_result(&result);
+        rows.push(PenetrationSweepRow {
+            penetration,
+            metrics,
+            advantage_by_depth: result.depth_stats.buckets,
+        });
+    }
+    Ok(PenetrationSweepResult { rows })
+}
+
+fn default_reference_decks() -> u8 {
+    6
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeckCountSweepInput {
+    /// Rules, strategy and counting shared by every deck count.
+    pub base: SimulationInput,
+    /// Deck counts to compare; 1, 2, 4, 6 and 8 when empty.
+    #[serde(default)]
+    pub num_decks: Vec<u8>,
+    /// Deck count the `ev_difference` column is measured against.
+    #[serde(default = "default_reference_decks")]
+    pub reference_decks: u8,
+    #[serde(default)]
+    pub shard: Option<Shard>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeckCountSweepRow {
+    pub num_decks: u8,
+    #[serde(flatten)]
+    pub metrics: SweepMetrics,
+    /// Return rate in percent minus the reference deck count's return rate.
+    pub ev_difference: f64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeckCountSweepResult {
+    pub reference_decks: u8,
+    pub reference: SweepMetrics,
+    pub rows: Vec<DeckCountSweepRow>,
+}
+
+/// Runs the base game at each deck count with every rule held fixed.
+pub fn run_deck_count_sweep(input: DeckCountSweepInput) -> Result<DeckCountSweepResult, String> {
+    let strategy = Strategy::from_input(input.base.strategy.clone())?;
+    let decks = if input.num_decks.is_empty() {
+        vec![1, 2, 4, 6, 8]
+    } else {
+        input.num_decks.clone()
+    };
+    let reference_decks = input.reference_decks.max(1);
+
+    let mut reference_input = input.base.clone();
+    reference_input.num_decks = reference_decks;
+    let reference = SweepMetrics::from_result(&run_point(&strategy, &reference_input)?);
+
+    let mut rows = Vec::new();
+    for (index, &num_decks) in decks.iter().enumerate() {
+        if !Shard::includes(input.shard, index) || num_decks == 0 {
+            continue;
+        }
+        let mut point = input.base.clone();
+        point.num_decks = num_decks;
+        let metrics = SweepMetrics::from_result(&run_point(&strategy, &point)?);
+        rows.push(DeckCountSweepRow {
+            num_decks,
+            ev_difference: metrics.return_rate - reference.return_rate,
+            metrics,
+        });
+    }
+
+    Ok(DeckCountSweepResult {
+        reference_decks,
+        reference,
+        rows,
+    })
+}
@@ -0,0 +1,266 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    counter::CardCounter,
+    deck::{Card, RANKS},
+    game::GameRules,
+    sim::{self, CountingInput, RulesInput},
+    strategy::{Action, Strategy, StrategyInput, ACTION_CODES},
+    trainer::prescribe,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct SessionInput {
+    pub num_decks: u8,
+    pub strategy: StrategyInput,
+    pub rules: RulesInput,
+    /// Counting system to keep; without it every decision is checked at a
+    /// true count of zero.
+    #[serde(default)]
+    pub counting: Option<CountingInput>,
+}
+
+/// One hand as it was played at a real table.
+#[derive(Debug, Deserialize)]
+pub struct RecordedHand {
+    /// Every card the player held, opening two first, including split hands.
+    pub player_cards: Vec<String>,
+    /// Dealer's cards, up card first.
+    pub dealer_cards: Vec<String>,
+    /// Other cards seen this round, e.g. other seats, for the count.
+    #[serde(default)]
+    pub other_cards: Vec<String>,
+    pub bet: f64,
+    /// Net result of the hand: winnings, or a negative loss.
+    pub result: f64,
+    /// First decision on the opening two cards ("H", "S", "D", "D3", "D4", "P",
+    /// "R" or "Z");
+    /// omit when there was none to make.
+    #[serde(default)]
+    pub action: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedHandResult {
+    pub hand: u32,
+    /// True count the decision was checked at, before this hand's cards.
+    pub true_count: i32,
+    pub player_label: String,
+    pub dealer_card: String,
+    pub prescribed_action: Action,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<Action>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correct: Option<bool>,
+    /// Running count after this hand's cards.
+    pub running_count: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionMistake {
+    pub hand: u32,
+    pub player_label: String,
+    pub dealer_card: String,
+    pub true_count: i32,
+    pub action: Action,
+    pub prescribed_action: Action,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSummary {
+    pub hands: u32,
+    pub total_bet: f64,
+    pub total_winnings: f64,
+    /// Mean net result per hand, and its spread.
+    pub ev_per_hand: f64,
+    pub variance_per_hand: f64,
+    pub std_dev_per_hand: f64,
+    pub return_rate: f64,
+    pub running_count: f64,
+    pub true_count: f64,
+    /// Cards seen since the last shuffle.
+    pub cards_seen: u32,
+    /// Hands with a recorded decision, and how many of them broke strategy.
+    pub decisions: u32,
+    pub mistakes: u32,
+    pub mistake_rate: f64,
+    pub mistake_log: Vec<SessionMistake>,
+}
+
+/// Log of a real session: keeps the count across hands, checks each decision
+/// against the strategy and accumulates the results.
+pub struct Session {
+    strategy: Strategy,
+    rules: GameRules,
+    num_decks: u8,
+    counter: Option<CardCounter>,
+    cards_seen: u32,
+    hands: u32,
+    total_bet: f64,
+    total_winnings: f64,
+    squared_winnings: f64,
+    decisions: u32,
+    mistakes: Vec<SessionMistake>,
+}
+
+fn parse_cards(ranks: &[String]) -> Result<Vec<Card>, String> {
+    ranks
+        .iter()
+        .map(|rank| {
+            if RANKS.contains(&rank.as_str()) {
+                Ok(Card::new(rank))
+            } else {
+                Err(format!("unknown card rank '{rank}'"))
+            }
+        })
+        .collect()
+}
+
+impl Session {
+    pub fn new(input: SessionInput) -> Result<Self, String> {
+        if input.num_decks == 0 {
+            return Err("session needs at least one deck".to_string());
+        }
+        input.rules.validate()?;
+        Ok(Session {
+            strategy: Strategy::from_input(input.strategy)?,
+            rules: sim::to_game_rules(&input.rules),
+            num_decks: input.num_decks,
+            counter: sim::build_counter(input.counting),
+            cards_seen: 0,
+            hands: 0,
+            total_bet: 0.0,
+            total_winnings: 0.0,
+            squared_winnings: 0.0,
+            decisions: 0,
+            mistakes: Vec::new(),
+        })
+    }
+
+    fn decks_remaining(&self) -> f64 {
+        (self.num_decks as f64 * 52.0 - self.cards_seen as f64) / 52.0
+    }
+
+    fn running_count(&self) -> f64 {
+        self.counter.as_ref().map_or(0.0, CardCounter::running_count)
+    }
+
+    fn true_count(&self) -> f64 {
+        self.counter
+            .as_ref()
+            .map_or(0.0, |counter| counter.true_count(self.decks_remaining(), self.num_decks))
+    }
+
+    fn see(&mut self, cards: &[Card]) {
+        self.cards_seen += cards.len() as u32;
+        if let Some(counter) = &mut self.counter {
+            cards.iter().for_each(|card| counter.update(card));
+        }
+    }
+
+    /// Counts cards seen outside a recorded hand.
+    pub fn record_cards(&mut self, ranks: &[String]) -> Result<(), String> {
+        let cards = parse_cards(ranks)?;
+        self.see(&cards);
+        Ok(())
+    }
+
+    pub fn record_hand(&mut self, hand: RecordedHand) -> Result<RecordedHandResult, String> {
+        if hand.player_cards.len() < 2 {
+            return Err("player needs at least two cards".to_string());
+        }
+        if hand.dealer_cards.is_empty() {
+            return Err("dealer needs an up card".to_string());
+        }
+        if !hand.bet.is_finite() || hand.bet < 0.0 || !hand.result.is_finite() {
+            return Err("bet and result must be finite, and the bet not negative".to_string());
+        }
+        if let Some(code) = hand.action.as_deref().filter(|code| !ACTION_CODES.contains(code)) {
+            return Err(format!("unknown action '{code}', expected H, S, D, D3, D4, P, R or Z"));
+        }
+        let player = parse_cards(&hand.player_cards)?;
+        let dealer = parse_cards(&hand.dealer_cards)?;
+        let others = parse_cards(&hand.other_cards)?;
+
+        let true_count = self
+            .counter
+            .as_ref()
+            .map_or(0, |counter| counter.count_range(self.decks_remaining(), self.num_decks));
+        let prescription = prescribe(&self.strategy, &self.rules, &player[..2], &dealer[0], true_count, false);
+        let action = hand.action.as_deref().map(Action::from_code);
+        if let Some(action) = action {
+            prescription.check_allowed(action)?;
+        }
+
+        self.hands += 1;
+        self.total_bet += hand.bet;
+        self.total_winnings += hand.result;
+        self.squared_winnings += hand.result * hand.result;
+        let correct = action.map(|action| action == prescription.action);
+        if let Some(action) = action {
+            self.decisions += 1;
+            if action != prescription.action {
+                self.mistakes.push(SessionMistake {
+                    hand: self.hands,
+                    player_label: prescription.player_label.clone(),
+                    dealer_card: prescription.dealer_label.clone(),
+                    true_count,
+                    action,
+                    prescribed_action: prescription.action,
+                });
+            }
+        }
+        self.see(&player);
+        self.see(&dealer);
+        self.see(&others);
+
+        Ok(RecordedHandResult {
+            hand: self.hands,
+            true_count,
+            player_label: prescription.player_label,
+            dealer_card: prescription.dealer_label,
+            prescribed_action: prescription.action,
+            action,
+            correct,
+            running_count: self.running_count(),
+        })
+    }
+
+    /// Starts a fresh shoe: the count resets, the session totals carry on.
+    pub fn shuffle(&mut self) {
+        self.cards_seen = 0;
+        if let Some(counter) = &mut self.counter {
+            counter.reset();
+        }
+    }
+
+    pub fn summary(&self) -> SessionSummary {
+        let hands = self.hands as f64;
+        let ev_per_hand = if self.hands > 0 { self.total_winnings / hands } else { 0.0 };
+        let variance_per_hand = if self.hands > 1 {
+            ((self.squared_winnings - hands * ev_per_hand * ev_per_hand) / (hands - 1.0)).max(0.0)
+        } else {
+            0.0
+        };
+        let mistakes = self.mistakes.len() as u32;
+        SessionSummary {
+            hands: self.hands,
+            total_bet: self.total_bet,
+            total_winnings: self.total_winnings,
+            ev_per_hand,
+            variance_per_hand,
+            std_dev_per_hand: variance_per_hand.sqrt(),
+            return_rate: if self.total_bet > 0.0 { self.total_winnings / self.total_bet } else { 0.0 },
+            running_count: self.running_count(),
+            true_count: self.true_count(),
+            cards_seen: self.cards_seen,
+            decisions: self.decisions,
+            mistakes,
+            mistake_rate: if self.decisions > 0 { mistakes as f64 / self.decisions as f64 } else { 0.0 },
+            mistake_log: self.mistakes.clone(),
+        }
+    }
+}
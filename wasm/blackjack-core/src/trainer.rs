@@ -0,0 +1,212 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    deck::{Card, RANKS},
+    game::{BlackjackGame, GameRules},
+    sim::{self, CountingInput, RulesInput, SpotCheckInput},
+    strategy::{Action, Strategy, StrategyInput, ACTION_CODES},
+};
+
+fn default_trainer_iterations() -> u32 {
+    20_000
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TrainerInput {
+    pub num_decks: u8,
+    pub strategy: StrategyInput,
+    pub rules: RulesInput,
+    pub player_cards: Vec<String>,
+    pub dealer_card: String,
+    /// True count used to look up index plays, and to price mistakes when
+    /// counting is set.
+    #[serde(default)]
+    pub true_count: i32,
+    /// Action the user chose ("H", "S", "D", "D3", "D4", "P", "R" or "Z"); omit to only get the answer.
+    #[serde(default)]
+    pub user_action: Option<String>,
+    /// Hand is the result of a split, so doubling follows the DAS rule.
+    #[serde(default)]
+    pub after_split: bool,
+    /// Spot-check rounds per action when pricing a mistake.
+    #[serde(default = "default_trainer_iterations")]
+    pub iterations: u32,
+    #[serde(default)]
+    pub seed: u64,
+    #[serde(default)]
+    pub counting: Option<CountingInput>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrainerResult {
+    pub player_label: String,
+    pub dealer_card: String,
+    pub prescribed_action: Action,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_action: Option<Action>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correct: Option<bool>,
+    /// Spot-check EV per unit bet of each action, only measured for mistakes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prescribed_ev: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_ev: Option<f64>,
+    /// EV given up by the user's action, in units of the bet; 0 when correct.
+    pub ev_cost: f64,
+}
+
+/// Checks a user's decision against the strategy. Mistakes are priced by
+/// spot-checking both actions on the same seeds, dealt at the given true count
+/// when counting is set (off a fresh shoe otherwise), with basic-strategy play
+/// after the first decision.
+pub fn check_action(input: TrainerInput) -> Result<TrainerResult, String> {
+    if input.player_cards.len() < 2 {
+        return Err("player needs at least two cards".to_string());
+    }
+    if let Some(rank) = input
+        .player_cards
+        .iter()
+        .chain(std::iter::once(&input.dealer_card))
+        .find(|rank| !RANKS.contains(&rank.as_str()))
+    {
+        return Err(format!("unknown card rank '{rank}'"));
+    }
+    if let Some(code) = input.user_action.as_deref().filter(|code| !ACTION_CODES.contains(code)) {
+        return Err(format!("unknown action '{code}', expected H, S, D, D3, D4, P, R or Z"));
+    }
+    input.rules.validate()?;
+    let strategy = Strategy::from_input(input.strategy.clone())?;
+    let rules = sim::to_game_rules(&input.rules);
+
+    let cards: Vec<Card> = input.player_cards.iter().map(|rank| Card::new(rank)).collect();
+    let dealer = Card::new(&input.dealer_card);
+    let prescription = prescribe(&strategy, &rules, &cards, &dealer, input.true_count, input.after_split);
+    let prescribed_action = prescription.action;
+    let user_action = input.user_action.as_deref().map(Action::from_code);
+    if let Some(action) = user_action {
+        prescription.check_allowed(action)?;
+    }
+
+    let mut result = TrainerResult {
+        player_label: prescription.player_label,
+        dealer_card: prescription.dealer_label,
+        prescribed_action,
+        user_action,
+        correct: user_action.map(|action| action == prescribed_action),
+        prescribed_ev: None,
+        user_ev: None,
+        ev_cost: 0.0,
+    };
+    if let Some(user) = user_action.filter(|&action| action != prescribed_action) {
+        let prescribed_ev = forced_ev(&strategy, &input, prescribed_action)?;
+        let user_ev = forced_ev(&strategy, &input, user)?;
+        result.prescribed_ev = Some(prescribed_ev);
+        result.user_ev = Some(user_ev);
+        result.ev_cost = prescribed_ev - user_ev;
+    }
+    Ok(result)
+}
+
+/// The strategy's play for a hand, with the options the rules leave open.
+pub struct Prescription {
+    pub player_label: String,
+    pub dealer_label: String,
+    pub action: Action,
+    pub can_double: bool,
+    /// Largest double multiplier open on the hand.
+    pub max_double_multiplier: u8,
+    pub can_split: bool,
+    pub can_surrender: bool,
+    pub can_zap: bool,
+}
+
+impl Prescription {
+    /// Errors when `action` is not available on the hand.
+    pub fn check_allowed(&self, action: Action) -> Result<(), String> {
+        match action {
+            action if action.double_multiplier().is_some() && !self.can_double => {
+                Err("double is not allowed on this hand".to_string())
+            }
+            action if action.double_multiplier().is_some_and(|multiplier| multiplier > self.max_double_multiplier) => {
+                Err(format!("doubling for more than {}x is not allowed", self.max_double_multiplier))
+            }
+            Action::Split if !self.can_split => Err("only pairs can be split".to_string()),
+            Action::Surrender if !self.can_surrender => Err("surrender is not allowed on this hand".to_string()),
+            Action::Zap if !self.can_zap => Err("only a hard 15-17 can be zapped, under Zappit".to_string()),
+            _ => Ok(()),
+        }
+    }
+}
+
+pub fn prescribe(
+    strategy: &Strategy,
+    rules: &GameRules,
+    cards: &[Card],
+    dealer: &Card,
+    true_count: i32,
+    after_split: bool,
+) -> Prescription {
+    let two_cards = cards.len() == 2;
+    let pair_label = BlackjackGame::strategy_pair_label(cards).filter(|_| rules.is_pair(cards));
+    let can_split = pair_label.is_some();
+    let can_double = (two_cards || rules.double_after_hit)
+        && (!after_split || rules.doubles_after_split(&cards[0]))
+        && rules.double_on.allows(cards);
+    let can_surrender = two_cards && !after_split && rules.surrender_allowed();
+    let dealer_label = if dealer.value == 11 {
+        "A".to_string()
+    } else {
+        dealer.value.to_string()
+    };
+    let player_label = pair_label.unwrap_or_else(|| hand_label(cards));
+    let can_zap = !after_split && rules.zap_allowed(cards);
+    let action = strategy.decide_action(&player_label, &dealer_label, can_double, can_split, can_surrender, true_count);
+    let action = if action == Action::Zap && !can_zap {
+        Action::Hit
+    } else {
+        rules.limit_double(action, cards.len())
+    };
+    Prescription {
+        player_label,
+        dealer_label,
+        action,
+        can_double,
+        max_double_multiplier: rules.limit_double(Action::Quadruple, cards.len()).double_multiplier().unwrap_or(2),
+        can_split,
+        can_surrender,
+        can_zap,
+    }
+}
+
+fn forced_ev(strategy: &Strategy, input: &TrainerInput, action: Action) -> Result<f64, String> {
+    let spot = SpotCheckInput {
+        num_decks: input.num_decks,
+        iterations: input.iterations.max(1),
+        seed: input.seed,
+        strategy: StrategyInput::default(),
+        rules: input.rules.clone(),
+        bet_size: 1.0,
+        player_cards: input.player_cards.clone(),
+        dealer_card: input.dealer_card.clone(),
+        forced_action: action.as_code().to_string(),
+        counting: input.counting.clone(),
+        true_count: Some(input.true_count as f64),
+    };
+    Ok(sim::spot_check(strategy, &spot)?.expected_value)
+}
+
+/// Hard or soft chart label ("16", "S18") for a non-pair hand.
+pub fn hand_label(cards: &[Card]) -> String {
+    let mut total: u32 = cards.iter().map(|c| c.value as u32).sum();
+    let mut soft_aces = cards.iter().filter(|c| c.rank == "A").count();
+    while total > 21 && soft_aces > 0 {
+        total -= 10;
+        soft_aces -= 1;
+    }
+    if soft_aces > 0 {
+        format!("S{total}")
+    } else {
+        total.to_string()
+    }
+}
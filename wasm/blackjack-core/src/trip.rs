@@ -0,0 +1,155 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    sim::{IterationUnit, OutputDetail, SimulationInput, SimulationRun},
+    stats::{quantile, SessionModel, TableSpeedInput},
+    strategy::Strategy,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct TripInput {
+    /// Game, strategy and betting for every trip; trip `n` is dealt from
+    /// `seed + n` and its iterations are replaced by the trip length.
+    pub simulation: SimulationInput,
+    pub trips: u32,
+    pub sessions_per_trip: u32,
+    /// Dealing speed; rounds sat out go at the other players' pace.
+    #[serde(default)]
+    pub table_speed: TableSpeedInput,
+    pub hours_per_session: f64,
+    /// Bankroll each trip starts with; a trip ends early once it cannot
+    /// cover a bet.
+    pub bankroll: f64,
+}
+
+/// Spread of trip bankrolls at one point of the trip.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BankrollQuantiles {
+    /// Sessions played so far; zero is the start of the trip.
+    pub session: u32,
+    pub mean: f64,
+    pub p5: f64,
+    pub p25: f64,
+    pub median: f64,
+    pub p75: f64,
+    pub p95: f64,
+}
+
+impl BankrollQuantiles {
+    fn new(session: u32, bankrolls: &mut [f64]) -> Self {
+        bankrolls.sort_by(f64::total_cmp);
+        BankrollQuantiles {
+            session,
+            mean: bankrolls.iter().sum::<f64>() / bankrolls.len() as f64,
+            p5: quantile(bankrolls, 0.05),
+            p25: quantile(bankrolls, 0.25),
+            median: quantile(bankrolls, 0.5),
+            p75: quantile(bankrolls, 0.75),
+            p95: quantile(bankrolls, 0.95),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TripResult {
+    pub trips: u32,
+    pub sessions_per_trip: u32,
+    /// Rounds dealt, sat out or not, in an average session of the trips
+    /// that lasted.
+    pub rounds_per_session: f64,
+    pub starting_bankroll: f64,
+    /// Net result of a trip.
+    pub mean_result: f64,
+    pub std_dev_result: f64,
+    pub losing_trips: u32,
+    pub losing_trip_probability: f64,
+    /// Trips that went broke before the end.
+    pub ruined_trips: u32,
+    pub risk_of_ruin: f64,
+    /// Net trip results, from the worst trip to the best.
+    pub worst_result: f64,
+    pub p5_result: f64,
+    pub median_result: f64,
+    pub p95_result: f64,
+    pub best_result: f64,
+    /// Bankroll spread at the start and after every session.
+    pub bankroll_over_time: Vec<BankrollQuantiles>,
+}
+
+/// Plays `trips` independent trips of `sessions_per_trip` sessions each
+/// against the same starting bankroll.
+pub fn run_trip(input: TripInput) -> Result<TripResult, String> {
+    if input.trips == 0 || input.sessions_per_trip == 0 {
+        return Err("a trip simulation needs at least one trip and one session".to_string());
+    }
+    if !input.bankroll.is_finite() || input.bankroll <= 0.0 {
+        return Err("trip bankroll must be positive".to_string());
+    }
+    let model = SessionModel {
+        table_speed: input.table_speed,
+        hours_per_session: input.hours_per_session,
+        sessions: Some(input.sessions_per_trip),
+    };
+    model.validate()?;
+    let strategy = Strategy::from_input(input.simulation.strategy.clone())?;
+
+    let mut bankrolls = vec![Vec::with_capacity(input.trips as usize); input.sessions_per_trip as usize + 1];
+    let mut results = Vec::with_capacity(input.trips as usize);
+    let mut ruined_trips = 0;
+    let mut rounds_dealt = 0u64;
+    let mut sessions_played = 0u64;
+    for trip in 0..input.trips {
+        let mut config = input.simulation.clone();
+        config.seed = input.simulation.seed.wrapping_add(trip as u64);
+        config.iteration_unit = IterationUnit::Rounds;
+        config.output_detail = OutputDetail::Summary;
+        config.session_model = Some(model.clone());
+        config.starting_bankroll = Some(input.bankroll);
+        config.stop_on_ruin = true;
+        let mut run = SimulationRun::new(strategy.clone(), config)?;
+        bankrolls[0].push(input.bankroll);
+        for (session, after_session) in bankrolls[1..].iter_mut().enumerate() {
+            while !run.is_done() && run.sessions_completed() <= session as u32 {
+                run.play_round();
+            }
+            after_session.push(input.bankroll + run.net_winnings());
+        }
+        if run.ruined() {
+            ruined_trips += 1;
+        } else {
+            rounds_dealt += run.completed() as u64;
+            sessions_played += run.sessions_completed() as u64;
+        }
+        results.push(run.net_winnings());
+    }
+
+    let trips = input.trips as f64;
+    let mean_result = results.iter().sum::<f64>() / trips;
+    let variance = results.iter().map(|r| (r - mean_result).powi(2)).sum::<f64>() / trips;
+    let losing_trips = results.iter().filter(|&&r| r < 0.0).count() as u32;
+    results.sort_by(f64::total_cmp);
+    Ok(TripResult {
+        trips: input.trips,
+        sessions_per_trip: input.sessions_per_trip,
+        rounds_per_session: rounds_dealt as f64 / sessions_played.max(1) as f64,
+        starting_bankroll: input.bankroll,
+        mean_result,
+        std_dev_result: variance.sqrt(),
+        losing_trips,
+        losing_trip_probability: losing_trips as f64 / trips,
+        ruined_trips,
+        risk_of_ruin: ruined_trips as f64 / trips,
+        worst_result: results[0],
+        p5_result: quantile(&results, 0.05),
+        median_result: quantile(&results, 0.5),
+        p95_result: quantile(&results, 0.95),
+        best_result: results[results.len() - 1],
+        bankroll_over_time: bankrolls
+            .iter_mut()
+            .enumerate()
+            .map(|(session, bankrolls)| BankrollQuantiles::new(session as u32, bankrolls))
+            .collect(),
+    })
+}